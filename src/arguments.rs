@@ -0,0 +1,222 @@
+//! Task arguments on the command line, after `--`.
+//!
+//! Values are given by position, in the order the arguments are declared, or by name
+//! with `--message fix`, `--message=fix` or a short alias `-m fix`. A bare `--` ends the
+//! flags: everything after it is positional.
+
+use std::collections::HashMap;
+
+use crate::config::{ArgumentType, TaskArgument};
+use crate::errors::{BodoError, Result};
+use crate::suggest::{did_you_mean, suggest};
+
+/// Whether the task's arguments ask for its help: `bodo <task> -- --help`.
+pub fn wants_help(args: &[String]) -> bool {
+    args.iter()
+        .take_while(|arg| *arg != "--")
+        .any(|arg| arg == "--help" || arg == "-h")
+}
+
+/// Match `args` to `definitions` and return the environment variables they set, with
+/// defaults filled in. Unknown flags, values that do not fit an argument's type, extra
+/// values and missing required arguments are errors. A task without definitions takes
+/// anything, as before tasks could declare arguments.
+pub fn parse_task_arguments(
+    definitions: &[TaskArgument],
+    args: &[String],
+) -> Result<HashMap<String, String>> {
+    if definitions.is_empty() {
+        return Ok(HashMap::new());
+    }
+    let mut named: HashMap<usize, Vec<String>> = HashMap::new();
+    let mut positional = Vec::new();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        if arg == "--" {
+            positional.extend(args.by_ref().cloned());
+            break;
+        }
+        let (index, inline_value) = if let Some(flag) = arg.strip_prefix("--") {
+            let (flag, value) = match flag.split_once('=') {
+                Some((flag, value)) => (flag, Some(value.to_string())),
+                None => (flag, None),
+            };
+            let flag = flag.to_lowercase().replace('_', "-");
+            let index = definitions
+                .iter()
+                .position(|definition| definition.flag() == flag)
+                .ok_or_else(|| {
+                    let flags: Vec<String> = definitions.iter().map(TaskArgument::flag).collect();
+                    let suggestions: Vec<String> = suggest(&flag, flags.iter().map(String::as_str))
+                        .into_iter()
+                        .map(|flag| format!("--{}", flag))
+                        .collect();
+                    BodoError::PluginError(format!(
+                        "Unknown argument --{}{}",
+                        flag,
+                        did_you_mean(&suggestions)
+                    ))
+                })?;
+            (index, value)
+        } else if let Some(short) = short_flag(arg) {
+            let index = definitions
+                .iter()
+                .position(|definition| definition.short == Some(short))
+                .ok_or_else(|| BodoError::PluginError(format!("Unknown argument -{}", short)))?;
+            (index, None)
+        } else {
+            positional.push(arg.clone());
+            continue;
+        };
+
+        let definition = &definitions[index];
+        let value = match (inline_value, definition.arg_type) {
+            (Some(value), _) => value,
+            (None, ArgumentType::Bool) => "true".to_string(),
+            (None, _) => args
+                .next()
+                .cloned()
+                .ok_or_else(|| BodoError::PluginError(format!("Argument {} needs a value", arg)))?,
+        };
+        let values = named.entry(index).or_default();
+        if !values.is_empty() && !definition.variadic {
+            return Err(BodoError::PluginError(format!(
+                "Argument --{} is given more than once",
+                definition.flag()
+            )));
+        }
+        values.push(value);
+    }
+
+    // Positional values fill the arguments that were not named, in order.
+    let mut positional = positional.into_iter();
+    let mut env_vars = HashMap::new();
+    for (index, definition) in definitions.iter().enumerate() {
+        let values = match named.remove(&index) {
+            Some(values) => values,
+            None if definition.variadic => positional.by_ref().collect(),
+            None => positional.next().into_iter().collect(),
+        };
+        let value = if values.is_empty() {
+            match &definition.default {
+                Some(default) => check(definition, default)?,
+                None if definition.required => {
+                    return Err(BodoError::PluginError(format!(
+                        "Missing required argument: {}",
+                        definition.name
+                    )))
+                }
+                None => continue,
+            }
+        } else {
+            values
+                .iter()
+                .map(|value| check(definition, value))
+                .collect::<Result<Vec<_>>>()?
+                .join(" ")
+        };
+        env_vars.insert(definition.name.clone(), value);
+    }
+    let extra: Vec<String> = positional.collect();
+    if !extra.is_empty() {
+        return Err(BodoError::PluginError(format!(
+            "Unexpected argument{} {}: the task takes {} argument{}",
+            if extra.len() == 1 { "" } else { "s" },
+            extra
+                .iter()
+                .map(|arg| format!("'{}'", arg))
+                .collect::<Vec<_>>()
+                .join(", "),
+            definitions.len(),
+            if definitions.len() == 1 { "" } else { "s" }
+        )));
+    }
+    Ok(env_vars)
+}
+
+/// The help for `bodo <task> -- --help`, built from the task's argument definitions.
+pub fn task_help(task: &str, description: Option<&str>, definitions: &[TaskArgument]) -> String {
+    let mut help = String::new();
+    if let Some(description) = description {
+        help.push_str(description);
+        help.push_str("\n\n");
+    }
+    help.push_str(&format!("Usage: bodo {}", task));
+    if !definitions.is_empty() {
+        help.push_str(" --");
+        for definition in definitions {
+            let name = match (
+                definition.required && definition.default.is_none(),
+                definition.variadic,
+            ) {
+                (true, false) => format!(" <{}>", definition.name),
+                (true, true) => format!(" <{}>...", definition.name),
+                (false, false) => format!(" [{}]", definition.name),
+                (false, true) => format!(" [{}]...", definition.name),
+            };
+            help.push_str(&name);
+        }
+    }
+    help.push('\n');
+    if definitions.is_empty() {
+        return help;
+    }
+
+    help.push_str("\nArguments:\n");
+    let flags: Vec<String> = definitions.iter().map(flag_usage).collect();
+    let width = flags.iter().map(String::len).max().unwrap_or(0);
+    for (definition, flags) in definitions.iter().zip(&flags) {
+        let mut line = format!("  {:<width$}", flags, width = width);
+        let mut notes = Vec::new();
+        if let Some(description) = &definition.description {
+            notes.push(description.clone());
+        }
+        if definition.required && definition.default.is_none() {
+            notes.push("[required]".to_string());
+        }
+        if let Some(default) = &definition.default {
+            notes.push(format!("[default: {}]", default));
+        }
+        notes.push(format!("[env: {}]", definition.name));
+        line.push_str("  ");
+        line.push_str(&notes.join(" "));
+        help.push_str(line.trim_end());
+        help.push('\n');
+    }
+    help
+}
+
+/// `-m, --message <string>`, `    --files <path>...`, `    --verbose`.
+fn flag_usage(definition: &TaskArgument) -> String {
+    let short = match definition.short {
+        Some(short) => format!("-{}, ", short),
+        None => "    ".to_string(),
+    };
+    let value = match definition.arg_type {
+        ArgumentType::Bool => String::new(),
+        ArgumentType::Enum => format!(" <{}>", definition.choices.join("|")),
+        ArgumentType::String => " <string>".to_string(),
+        ArgumentType::Int => " <int>".to_string(),
+        ArgumentType::Path => " <path>".to_string(),
+    };
+    let repeat = if definition.variadic { "..." } else { "" };
+    format!("{}--{}{}{}", short, definition.flag(), value, repeat)
+}
+
+/// The letter of a short flag such as `-m`. Negative numbers are values, not flags.
+fn short_flag(arg: &str) -> Option<char> {
+    let mut chars = arg.strip_prefix('-')?.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) if !c.is_ascii_digit() => Some(c),
+        _ => None,
+    }
+}
+
+fn check(definition: &TaskArgument, value: &str) -> Result<String> {
+    definition.parse_value(value).map_err(|e| {
+        BodoError::PluginError(format!(
+            "Invalid value for argument {}: {}",
+            definition.name, e
+        ))
+    })
+}
@@ -0,0 +1,239 @@
+//! `bodo check`: every problem in the project's scripts at once, without running anything.
+//!
+//! On top of what loading already rejects, it resolves every task reference, looks for
+//! dependency cycles and checks what is otherwise only noticed at run time: watch globs,
+//! prefix colors and working directories.
+
+use globset::Glob;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use crate::config::{BodoConfig, Dependency};
+use crate::diagnostic::Diagnostic;
+use crate::errors::{BodoError, Result};
+use crate::graph::{EdgeKind, Graph, NodeId, NodeKind, TaskData};
+use crate::process::{parse_color, COLOR_NAMES};
+use crate::script_loader::ScriptLoader;
+use crate::task_resolver::resolve_task_ref;
+
+/// Version of the JSON report. Bump it whenever the shape of [`CheckReport`] changes.
+pub const CHECK_REPORT_VERSION: u32 = 1;
+
+/// How `bodo check` prints its report.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CheckFormat {
+    #[default]
+    Text,
+    Json,
+}
+
+impl FromStr for CheckFormat {
+    type Err = BodoError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "text" => Ok(Self::Text),
+            "json" => Ok(Self::Json),
+            other => Err(BodoError::ValidationError(format!(
+                "unknown check format '{}'; expected one of: text, json",
+                other
+            ))),
+        }
+    }
+}
+
+/// The outcome of checking a project: what was loaded and every problem found, sorted by
+/// file and position.
+#[derive(Debug, Clone, Serialize)]
+pub struct CheckReport {
+    pub version: u32,
+    pub scripts: usize,
+    pub tasks: usize,
+    pub problems: Vec<Diagnostic>,
+}
+
+impl CheckReport {
+    pub fn is_ok(&self) -> bool {
+        self.problems.is_empty()
+    }
+
+    /// A one-line summary, e.g. `2 problems in 3 scripts`.
+    pub fn summary(&self) -> String {
+        let plural =
+            |n: usize, word: &str| format!("{} {}{}", n, word, if n == 1 { "" } else { "s" });
+        if self.is_ok() {
+            format!(
+                "No problems found in {} across {}",
+                plural(self.tasks, "task"),
+                plural(self.scripts, "script")
+            )
+        } else {
+            format!(
+                "{} in {}",
+                plural(self.problems.len(), "problem"),
+                plural(self.scripts, "script")
+            )
+        }
+    }
+
+    /// The problems as diagnostics separated by blank lines, or the report as JSON.
+    pub fn render(&self, format: CheckFormat) -> Result<String> {
+        match format {
+            CheckFormat::Text => Ok(self
+                .problems
+                .iter()
+                .map(|problem| format!("{}\n\n", problem))
+                .collect()),
+            CheckFormat::Json => Ok(format!("{}\n", serde_json::to_string_pretty(self)?)),
+        }
+    }
+}
+
+/// Load every script of `config` and report all problems found.
+pub fn check_project(config: BodoConfig) -> CheckReport {
+    let scripts = script_files(&config).len();
+    let (mut graph, mut problems) = ScriptLoader::check_graph(config);
+
+    let mut tasks: Vec<(&String, NodeId)> = graph
+        .task_registry
+        .iter()
+        .map(|(key, &id)| (key, id))
+        .collect();
+    tasks.sort();
+    for &(_, id) in &tasks {
+        if let NodeKind::Task(task) = &graph.nodes[id as usize].kind {
+            check_task(&graph, id, task, &mut problems);
+        }
+    }
+    // Running `concurrently` tasks as part of a task also depends on them, so their
+    // references count towards cycles even though loading does not add them as edges.
+    let task_count = tasks.len();
+    let concurrent_edges: Vec<(NodeId, NodeId)> = tasks
+        .iter()
+        .flat_map(|&(_, id)| concurrent_task_refs(&graph, id))
+        .collect();
+    for (from, to) in concurrent_edges {
+        let _ = graph.add_edge_of_kind(from, to, EdgeKind::Concurrent);
+    }
+    if let Some(mut cycle) = graph.detect_cycle() {
+        // Start at the first task by name, so the same cycle is always reported the same way.
+        let start = (0..cycle.len())
+            .filter(|&i| matches!(graph.nodes[cycle[i] as usize].kind, NodeKind::Task(_)))
+            .min_by_key(|&i| graph.node_name(cycle[i] as usize))
+            .unwrap_or(0);
+        cycle.rotate_left(start);
+        let mut names: Vec<String> = cycle
+            .iter()
+            .map(|&id| graph.node_name(id as usize))
+            .collect();
+        names.push(names[0].clone());
+        problems.push(ScriptLoader::task_diagnostic(
+            &graph,
+            cycle[0],
+            &[],
+            format!("found cyclical dependency: {}", names.join(" -> ")),
+        ));
+    }
+
+    problems.sort_by(|a, b| (&a.path, a.line, a.column).cmp(&(&b.path, b.line, b.column)));
+    problems.dedup();
+    CheckReport {
+        version: CHECK_REPORT_VERSION,
+        scripts,
+        tasks: task_count,
+        problems,
+    }
+}
+
+/// The tasks `id` runs `concurrently` that resolve, as `(id, task)` pairs.
+fn concurrent_task_refs(graph: &Graph, id: NodeId) -> Vec<(NodeId, NodeId)> {
+    let NodeKind::Task(task) = &graph.nodes[id as usize].kind else {
+        return Vec::new();
+    };
+    task.concurrently
+        .iter()
+        .filter_map(|dep| match dep {
+            Dependency::Task { task: name } => resolve_task_ref(graph, id, name).ok(),
+            Dependency::Command { .. } => None,
+        })
+        .map(|to| (id, to))
+        .collect()
+}
+
+/// What loading does not check because it only matters when the task runs.
+fn check_task(graph: &Graph, id: NodeId, task: &TaskData, problems: &mut Vec<Diagnostic>) {
+    let mut report = |field: &[&str], message: String| {
+        problems.push(ScriptLoader::task_diagnostic(graph, id, field, message));
+    };
+
+    for dep in &task.concurrently {
+        if let Dependency::Task { task: name } = dep {
+            if let Err(err) = resolve_task_ref(graph, id, name) {
+                let message = match err {
+                    BodoError::ValidationError(message) => message,
+                    other => other.to_string(),
+                };
+                report(&["concurrently"], message);
+            }
+        }
+    }
+
+    if let Some(watch) = &task.watch {
+        for (field, patterns) in [
+            ("patterns", &watch.patterns),
+            ("ignore_patterns", &watch.ignore_patterns),
+        ] {
+            for pattern in patterns {
+                if let Err(err) = Glob::new(pattern) {
+                    report(
+                        &["watch", field],
+                        format!("invalid watch pattern '{}': {}", pattern, err.kind()),
+                    );
+                }
+            }
+        }
+    }
+
+    if let Some(color) = &task.concurrently_options.prefix_color {
+        if parse_color(color).is_none() {
+            report(
+                &["concurrently_options", "prefix_color"],
+                format!(
+                    "unknown prefix_color '{}'; expected one of: {}",
+                    color,
+                    COLOR_NAMES.join(", ")
+                ),
+            );
+        }
+    }
+
+    // Directories with variables in them are only known at run time.
+    if let Some(dir) = &task.working_dir {
+        if !dir.contains('$') && !Path::new(dir).is_dir() {
+            report(
+                &["cwd"],
+                format!("working directory '{}' does not exist", dir),
+            );
+        }
+    }
+}
+
+/// The script files `config` loads, as [`ScriptLoader::build_graph`] finds them.
+fn script_files(config: &BodoConfig) -> Vec<PathBuf> {
+    let mut seen = HashSet::new();
+    let root = config
+        .root_script
+        .iter()
+        .map(PathBuf::from)
+        .filter(|path| path.is_file());
+    let discovered = config
+        .scripts_dirs
+        .iter()
+        .flatten()
+        .flat_map(|dir| ScriptLoader::discover_scripts(Path::new(dir)));
+    root.chain(discovered)
+        .filter(|path| seen.insert(path.canonicalize().unwrap_or_else(|_| path.clone())))
+        .collect()
+}
@@ -1,31 +1,73 @@
+use crate::completion::Shell;
 use crate::errors::BodoError;
 use crate::manager::GraphManager;
-use clap::Parser;
+use clap::{CommandFactory, Parser, Subcommand};
+use std::ffi::OsString;
 use std::fmt::Debug;
 
-#[derive(Parser, Debug)]
-#[command(author, version, about, long_about = None)]
+// `bodo <command>`, or the shorthand `bodo [task] [subtask] [-- args]` for `bodo run`.
+// A command name as the first word always means the command; `bodo run <name>` runs a task
+// of that name. Later words are subtasks, so `bodo build check` runs the `check` task of `build`.
+#[derive(Parser, Debug, Clone)]
+#[command(
+    author,
+    version,
+    about,
+    long_about = None,
+    disable_help_flag = true,
+    disable_help_subcommand = true,
+    override_usage = "bodo [OPTIONS] <COMMAND>\n       bodo [OPTIONS] [TASK] [SUBTASK] [-- <ARGS>...]"
+)]
 pub struct Args {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
     /// List all available tasks
     #[arg(short, long)]
     pub list: bool,
 
     /// Watch mode - rerun task on file changes
-    #[arg(short, long)]
+    #[arg(short, long, global = true)]
     pub watch: bool,
 
     /// Auto watch mode - automatically enable watch if specified
-    #[arg(long)]
+    #[arg(long, global = true)]
     pub auto_watch: bool,
 
     /// Enable debug logs
-    #[arg(long)]
+    #[arg(long, global = true)]
     pub debug: bool,
 
     /// Dry-run mode - show what would be executed without running commands
-    #[arg(long)]
+    #[arg(long, global = true)]
     pub dry_run: bool,
 
+    /// With --dry-run, also run each command in a sandbox to report its side effects
+    #[arg(long, requires = "dry_run", global = true)]
+    pub side_effects: bool,
+
+    /// Run every command in a sandbox: writes limited to its working directory, no network
+    #[arg(long, global = true)]
+    pub sandbox: bool,
+
+    /// Interactive mode - pick a task with fuzzy search and fill in its arguments
+    #[cfg_attr(feature = "dialoguer", arg(short, long))]
+    #[cfg_attr(not(feature = "dialoguer"), arg(skip))]
+    pub interactive: bool,
+
+    /// Print the dependency tree of the task instead of running it
+    #[arg(long, global = true)]
+    pub graph: bool,
+
+    /// Print help; with a task, describe the task instead
+    #[arg(short, long)]
+    pub help: bool,
+
+    /// Output format: tree, dot, mermaid or json for `bodo graph`; text, json or yaml for --dry-run;
+    /// text or json for `bodo check`
+    #[arg(long, global = true)]
+    pub format: Option<String>,
+
     /// Task to run (defaults to default_task)
     pub task: Option<String>,
 
@@ -37,13 +79,97 @@ pub struct Args {
     pub args: Vec<String>,
 }
 
-pub fn get_task_name(args: &Args, graph_manager: &GraphManager) -> Result<String, BodoError> {
-    let task_name = if let Some(task) = args.task.clone() {
-        if let Some(subtask) = args.subtask.clone() {
-            format!("{} {}", task, subtask)
-        } else {
-            task
+#[derive(Subcommand, Debug, Clone)]
+pub enum Command {
+    /// Run a task (the default when the first word is not a command)
+    #[command(disable_help_flag = true)]
+    Run {
+        /// Task to run (defaults to default_task)
+        task: Option<String>,
+
+        /// Subtask to run
+        subtask: Option<String>,
+
+        /// Describe the task instead of running it
+        #[arg(short, long)]
+        help: bool,
+
+        /// Additional arguments passed to the task
+        #[arg(last = true)]
+        args: Vec<String>,
+    },
+
+    /// List all available tasks
+    List,
+
+    /// Print the dependency tree of a task, or export the whole graph with --format
+    Graph {
+        task: Option<String>,
+        subtask: Option<String>,
+    },
+
+    /// Describe a task: source, command, arguments, environment and dependencies
+    Describe {
+        task: String,
+        subtask: Option<String>,
+    },
+
+    /// Check all script files for errors without running anything
+    Check,
+
+    /// Create a starter script in scripts/script.yaml
+    Init,
+
+    /// Print the shell completion script for bash, zsh or fish
+    Completions { shell: Shell },
+}
+
+/// `task` and `subtask` as one task name, e.g. `deploy prod`.
+pub fn join_task_name(task: &str, subtask: Option<&str>) -> String {
+    match subtask {
+        Some(subtask) => format!("{} {}", task, subtask),
+        None => task.to_string(),
+    }
+}
+
+impl Args {
+    /// Parse a command line. Only the first word can be a command: in `bodo build check`,
+    /// `check` is the subtask of `build`, not `bodo check`, so it is read as `bodo run`.
+    pub fn parse_command_line<I, T>(args: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<OsString>,
+    {
+        let mut args: Vec<OsString> = args.into_iter().map(Into::into).collect();
+        let command = Self::command();
+        let is_command = |word: &OsString| {
+            command
+                .get_subcommands()
+                .any(|subcommand| word.to_str() == Some(subcommand.get_name()))
+        };
+        let mut words = Vec::new();
+        let mut takes_value = false;
+        for (i, word) in args.iter().enumerate().skip(1) {
+            if word == "--" {
+                break;
+            }
+            if !takes_value && !word.to_string_lossy().starts_with('-') {
+                words.push(i);
+            }
+            takes_value = word == "--format";
         }
+        if let Some((&first, rest)) = words.split_first() {
+            if !is_command(&args[first]) && rest.iter().any(|&i| is_command(&args[i])) {
+                args.insert(first, "run".into());
+            }
+        }
+        Self::parse_from(args)
+    }
+}
+
+pub fn get_task_name(args: &Args, graph_manager: &GraphManager) -> Result<String, BodoError> {
+    let task_name = if let Some(task) = &args.task {
+        join_task_name(task, args.subtask.as_deref())
     } else {
         // Check for default task in the task registry
         if graph_manager.task_exists("default") {
@@ -54,7 +180,7 @@ pub fn get_task_name(args: &Args, graph_manager: &GraphManager) -> Result<String
     };
 
     if !graph_manager.task_exists(&task_name) {
-        return Err(BodoError::TaskNotFound(task_name));
+        return Err(graph_manager.graph.task_not_found(&task_name));
     }
 
     Ok(task_name)
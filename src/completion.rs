@@ -0,0 +1,336 @@
+//! Shell completion: `bodo completions bash|zsh|fish` prints a script that asks
+//! `bodo __complete` for candidates, so task names and arguments always come from the
+//! project's current scripts.
+//!
+//! `bodo __complete -- <words>` gets the words after `bodo`, the last one being the word
+//! under the cursor (possibly empty), and prints one candidate per line, with an optional
+//! tab-separated description.
+
+use clap::{CommandFactory, ValueEnum};
+use std::fmt;
+use std::str::FromStr;
+
+use crate::cli::Args;
+use crate::config::{ArgumentType, TaskArgument};
+use crate::errors::BodoError;
+use crate::graph::{Graph, NodeKind};
+
+/// The hidden command completion scripts call.
+pub const COMPLETE_COMMAND: &str = "__complete";
+
+/// Values of `--format`, for `bodo graph` and `--dry-run`.
+const FORMATS: &[&str] = &["tree", "dot", "mermaid", "json", "text", "yaml"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+}
+
+impl FromStr for Shell {
+    type Err = BodoError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "bash" => Ok(Shell::Bash),
+            "zsh" => Ok(Shell::Zsh),
+            "fish" => Ok(Shell::Fish),
+            other => Err(BodoError::ValidationError(format!(
+                "unknown shell '{}'; expected one of: bash, zsh, fish",
+                other
+            ))),
+        }
+    }
+}
+
+impl Shell {
+    /// The completion script to source in this shell.
+    pub fn script(self) -> &'static str {
+        match self {
+            Shell::Bash => BASH,
+            Shell::Zsh => ZSH,
+            Shell::Fish => FISH,
+        }
+    }
+}
+
+const BASH: &str = r#"_bodo() {
+    local IFS=$'\n'
+    COMPREPLY=($(bodo __complete -- "${COMP_WORDS[@]:1:COMP_CWORD}" 2>/dev/null | cut -f1))
+}
+complete -o default -F _bodo bodo
+"#;
+
+const ZSH: &str = r#"#compdef bodo
+_bodo() {
+    local -a candidates
+    local line
+    for line in "${(@f)$(bodo __complete -- "${words[@]:1:$((CURRENT-1))}" 2>/dev/null)}"; do
+        [[ -z $line ]] && continue
+        if [[ $line == *$'\t'* ]]; then
+            candidates+=("${${line%%$'\t'*}//:/\\:}:${line#*$'\t'}")
+        else
+            candidates+=("${line//:/\\:}")
+        fi
+    done
+    if (( ${#candidates} )); then
+        _describe 'bodo' candidates
+    else
+        _files
+    fi
+}
+if [ "$funcstack[1]" = "_bodo" ]; then
+    _bodo "$@"
+else
+    compdef _bodo bodo
+fi
+"#;
+
+const FISH: &str = r#"function __bodo_complete
+    set -l tokens (commandline -opc)
+    set -e tokens[1]
+    bodo __complete -- $tokens (commandline -ct | string collect --allow-empty) 2>/dev/null
+end
+complete -c bodo -f -a '(__bodo_complete)'
+"#;
+
+/// A completion candidate and what it is.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Candidate {
+    pub value: String,
+    pub description: Option<String>,
+}
+
+impl Candidate {
+    fn new(value: impl Into<String>, description: Option<&str>) -> Self {
+        Self {
+            value: value.into(),
+            description: description
+                .map(|d| d.lines().next().unwrap_or_default().to_string())
+                .filter(|d| !d.is_empty()),
+        }
+    }
+}
+
+impl fmt::Display for Candidate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.description {
+            Some(description) => write!(f, "{}\t{}", self.value, description),
+            None => write!(f, "{}", self.value),
+        }
+    }
+}
+
+/// Candidates for the last of `words`, the words after `bodo`: bodo's options, task
+/// names, subtasks of a script's namespace, and after `--` the task's argument flags and
+/// enum choices.
+pub fn complete(graph: &Graph, words: &[String]) -> Vec<Candidate> {
+    let (current, before) = match words.split_last() {
+        Some((current, before)) => (current.as_str(), before),
+        None => ("", &[][..]),
+    };
+    let mut candidates = match before.iter().position(|word| word == "--") {
+        Some(separator) => {
+            let (_, task) = command_and_task(&before[..separator]);
+            let task = task.join(" ");
+            let arguments = task_arguments(graph, &task);
+            complete_task_argument(&arguments, &before[separator + 1..], current)
+        }
+        None => complete_bodo(graph, before, current),
+    };
+    candidates.retain(|candidate| candidate.value.starts_with(current));
+    candidates
+}
+
+/// Completion before `--`: options, then a command or task and what follows it.
+fn complete_bodo(graph: &Graph, before: &[String], current: &str) -> Vec<Candidate> {
+    if before.last().map(String::as_str) == Some("--format") {
+        return FORMATS.iter().map(|f| Candidate::new(*f, None)).collect();
+    }
+    if current.starts_with('-') {
+        return Args::command()
+            .get_arguments()
+            .filter(|arg| !arg.is_hide_set())
+            .filter_map(|arg| {
+                let help = arg.get_help().map(ToString::to_string);
+                arg.get_long()
+                    .map(|long| Candidate::new(format!("--{}", long), help.as_deref()))
+            })
+            .collect();
+    }
+    let (command, task) = command_and_task(before);
+    let takes_task = matches!(command, None | Some("run" | "describe" | "graph"));
+    match task.as_slice() {
+        [] if command.is_none() => {
+            let mut candidates = top_level_names(graph);
+            candidates.extend(
+                Args::command()
+                    .get_subcommands()
+                    .filter(|subcommand| !subcommand.is_hide_set())
+                    .map(|subcommand| {
+                        let about = subcommand.get_about().map(ToString::to_string);
+                        Candidate::new(subcommand.get_name(), about.as_deref())
+                    }),
+            );
+            sort_and_dedup(candidates)
+        }
+        [] if command == Some("completions") => Shell::value_variants()
+            .iter()
+            .filter_map(|shell| shell.to_possible_value())
+            .map(|value| Candidate::new(value.get_name(), None))
+            .collect(),
+        [] if takes_task => sort_and_dedup(top_level_names(graph)),
+        [namespace] if takes_task => {
+            let prefix = format!("{} ", namespace);
+            sort_and_dedup(
+                task_candidates(graph)
+                    .filter_map(|(key, description)| {
+                        key.strip_prefix(&prefix)
+                            .map(|subtask| Candidate::new(subtask, description))
+                    })
+                    .collect(),
+            )
+        }
+        _ => Vec::new(),
+    }
+}
+
+/// Sorted by value. A namespace with a default task is listed once, with its description.
+fn sort_and_dedup(mut candidates: Vec<Candidate>) -> Vec<Candidate> {
+    candidates.sort_by_key(|c| (c.value.clone(), c.description.is_none()));
+    candidates.dedup_by(|a, b| a.value == b.value);
+    candidates
+}
+
+/// Completion after `--`: the task's flags, and values for the flag or position at hand.
+fn complete_task_argument(
+    arguments: &[TaskArgument],
+    before: &[String],
+    current: &str,
+) -> Vec<Candidate> {
+    let values = |argument: &TaskArgument| -> Vec<Candidate> {
+        match argument.arg_type {
+            ArgumentType::Enum => argument
+                .choices
+                .iter()
+                .map(|choice| Candidate::new(choice.clone(), None))
+                .collect(),
+            ArgumentType::Bool => vec![Candidate::new("true", None), Candidate::new("false", None)],
+            _ => Vec::new(),
+        }
+    };
+    let by_flag = |word: &str| {
+        arguments.iter().find(|argument| {
+            word.strip_prefix("--") == Some(argument.flag().as_str())
+                || (word.len() == 2
+                    && word.starts_with('-')
+                    && argument.short.map(|s| s.to_string()).as_deref() == word.get(1..))
+        })
+    };
+
+    if let Some((flag, _)) = current.split_once('=') {
+        return by_flag(flag)
+            .map(|argument| {
+                values(argument)
+                    .into_iter()
+                    .map(|c| Candidate::new(format!("{}={}", flag, c.value), None))
+                    .collect()
+            })
+            .unwrap_or_default();
+    }
+    if let Some(argument) = before.last().and_then(|word| by_flag(word)) {
+        if argument.arg_type != ArgumentType::Bool {
+            return values(argument);
+        }
+    }
+    if current.starts_with('-') {
+        return arguments
+            .iter()
+            .map(|argument| {
+                Candidate::new(
+                    format!("--{}", argument.flag()),
+                    argument.description.as_deref(),
+                )
+            })
+            .collect();
+    }
+
+    // The next positional value goes to the first argument not given by name.
+    let mut named = Vec::new();
+    let mut positional = 0;
+    let mut words = before.iter();
+    while let Some(word) = words.next() {
+        let flag = word.split_once('=').map_or(word.as_str(), |(flag, _)| flag);
+        match by_flag(flag) {
+            Some(argument) => {
+                named.push(argument.name.clone());
+                if !word.contains('=') && argument.arg_type != ArgumentType::Bool {
+                    words.next();
+                }
+            }
+            None => positional += 1,
+        }
+    }
+    arguments
+        .iter()
+        .filter(|argument| !named.contains(&argument.name))
+        .nth(positional)
+        .or_else(|| arguments.last().filter(|argument| argument.variadic))
+        .map(values)
+        .unwrap_or_default()
+}
+
+/// The command the words start with, if any, and the words naming the task.
+fn command_and_task(words: &[String]) -> (Option<&str>, Vec<&str>) {
+    let mut positionals = Vec::new();
+    let mut words = words.iter();
+    while let Some(word) = words.next() {
+        if word == "--format" {
+            words.next();
+        } else if !word.starts_with('-') {
+            positionals.push(word.as_str());
+        }
+    }
+    let command = positionals.first().copied().filter(|first| {
+        Args::command()
+            .get_subcommands()
+            .any(|subcommand| subcommand.get_name() == *first)
+    });
+    if command.is_some() {
+        positionals.remove(0);
+    }
+    (command, positionals)
+}
+
+fn task_candidates(graph: &Graph) -> impl Iterator<Item = (&str, Option<&str>)> {
+    graph
+        .task_registry
+        .iter()
+        .filter_map(|(key, &id)| match &graph.nodes[id as usize].kind {
+            NodeKind::Task(task) => Some((key.as_str(), task.description.as_deref())),
+            _ => None,
+        })
+}
+
+/// Tasks of the root script, and the namespaces of the other scripts.
+fn top_level_names(graph: &Graph) -> Vec<Candidate> {
+    task_candidates(graph)
+        .map(|(key, description)| match key.split_once(' ') {
+            Some((namespace, _)) => Candidate::new(namespace, None),
+            None => Candidate::new(key, description),
+        })
+        .collect()
+}
+
+fn task_arguments(graph: &Graph, task: &str) -> Vec<TaskArgument> {
+    let key = if task.is_empty() { "default" } else { task };
+    graph
+        .task_registry
+        .get(key)
+        .and_then(|&id| match &graph.nodes[id as usize].kind {
+            NodeKind::Task(task) => Some(task.arguments.clone()),
+            _ => None,
+        })
+        .unwrap_or_default()
+}
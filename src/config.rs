@@ -61,6 +61,58 @@ fn validate_task_config(task: &TaskConfig) -> std::result::Result<(), Validation
         }
     }
 
+    if let Err(message) = validate_task_arguments(&task.arguments) {
+        let mut err = ValidationError::new("invalid_argument");
+        err.message = Some(message.into());
+        return Err(err);
+    }
+
+    Ok(())
+}
+
+/// Argument definitions must be usable from the command line: distinct flags, choices
+/// only on enums, a variadic argument only in last place and valid defaults.
+fn validate_task_arguments(arguments: &[TaskArgument]) -> std::result::Result<(), String> {
+    for (i, argument) in arguments.iter().enumerate() {
+        let flag = argument.flag();
+        if flag == "help" || argument.short == Some('h') {
+            return Err(format!(
+                "Argument '{}' cannot use --help or -h, which show the task's help",
+                argument.name
+            ));
+        }
+        if let Some(other) = arguments[..i].iter().find(|other| {
+            other.flag() == flag || (argument.short.is_some() && other.short == argument.short)
+        }) {
+            return Err(format!(
+                "Arguments '{}' and '{}' have the same flag",
+                other.name, argument.name
+            ));
+        }
+        match (argument.arg_type, argument.choices.is_empty()) {
+            (ArgumentType::Enum, true) => {
+                return Err(format!("Enum argument '{}' needs choices", argument.name))
+            }
+            (ArgumentType::Enum, false) | (_, true) => {}
+            (_, false) => {
+                return Err(format!(
+                    "Argument '{}' has choices but is not an enum",
+                    argument.name
+                ))
+            }
+        }
+        if argument.variadic && i + 1 != arguments.len() {
+            return Err(format!(
+                "Variadic argument '{}' must be the last argument",
+                argument.name
+            ));
+        }
+        if let Some(default) = &argument.default {
+            argument
+                .parse_value(default)
+                .map_err(|e| format!("Invalid default for argument '{}': {}", argument.name, e))?;
+        }
+    }
     Ok(())
 }
 
@@ -103,6 +155,25 @@ impl Default for WatchConfig {
     }
 }
 
+/// What a task may touch when it runs in a sandbox (`bodo --sandbox`, or always when set).
+///
+/// The task's working directory is always writable. Relative paths are resolved against it.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct SandboxConfig {
+    /// Allow network access (blocked by default)
+    #[serde(default)]
+    pub network: bool,
+
+    /// Extra paths the task may write to
+    #[serde(default)]
+    pub writable: Vec<String>,
+
+    /// Extra host paths made visible to the task, read-only
+    #[serde(default)]
+    pub read_only: Vec<String>,
+}
+
 fn default_debounce_ms() -> u64 {
     500
 }
@@ -122,7 +193,27 @@ pub struct ConcurrentlyOptions {
     pub prefix_color: Option<String>,
 }
 
+/// The kind of value a task argument takes.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ArgumentType {
+    #[default]
+    String,
+    /// A whole number
+    Int,
+    /// `true` or `false`; as a flag, `--verbose` alone means `true`
+    Bool,
+    /// One of the argument's `choices`
+    Enum,
+    /// A file or directory, made absolute against the directory bodo runs in
+    Path,
+}
+
 /// Represents a CLI argument that can be passed to a task
+///
+/// Arguments follow `--` on the command line, by position or by name:
+/// `bodo commit -- "fix typo"` and `bodo commit -- --message "fix typo"` both set
+/// `MESSAGE`.
 #[derive(Debug, Clone, Serialize, Deserialize, Validate, JsonSchema, PartialEq, Default)]
 pub struct TaskArgument {
     /// Name of the argument (used as environment variable)
@@ -139,6 +230,58 @@ pub struct TaskArgument {
 
     /// Default value if not provided
     pub default: Option<String>,
+
+    /// Type of the value
+    #[serde(default, rename = "type")]
+    pub arg_type: ArgumentType,
+
+    /// Allowed values of an `enum` argument
+    #[serde(default)]
+    pub choices: Vec<String>,
+
+    /// One-letter alias of the flag, as in `-m`
+    pub short: Option<char>,
+
+    /// Take all remaining positional values, joined with spaces; only for the last argument
+    #[serde(default)]
+    pub variadic: bool,
+}
+
+impl TaskArgument {
+    /// The long flag for this argument, without dashes: `DRY_RUN` is `--dry-run`.
+    pub fn flag(&self) -> String {
+        self.name.to_lowercase().replace('_', "-")
+    }
+
+    /// Check `value` against the argument's type and choices and return it as the task
+    /// will see it: booleans as `true`/`false`, paths made absolute.
+    pub fn parse_value(&self, value: &str) -> std::result::Result<String, String> {
+        match self.arg_type {
+            ArgumentType::String => Ok(value.to_string()),
+            ArgumentType::Int => value
+                .parse::<i64>()
+                .map(|n| n.to_string())
+                .map_err(|_| format!("'{}' is not a whole number", value)),
+            ArgumentType::Bool => match value.to_lowercase().as_str() {
+                "true" | "yes" | "on" | "1" => Ok("true".to_string()),
+                "false" | "no" | "off" | "0" => Ok("false".to_string()),
+                _ => Err(format!("'{}' is not true or false", value)),
+            },
+            ArgumentType::Enum if self.choices.iter().any(|choice| choice == value) => {
+                Ok(value.to_string())
+            }
+            ArgumentType::Enum => Err(format!(
+                "'{}' is not one of: {}",
+                value,
+                self.choices.join(", ")
+            )),
+            ArgumentType::Path if value.is_empty() => Err("the path is empty".to_string()),
+            ArgumentType::Path => {
+                let cwd = std::env::current_dir().unwrap_or_default();
+                Ok(cwd.join(value).to_string_lossy().into_owned())
+            }
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Validate, JsonSchema)]
@@ -178,6 +321,9 @@ pub struct TaskConfig {
     /// Timeout duration (e.g. "30s", "1m")
     pub timeout: Option<String>,
 
+    /// Run the task in a sandbox with this policy
+    pub sandbox: Option<SandboxConfig>,
+
     /// Environment variables for the task
     #[serde(default)]
     pub env: HashMap<String, String>,
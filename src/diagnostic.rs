@@ -0,0 +1,244 @@
+//! Errors that point at a location in a script file.
+//!
+//! ```text
+//! Task name cannot contain '/', '.' or '..'
+//!   --> scripts/build/script.yaml:4:3
+//!    |
+//!  4 |   build.all:
+//!    |   ^^^^^^^^^
+//! ```
+
+use serde::Serialize;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// A problem in a script file, with the offending line and a caret under the span.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Diagnostic {
+    pub message: String,
+    pub path: PathBuf,
+    /// 1-based line number
+    pub line: usize,
+    /// 1-based column number
+    pub column: usize,
+    /// Width of the highlighted span, at least 1
+    pub span: usize,
+    /// The source line the diagnostic points at
+    pub source_line: String,
+    pub note: Option<String>,
+}
+
+impl Diagnostic {
+    /// A diagnostic at `line`/`column` of `source`, highlighting `span` characters.
+    pub fn new(
+        message: impl Into<String>,
+        path: &Path,
+        source: &str,
+        line: usize,
+        column: usize,
+        span: usize,
+    ) -> Self {
+        let line = line.max(1);
+        Self {
+            message: message.into(),
+            path: display_path(path),
+            line,
+            column: column.max(1),
+            span: span.max(1),
+            source_line: source.lines().nth(line - 1).unwrap_or_default().to_string(),
+            note: None,
+        }
+    }
+
+    /// A diagnostic for a `serde_yaml` error, using its location when it has one.
+    pub fn from_yaml_error(err: &serde_yaml::Error, path: &Path, source: &str) -> Self {
+        let message = err.to_string();
+        // serde_yaml appends " at line X column Y"; the location is shown separately.
+        let message = match (err.location(), message.rfind(" at line ")) {
+            (Some(_), Some(pos)) => message[..pos].to_string(),
+            _ => message,
+        };
+        let (line, column) = err
+            .location()
+            .map(|location| (location.line(), location.column()))
+            .unwrap_or((1, 1));
+        Self::new(message, path, source, line, column, 1)
+    }
+
+    pub fn with_note(mut self, note: impl Into<String>) -> Self {
+        self.note = Some(note.into());
+        self
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let number = self.line.to_string();
+        let gutter = " ".repeat(number.len());
+        writeln!(f, "{}", self.message)?;
+        writeln!(
+            f,
+            "{}--> {}:{}:{}",
+            gutter,
+            self.path.display(),
+            self.line,
+            self.column
+        )?;
+        writeln!(f, "{} |", gutter)?;
+        writeln!(f, "{} | {}", number, self.source_line)?;
+        write!(
+            f,
+            "{} | {}{}",
+            gutter,
+            " ".repeat(self.column - 1),
+            "^".repeat(self.span)
+        )?;
+        if let Some(note) = &self.note {
+            write!(f, "\n{} = note: {}", gutter, note)?;
+        }
+        Ok(())
+    }
+}
+
+/// Show paths relative to the current directory when they are inside it.
+pub fn display_path(path: &Path) -> PathBuf {
+    std::env::current_dir()
+        .ok()
+        .and_then(|cwd| path.strip_prefix(cwd).ok().map(Path::to_path_buf))
+        .unwrap_or_else(|| path.to_path_buf())
+}
+
+/// Where a key sits in a YAML document: 1-based line and column, and the key's width.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyLocation {
+    pub line: usize,
+    pub column: usize,
+    pub width: usize,
+}
+
+/// Finds mapping keys in block-style YAML by indentation, to point diagnostics at the
+/// task or field they are about. Flow-style mappings are not looked into.
+pub struct YamlKeys<'a> {
+    lines: Vec<&'a str>,
+}
+
+impl<'a> YamlKeys<'a> {
+    pub fn new(source: &'a str) -> Self {
+        Self {
+            lines: source.lines().collect(),
+        }
+    }
+
+    /// The location of the key at `path`, e.g. `["tasks", "build", "timeout"]`.
+    pub fn find(&self, path: &[&str]) -> Option<KeyLocation> {
+        let mut range = 0..self.lines.len();
+        let mut parent_indent = None;
+        let mut found = None;
+        for segment in path {
+            let (index, location) = self
+                .children(range.clone(), parent_indent)
+                .into_iter()
+                .find(|(_, key, _)| key == segment)
+                .map(|(index, _, location)| (index, location))?;
+            range = index + 1..self.block_end(index, location.column - 1);
+            parent_indent = Some(location.column - 1);
+            found = Some(location);
+        }
+        found
+    }
+
+    /// Keys that appear more than once directly under `path`: the key, its first location
+    /// and the location of each repetition.
+    pub fn duplicates(&self, path: &[&str]) -> Vec<(String, KeyLocation, KeyLocation)> {
+        let (range, parent_indent) = match path {
+            [] => (0..self.lines.len(), None),
+            _ => match self.find(path) {
+                Some(location) => (
+                    location.line..self.block_end(location.line - 1, location.column - 1),
+                    Some(location.column - 1),
+                ),
+                None => return Vec::new(),
+            },
+        };
+        let mut seen: Vec<(String, KeyLocation)> = Vec::new();
+        let mut duplicates = Vec::new();
+        for (_, key, location) in self.children(range, parent_indent) {
+            match seen.iter().find(|(name, _)| *name == key) {
+                Some((_, first)) => duplicates.push((key, *first, location)),
+                None => seen.push((key, location)),
+            }
+        }
+        duplicates
+    }
+
+    /// The mapping keys directly inside `range`, one indentation level below the parent.
+    fn children(
+        &self,
+        range: std::ops::Range<usize>,
+        parent_indent: Option<usize>,
+    ) -> Vec<(usize, String, KeyLocation)> {
+        let mut child_indent = None;
+        let mut keys = Vec::new();
+        for index in range {
+            let line = self.lines[index];
+            let Some(indent) = content_indent(line) else {
+                continue;
+            };
+            if parent_indent.is_some_and(|parent| indent <= parent) {
+                break;
+            }
+            let child_indent = *child_indent.get_or_insert(indent);
+            if indent != child_indent {
+                continue;
+            }
+            if let Some((key, width)) = parse_key(&line[indent..]) {
+                keys.push((
+                    index,
+                    key,
+                    KeyLocation {
+                        line: index + 1,
+                        column: indent + 1,
+                        width,
+                    },
+                ));
+            }
+        }
+        keys
+    }
+
+    /// The index one past the last line belonging to the key on line `index`.
+    fn block_end(&self, index: usize, indent: usize) -> usize {
+        (index + 1..self.lines.len())
+            .find(|&i| content_indent(self.lines[i]).is_some_and(|i| i <= indent))
+            .unwrap_or(self.lines.len())
+    }
+}
+
+/// Indentation of a line with content, or `None` for blank and comment lines.
+fn content_indent(line: &str) -> Option<usize> {
+    let trimmed = line.trim_start();
+    if trimmed.is_empty() || trimmed.starts_with('#') {
+        None
+    } else {
+        Some(line.len() - trimmed.len())
+    }
+}
+
+/// The key of a `key: value` line and the key's width as written (including quotes).
+fn parse_key(text: &str) -> Option<(String, usize)> {
+    if let Some(quote) = text.chars().next().filter(|c| *c == '"' || *c == '\'') {
+        let end = text[1..].find(quote)? + 1;
+        return text[end + 1..]
+            .starts_with(':')
+            .then(|| (text[1..end].to_string(), end + 1));
+    }
+    if text.starts_with('-') {
+        return None;
+    }
+    let colon = text
+        .char_indices()
+        .find(|&(i, c)| c == ':' && text[i + 1..].chars().next().is_none_or(char::is_whitespace))
+        .map(|(i, _)| i)?;
+    let key = text[..colon].trim_end();
+    Some((key.to_string(), key.len()))
+}
@@ -1,16 +1,21 @@
-use std::{error::Error, fmt, io};
+use std::{error::Error, fmt, io, time::Duration};
 use validator::{ValidationError, ValidationErrors};
 
 #[derive(Debug)]
 pub enum BodoError {
     IoError(io::Error),
     WatcherError(String),
-    TaskNotFound(String),
+    /// An unknown task name and the closest existing names, best first.
+    TaskNotFound(String, Vec<String>),
     PluginError(String),
     SerdeError(serde_json::Error),
     YamlError(serde_yaml::Error),
     NoTaskSpecified,
     ValidationError(String),
+    /// A problem in a script file, pointing at where it is.
+    ConfigError(Box<crate::diagnostic::Diagnostic>),
+    /// A task was killed because it ran past its timeout: task name and elapsed time.
+    Timeout(String, Duration),
 }
 
 impl fmt::Display for BodoError {
@@ -18,7 +23,12 @@ impl fmt::Display for BodoError {
         match self {
             BodoError::IoError(err) => write!(f, "{}", err),
             BodoError::WatcherError(err) => write!(f, "{}", err),
-            BodoError::TaskNotFound(_) => write!(f, "not found"),
+            BodoError::TaskNotFound(task, suggestions) => write!(
+                f,
+                "task '{}' not found{}",
+                task,
+                crate::suggest::did_you_mean(suggestions)
+            ),
             BodoError::PluginError(err) => write!(f, "Plugin error: {}", err),
             BodoError::SerdeError(err) => write!(f, "{}", err),
             BodoError::YamlError(err) => write!(f, "{}", err),
@@ -26,6 +36,13 @@ impl fmt::Display for BodoError {
                 write!(f, "No task specified and no scripts/script.yaml found")
             }
             BodoError::ValidationError(err) => write!(f, "Validation error: {}", err),
+            BodoError::ConfigError(diagnostic) => write!(f, "{}", diagnostic),
+            BodoError::Timeout(task, elapsed) => write!(
+                f,
+                "Task '{}' timed out after {}",
+                task,
+                humantime::format_duration(Duration::from_millis(elapsed.as_millis() as u64))
+            ),
         }
     }
 }
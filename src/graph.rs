@@ -33,6 +33,7 @@ pub struct CommandData {
     pub description: Option<String>,
     pub working_dir: Option<String>,
     pub env: std::collections::HashMap<String, String>,
+    pub exec_paths: Vec<String>,
     pub watch: Option<String>,
 }
 
@@ -51,6 +52,41 @@ pub struct Node {
     pub metadata: std::collections::HashMap<String, String>,
 }
 
+impl Node {
+    /// Where each environment variable of this node came from (`global`, `script`, `task`, `cli`, ...).
+    pub fn env_sources(&self) -> std::collections::HashMap<String, String> {
+        self.source_map("env_sources")
+    }
+
+    /// Where each exec path of this node came from (`global`, `script` or `task`).
+    pub fn exec_path_sources(&self) -> std::collections::HashMap<String, String> {
+        self.source_map("exec_path_sources")
+    }
+
+    pub fn set_env_source(&mut self, key: &str, source: &str) {
+        self.set_source("env_sources", key, source);
+    }
+
+    pub fn set_exec_path_source(&mut self, path: &str, source: &str) {
+        self.set_source("exec_path_sources", path, source);
+    }
+
+    fn source_map(&self, meta_key: &str) -> std::collections::HashMap<String, String> {
+        self.metadata
+            .get(meta_key)
+            .and_then(|v| serde_json::from_str(v).ok())
+            .unwrap_or_default()
+    }
+
+    fn set_source(&mut self, meta_key: &str, key: &str, source: &str) {
+        let mut sources = self.source_map(meta_key);
+        sources.insert(key.to_string(), source.to_string());
+        if let Ok(json) = serde_json::to_string(&sources) {
+            self.metadata.insert(meta_key.to_string(), json);
+        }
+    }
+}
+
 #[derive(Debug, Clone, Default)]
 pub struct Graph {
     pub nodes: Vec<Node>,
@@ -58,10 +94,29 @@ pub struct Graph {
     pub task_registry: std::collections::HashMap<String, NodeId>,
 }
 
+/// An edge from a node to one of its dependencies: `from` depends on `to`, and running
+/// `from` runs `to` too. [`EdgeKind`] says when.
+///
+/// Edges used to mean "`from` runs first"; they now point from the dependent to what it
+/// depends on, so [`Graph::topological_sort`] lists a task before its `pre_deps`.
 #[derive(Debug, Clone)]
 pub struct Edge {
     pub from: NodeId,
     pub to: NodeId,
+    pub kind: EdgeKind,
+}
+
+/// When the `to` of an [`Edge`] runs relative to its `from`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum EdgeKind {
+    /// `to` runs before `from` (`pre_deps`)
+    #[default]
+    PreDep,
+    /// `to` runs after `from` (`post_deps`)
+    PostDep,
+    /// `from` waits for `to` to finish: a task for its concurrent group, or a group for
+    /// each of its children, which run side by side
+    Concurrent,
 }
 
 impl Graph {
@@ -84,15 +139,33 @@ impl Graph {
     }
 
     pub fn add_edge(&mut self, from: NodeId, to: NodeId) -> crate::Result<()> {
+        self.add_edge_of_kind(from, to, EdgeKind::PreDep)
+    }
+
+    pub fn add_edge_of_kind(
+        &mut self,
+        from: NodeId,
+        to: NodeId,
+        kind: EdgeKind,
+    ) -> crate::Result<()> {
         if from as usize >= self.nodes.len() || to as usize >= self.nodes.len() {
             return Err(crate::errors::BodoError::PluginError(
                 "Invalid node ID".to_string(),
             ));
         }
-        self.edges.push(Edge { from, to });
+        self.edges.push(Edge { from, to, kind });
         Ok(())
     }
 
+    /// Dependencies of `node_id` with the given edge kind, in the order they were declared.
+    pub fn dependencies_of(&self, node_id: NodeId, kind: EdgeKind) -> Vec<NodeId> {
+        self.edges
+            .iter()
+            .filter(|e| e.from == node_id && e.kind == kind)
+            .map(|e| e.to)
+            .collect()
+    }
+
     pub fn print_debug(&self) {
         log::debug!("\nGraph Debug Info:");
         log::debug!("Nodes: {}", self.nodes.len());
@@ -140,7 +213,7 @@ impl Graph {
         }
         log::debug!("\nEdges: {}", self.edges.len());
         for edge in &self.edges {
-            log::debug!("  {} -> {}", edge.from, edge.to);
+            log::debug!("  {} -> {} ({:?})", edge.from, edge.to, edge.kind);
         }
         log::debug!("");
     }
@@ -237,6 +310,26 @@ impl Graph {
         self.get_node_name(node_id)
     }
 
+    /// Registry keys and script namespaces that look like a misspelling of `name`.
+    pub fn suggest_tasks(&self, name: &str) -> Vec<String> {
+        let namespaces = self.nodes.iter().filter_map(|node| match &node.kind {
+            NodeKind::Task(task) if !task.script_id.is_empty() => Some(task.script_id.as_str()),
+            _ => None,
+        });
+        crate::suggest::suggest(
+            name,
+            self.task_registry
+                .keys()
+                .map(String::as_str)
+                .chain(namespaces),
+        )
+    }
+
+    /// A [`BodoError::TaskNotFound`] for `name`, with suggestions from the registry.
+    pub fn task_not_found(&self, name: &str) -> crate::errors::BodoError {
+        crate::errors::BodoError::TaskNotFound(name.to_string(), self.suggest_tasks(name))
+    }
+
     pub fn topological_sort(&self) -> crate::Result<Vec<NodeId>> {
         let mut in_degree = vec![0; self.nodes.len()];
         for e in &self.edges {
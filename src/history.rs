@@ -0,0 +1,163 @@
+//! Wall-clock durations of previous runs, kept per node in `.bodo/history.json`.
+//!
+//! Only successful runs are recorded, and only the last [`MAX_SAMPLES`] of them, so
+//! percentiles follow the task as it gets faster or slower.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::errors::Result;
+
+/// Version of the history file. Files with another version are ignored and rewritten.
+pub const HISTORY_VERSION: u32 = 1;
+
+/// Samples kept per node.
+pub const MAX_SAMPLES: usize = 20;
+
+/// Samples needed before a run is compared against the history.
+pub const MIN_SAMPLES_FOR_REGRESSION: usize = 5;
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct HistoryFile {
+    version: u32,
+    /// Durations in seconds, oldest first, keyed by node id
+    tasks: BTreeMap<String, Vec<f64>>,
+}
+
+/// A run that took much longer than usual.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Regression {
+    pub took: Duration,
+    pub p50: Duration,
+    pub p90: Duration,
+}
+
+impl fmt::Display for Regression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "took {}, usually {} (p90 {})",
+            format_secs(self.took),
+            format_secs(self.p50),
+            format_secs(self.p90)
+        )
+    }
+}
+
+/// Run durations per node id, loaded from and saved to a JSON file.
+#[derive(Debug)]
+pub struct History {
+    path: PathBuf,
+    file: HistoryFile,
+}
+
+impl History {
+    /// `.bodo/history.json` in the current directory.
+    pub fn default_path() -> PathBuf {
+        std::env::current_dir()
+            .unwrap_or_default()
+            .join(".bodo")
+            .join("history.json")
+    }
+
+    /// Load the history at `path`. A missing, unreadable or outdated file starts empty.
+    pub fn load(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        let file = fs::read_to_string(&path)
+            .ok()
+            .and_then(
+                |content| match serde_json::from_str::<HistoryFile>(&content) {
+                    Ok(file) if file.version == HISTORY_VERSION => Some(file),
+                    Ok(_) => None,
+                    Err(e) => {
+                        log::warn!("Ignoring unreadable history {}: {}", path.display(), e);
+                        None
+                    }
+                },
+            )
+            .unwrap_or(HistoryFile {
+                version: HISTORY_VERSION,
+                tasks: BTreeMap::new(),
+            });
+        Self { path, file }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Write the history, replacing the file in one step so that runs finishing at the same
+    /// time or an interrupted write never leave it half-written.
+    pub fn save(&self) -> Result<()> {
+        let parent = match self.path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent,
+            _ => Path::new("."),
+        };
+        fs::create_dir_all(parent)?;
+        let content = serde_json::to_string_pretty(&self.file)?;
+        let mut file = tempfile::NamedTempFile::new_in(parent)?;
+        file.write_all(content.as_bytes())?;
+        file.persist(&self.path).map_err(|err| err.error)?;
+        Ok(())
+    }
+
+    /// Add a successful run of `id`, dropping the oldest sample past [`MAX_SAMPLES`].
+    pub fn record(&mut self, id: &str, took: Duration) {
+        let samples = self.file.tasks.entry(id.to_string()).or_default();
+        samples.push(took.as_secs_f64());
+        if samples.len() > MAX_SAMPLES {
+            samples.drain(..samples.len() - MAX_SAMPLES);
+        }
+    }
+
+    /// Number of recorded runs of `id`.
+    pub fn runs(&self, id: &str) -> usize {
+        self.file.tasks.get(id).map_or(0, Vec::len)
+    }
+
+    /// The `p`th percentile (0–100, nearest rank) of the recorded durations of `id`.
+    pub fn percentile(&self, id: &str, p: f64) -> Option<Duration> {
+        let mut samples = self.file.tasks.get(id)?.clone();
+        if samples.is_empty() {
+            return None;
+        }
+        samples.sort_by(f64::total_cmp);
+        let rank = ((p / 100.0) * samples.len() as f64).ceil() as usize;
+        let secs = samples[rank.clamp(1, samples.len()) - 1];
+        Some(Duration::from_secs_f64(secs))
+    }
+
+    /// The median duration of `id`, if it has run before.
+    pub fn estimate(&self, id: &str) -> Option<Duration> {
+        self.percentile(id, 50.0)
+    }
+
+    /// Whether `took` is well beyond what `id` usually takes: more than 1.5 times its p90
+    /// and at least a second over its median.
+    pub fn regression(&self, id: &str, took: Duration) -> Option<Regression> {
+        if self.runs(id) < MIN_SAMPLES_FOR_REGRESSION {
+            return None;
+        }
+        let p50 = self.percentile(id, 50.0)?;
+        let p90 = self.percentile(id, 90.0)?;
+        let slower = took.as_secs_f64() > p90.as_secs_f64() * 1.5
+            && took.saturating_sub(p50) >= Duration::from_secs(1);
+        slower.then_some(Regression { took, p50, p90 })
+    }
+}
+
+/// `3.2s`, `1m 05s`: short enough for a status line.
+pub fn format_secs(duration: Duration) -> String {
+    let secs = duration.as_secs_f64();
+    if secs < 60.0 {
+        format!("{:.1}s", secs)
+    } else {
+        let secs = duration.as_secs();
+        format!("{}m {:02}s", secs / 60, secs % 60)
+    }
+}
@@ -0,0 +1,40 @@
+//! `bodo init`: a starter script to edit.
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::errors::Result;
+
+/// The script `bodo init` writes: a default task and one task with an argument.
+pub const STARTER_SCRIPT: &str = r#"# Tasks for this project. `bodo --list` shows them, `bodo <task>` runs one.
+default_task:
+  description: Say hello
+  command: echo "Hello from bodo"
+
+tasks:
+  greet:
+    description: Greet someone by name
+    command: echo "Hello, $NAME"
+    args:
+      - name: NAME
+        description: Who to greet
+        default: world
+"#;
+
+/// Write [`STARTER_SCRIPT`] to `path`, creating its directory. An existing script is
+/// never overwritten.
+pub fn init_script(path: &Path) -> Result<()> {
+    if path.exists() {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!("{} already exists", path.display()),
+        )
+        .into());
+    }
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(path, STARTER_SCRIPT)?;
+    Ok(())
+}
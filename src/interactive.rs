@@ -0,0 +1,130 @@
+//! Interactive task picker for `bodo --interactive`.
+//!
+//! Lists every task in the registry grouped by script, filters them fuzzily as the
+//! user types and then prompts for the chosen task's arguments.
+
+use crate::{
+    config::TaskArgument,
+    errors::BodoError,
+    graph::{Graph, NodeKind},
+    Result,
+};
+use dialoguer::{theme::ColorfulTheme, FuzzySelect, Input};
+use std::cmp::Ordering;
+
+/// A task the picker can offer.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TaskChoice {
+    /// Registry key, as accepted on the command line (`build`, `deploy prod`).
+    pub key: String,
+    pub description: Option<String>,
+    /// Display name of the script the task belongs to; empty for the root script.
+    pub script: String,
+    pub arguments: Vec<TaskArgument>,
+}
+
+/// The task chosen in the picker and its arguments, as `--name=value` flags.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Selection {
+    pub task: String,
+    pub args: Vec<String>,
+}
+
+/// All tasks in the registry, root script first and then by script name, like `--list`.
+pub fn task_choices(graph: &Graph) -> Vec<TaskChoice> {
+    let mut choices: Vec<TaskChoice> = graph
+        .task_registry
+        .iter()
+        .filter_map(|(key, &id)| match &graph.nodes[id as usize].kind {
+            NodeKind::Task(task) => Some(TaskChoice {
+                key: key.clone(),
+                description: task.description.clone(),
+                script: task.script_display_name.clone(),
+                arguments: task.arguments.clone(),
+            }),
+            _ => None,
+        })
+        .collect();
+    choices.sort_by(|a, b| match (a.script.is_empty(), b.script.is_empty()) {
+        (true, false) => Ordering::Less,
+        (false, true) => Ordering::Greater,
+        _ => a.script.cmp(&b.script).then_with(|| a.key.cmp(&b.key)),
+    });
+    choices
+}
+
+/// One line per choice: the task key, its description and the script it comes from.
+pub fn choice_labels(choices: &[TaskChoice]) -> Vec<String> {
+    let width = choices.iter().map(|c| c.key.len()).max().unwrap_or(0);
+    choices
+        .iter()
+        .map(|choice| {
+            let mut label = format!("{:<width$}", choice.key, width = width);
+            if let Some(desc) = &choice.description {
+                label.push_str("  ");
+                label.push_str(desc);
+            }
+            if !choice.script.is_empty() {
+                label.push_str(&format!("  [{}]", choice.script));
+            }
+            label.trim_end().to_string()
+        })
+        .collect()
+}
+
+/// Open the picker. Returns `None` when the user cancels with Esc.
+pub fn select_task(graph: &Graph) -> Result<Option<Selection>> {
+    let choices = task_choices(graph);
+    if choices.is_empty() {
+        return Err(BodoError::NoTaskSpecified);
+    }
+    let theme = ColorfulTheme::default();
+    let picked = FuzzySelect::with_theme(&theme)
+        .with_prompt("Select a task")
+        .items(&choice_labels(&choices))
+        .default(0)
+        .interact_opt()
+        .map_err(|e| BodoError::PluginError(format!("Interactive prompt failed: {}", e)))?;
+    let Some(index) = picked else {
+        return Ok(None);
+    };
+    let choice = &choices[index];
+
+    let mut args = Vec::new();
+    for argument in &choice.arguments {
+        let mut prompt = argument.name.clone();
+        if let Some(desc) = &argument.description {
+            prompt.push_str(&format!(" ({})", desc));
+        }
+        let mut input = Input::<String>::with_theme(&theme)
+            .with_prompt(prompt)
+            .allow_empty(!argument.required);
+        if let Some(default) = &argument.default {
+            input = input.default(default.clone());
+        }
+        input = input.validate_with(|value: &String| {
+            values(argument, value).try_for_each(|value| argument.parse_value(value).map(|_| ()))
+        });
+        let value = input
+            .interact_text()
+            .map_err(|e| BodoError::PluginError(format!("Interactive prompt failed: {}", e)))?;
+        // Named, so that arguments left empty do not shift the ones after them
+        args.extend(
+            values(argument, &value).map(|value| format!("--{}={}", argument.flag(), value)),
+        );
+    }
+
+    Ok(Some(Selection {
+        task: choice.key.clone(),
+        args,
+    }))
+}
+
+/// The values typed for `argument`: none when left empty, one per word when variadic.
+fn values<'a>(argument: &TaskArgument, input: &'a str) -> Box<dyn Iterator<Item = &'a str> + 'a> {
+    if argument.variadic {
+        Box::new(input.split_whitespace())
+    } else {
+        Box::new((!input.is_empty()).then_some(input).into_iter())
+    }
+}
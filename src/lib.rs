@@ -1,14 +1,25 @@
+pub mod arguments;
+pub mod check;
 pub mod cli;
+pub mod completion;
 pub mod config;
 pub mod designer;
+pub mod diagnostic;
 pub mod errors;
 pub mod graph;
+pub mod history;
+pub mod init;
+#[cfg(feature = "dialoguer")]
+pub mod interactive;
 pub mod manager;
 pub mod plugin;
 pub mod plugins;
 pub mod process;
 pub mod sandbox;
 pub mod script_loader; // Added empty designer module for coverage
+pub mod suggest;
+pub mod task_resolver;
+pub mod trace;
 
 pub use config::BodoConfig;
 pub use errors::{BodoError, Result};
@@ -1,22 +1,54 @@
 use bodo::{
-    cli::{get_task_name, Args},
+    arguments::wants_help,
+    check::{check_project, CheckFormat},
+    cli::{get_task_name, join_task_name, Args, Command},
+    completion::{complete, COMPLETE_COMMAND},
     config::BodoConfig,
+    history::History,
+    init::init_script,
     manager::GraphManager,
     plugin::PluginConfig,
     plugins::{
-        concurrent_plugin::ConcurrentPlugin, env_plugin::EnvPlugin,
-        execution_plugin::ExecutionPlugin, path_plugin::PathPlugin, prefix_plugin::PrefixPlugin,
-        print_list_plugin::PrintListPlugin, timeout_plugin::TimeoutPlugin,
-        watch_plugin::WatchPlugin,
+        concurrent_plugin::ConcurrentPlugin,
+        describe_plugin::DescribePlugin,
+        env_plugin::EnvPlugin,
+        execution_plugin::ExecutionPlugin,
+        graph_plugin::{GraphFormat, GraphPlugin},
+        path_plugin::PathPlugin,
+        prefix_plugin::PrefixPlugin,
+        print_list_plugin::PrintListPlugin,
+        timeout_plugin::TimeoutPlugin,
+        watch_plugin::{TaskRunner, WatchPlugin, CHANGED_FILES_VAR},
     },
     BodoError,
 };
-use clap::Parser;
+use clap::CommandFactory;
 use log::{error, LevelFilter};
-use std::{collections::HashMap, process::exit};
+use std::{
+    collections::HashMap,
+    path::Path,
+    process::exit,
+    sync::{atomic::AtomicBool, Arc},
+};
 
 fn main() {
-    let args = Args::parse();
+    // `bodo __complete -- <words>`, called by the completion scripts. It never fails:
+    // a project that does not load just has nothing to complete.
+    let raw_args: Vec<String> = std::env::args().collect();
+    if raw_args.get(1).map(String::as_str) == Some(COMPLETE_COMMAND) {
+        let words = match raw_args.get(2).map(String::as_str) {
+            Some("--") => &raw_args[3..],
+            _ => &raw_args[2..],
+        };
+        if let Ok(graph_manager) = load_project() {
+            for candidate in complete(&graph_manager.graph, words) {
+                println!("{}", candidate);
+            }
+        }
+        return;
+    }
+
+    let args = Args::parse_command_line(std::env::args_os());
 
     if args.debug {
         std::env::set_var("RUST_LOG", "bodo=debug");
@@ -40,7 +72,63 @@ fn main() {
     }
 }
 
-fn run(args: Args) -> Result<(), BodoError> {
+fn run(mut args: Args) -> Result<(), BodoError> {
+    // Each command builds only what it needs; `bodo run` and the shorthand share the rest.
+    match args.command.take() {
+        Some(Command::Run {
+            task: None,
+            help: true,
+            ..
+        }) => {
+            let mut command = Args::command();
+            command.build();
+            if let Some(run) = command.find_subcommand_mut("run") {
+                run.print_help()?;
+            }
+            return Ok(());
+        }
+        Some(Command::Run {
+            task,
+            subtask,
+            help,
+            args: task_args,
+        }) => {
+            args.task = task;
+            args.subtask = subtask;
+            args.help |= help;
+            args.args = task_args;
+        }
+        Some(Command::List) => return list(&mut load_project()?),
+        Some(Command::Graph { task, subtask }) => {
+            let task = task.map(|task| join_task_name(&task, subtask.as_deref()));
+            return print_graph(
+                &mut load_project()?,
+                task.as_deref(),
+                args.format.as_deref(),
+            );
+        }
+        Some(Command::Describe { task, subtask }) => {
+            let task = join_task_name(&task, subtask.as_deref());
+            return describe(&mut load_project()?, &task);
+        }
+        Some(Command::Check) => return check(args.format.as_deref()),
+        Some(Command::Init) => {
+            let path = project_config().root_script.unwrap_or_default();
+            init_script(Path::new(&path))?;
+            println!("Created {}", path);
+            return Ok(());
+        }
+        Some(Command::Completions { shell }) => {
+            print!("{}", shell.script());
+            return Ok(());
+        }
+        None if args.help && args.task.is_none() => {
+            Args::command().print_help()?;
+            return Ok(());
+        }
+        None => {}
+    }
+
     let watch_mode = if std::env::var("BODO_NO_WATCH").is_ok() {
         false
     } else if args.auto_watch {
@@ -49,6 +137,100 @@ fn run(args: Args) -> Result<(), BodoError> {
         args.watch
     };
 
+    let config = project_config();
+    let mut graph_manager = GraphManager::new();
+    graph_manager.build_graph(config.clone())?;
+
+    if args.list {
+        return list(&mut graph_manager);
+    }
+
+    #[cfg(feature = "dialoguer")]
+    if args.interactive {
+        let Some(selection) = bodo::interactive::select_task(&graph_manager.graph)? else {
+            return Ok(());
+        };
+        args.task = Some(selection.task);
+        args.subtask = None;
+        args.args = selection.args;
+    }
+    let task_name = get_task_name(&args, &graph_manager)?;
+
+    if args.graph {
+        return print_graph(&mut graph_manager, Some(&task_name), args.format.as_deref());
+    }
+
+    if args.help || wants_help(&args.args) {
+        return describe(&mut graph_manager, &task_name);
+    }
+
+    // Watch mode reruns the task from scratch: same config, arguments and plugins.
+    let runner: TaskRunner = {
+        let args = args.clone();
+        let task_name = task_name.clone();
+        Arc::new(move |cancel, changed_files| {
+            let mut manager = GraphManager::new();
+            manager.build_graph(config.clone())?;
+            let mut extra_env = HashMap::new();
+            if !changed_files.is_empty() {
+                let files: Vec<String> = changed_files
+                    .iter()
+                    .map(|path| path.to_string_lossy().into_owned())
+                    .collect();
+                extra_env.insert(CHANGED_FILES_VAR.to_string(), files.join("\n"));
+            }
+            run_task(
+                &mut manager,
+                &args,
+                &task_name,
+                None,
+                Stop::Cancel(cancel),
+                extra_env,
+            )
+        })
+    };
+    let watch_plugin = WatchPlugin::new(watch_mode, true).with_runner(runner);
+
+    // Watched runs and tasks with a timeout live in their own process groups, which Ctrl-C
+    // in a terminal does not reach, so a signal stops them through the watch loop or the
+    // run. A second signal exits right away.
+    let shutdown = watch_plugin.shutdown_handle();
+    #[cfg(unix)]
+    {
+        use signal_hook::consts::{SIGINT, SIGTERM};
+        for signal in [SIGINT, SIGTERM] {
+            signal_hook::flag::register_conditional_shutdown(signal, 1, shutdown.clone())?;
+            signal_hook::flag::register(signal, shutdown.clone())?;
+        }
+    }
+
+    run_task(
+        &mut graph_manager,
+        &args,
+        &task_name,
+        Some(watch_plugin),
+        Stop::Interrupt(shutdown),
+        HashMap::new(),
+    )
+}
+
+/// How a run is stopped from outside.
+enum Stop {
+    /// Watch mode replacing the run: every process gets its own group, killed on cancel.
+    Cancel(Arc<AtomicBool>),
+    /// Ctrl-C or SIGTERM.
+    Interrupt(Arc<AtomicBool>),
+}
+
+/// The project's task graph, loaded from its scripts.
+fn load_project() -> Result<GraphManager, BodoError> {
+    let mut graph_manager = GraphManager::new();
+    graph_manager.build_graph(project_config())?;
+    Ok(graph_manager)
+}
+
+/// The project's scripts, from `BODO_ROOT_SCRIPT` and `BODO_SCRIPTS_DIRS` or the defaults.
+fn project_config() -> BodoConfig {
     let root_script = std::env::var("BODO_ROOT_SCRIPT")
         .map(|s| s.to_string())
         .unwrap_or_else(|_| "scripts/script.yaml".to_string());
@@ -68,40 +250,70 @@ fn run(args: Args) -> Result<(), BodoError> {
         None
     };
 
-    let config = BodoConfig {
+    BodoConfig {
         root_script: Some(root_script),
         scripts_dirs: Some(scripts_dirs),
         default_task,
         tasks: HashMap::new(),
         env: HashMap::new(),
         exec_paths: vec![],
-    };
-
-    let mut graph_manager = GraphManager::new();
-    graph_manager.build_graph(config)?;
-
-    if args.list {
-        graph_manager.register_plugin(Box::new(PrintListPlugin));
-        graph_manager.run_plugins(None)?;
-        return Ok(());
     }
+}
+
+/// Register the plugins for running a task, apply its CLI arguments and run it.
+/// `extra_env` is set on every task and command, e.g. the changed files in watch mode.
+fn run_task(
+    graph_manager: &mut GraphManager,
+    args: &Args,
+    task_name: &str,
+    watch_plugin: Option<WatchPlugin>,
+    stop: Stop,
+    extra_env: HashMap<String, String>,
+) -> Result<(), BodoError> {
+    let watch_mode = watch_plugin.as_ref().is_some_and(|w| w.is_watch_mode());
 
     // Register all normal plugins
     graph_manager.register_plugin(Box::new(EnvPlugin::new()));
     graph_manager.register_plugin(Box::new(PathPlugin::new()));
     graph_manager.register_plugin(Box::new(ConcurrentPlugin::new()));
     graph_manager.register_plugin(Box::new(PrefixPlugin::new()));
-    graph_manager.register_plugin(Box::new(WatchPlugin::new(watch_mode, true)));
-    graph_manager.register_plugin(Box::new(ExecutionPlugin::new()));
+    if let Some(watch_plugin) = watch_plugin {
+        graph_manager.register_plugin(Box::new(watch_plugin));
+    }
+    let mut execution_plugin = match stop {
+        Stop::Cancel(cancel) => ExecutionPlugin::new().with_cancel_flag(cancel),
+        Stop::Interrupt(interrupt) => ExecutionPlugin::new().with_interrupt_flag(interrupt),
+    };
+    if std::env::var_os("BODO_NO_HISTORY").is_none() {
+        execution_plugin = execution_plugin.with_history(History::load(History::default_path()));
+    }
+    if let (true, Some(format)) = (args.dry_run, &args.format) {
+        execution_plugin = execution_plugin.with_plan_format(format.parse()?);
+    }
+    graph_manager.register_plugin(Box::new(execution_plugin));
     graph_manager.register_plugin(Box::new(TimeoutPlugin::new()));
 
-    let task_name = get_task_name(&args, &graph_manager)?;
-
     // Apply any CLI arguments to the task before running plugins
-    graph_manager.apply_task_arguments(&task_name, &args.args)?;
+    graph_manager.apply_task_arguments(task_name, &args.args)?;
 
     let mut options = serde_json::Map::new();
-    options.insert("task".into(), serde_json::Value::String(task_name.clone()));
+    options.insert(
+        "task".into(),
+        serde_json::Value::String(task_name.to_string()),
+    );
+    if !extra_env.is_empty() {
+        let env = extra_env
+            .into_iter()
+            .map(|(k, v)| (k, serde_json::Value::String(v)))
+            .collect();
+        options.insert("env".into(), serde_json::Value::Object(env));
+    }
+    if args.side_effects {
+        options.insert("side_effects".into(), serde_json::Value::Bool(true));
+    }
+    if args.sandbox {
+        options.insert("sandbox".into(), serde_json::Value::Bool(true));
+    }
 
     let plugin_config = PluginConfig {
         fail_fast: true,
@@ -114,3 +326,63 @@ fn run(args: Args) -> Result<(), BodoError> {
     graph_manager.run_plugins(Some(plugin_config))?;
     Ok(())
 }
+
+/// Print all tasks, grouped by script.
+fn list(graph_manager: &mut GraphManager) -> Result<(), BodoError> {
+    graph_manager.register_plugin(Box::new(PrintListPlugin));
+    graph_manager.run_plugins(None)
+}
+
+/// Check every script and print all problems found, in `format`. Fails when there are any.
+fn check(format: Option<&str>) -> Result<(), BodoError> {
+    let format: CheckFormat = format.map(str::parse).transpose()?.unwrap_or_default();
+    let report = check_project(project_config());
+    print!("{}", report.render(format)?);
+    if !report.is_ok() {
+        return Err(BodoError::ValidationError(report.summary()));
+    }
+    if format == CheckFormat::Text {
+        println!("{}", report.summary());
+    }
+    Ok(())
+}
+
+/// Print everything about `task`, with its environment and dependencies resolved by the
+/// same plugins that run it.
+fn describe(graph_manager: &mut GraphManager, task: &str) -> Result<(), BodoError> {
+    if !graph_manager.task_exists(task) {
+        return Err(graph_manager.graph.task_not_found(task));
+    }
+    graph_manager.register_plugin(Box::new(EnvPlugin::new()));
+    graph_manager.register_plugin(Box::new(PathPlugin::new()));
+    graph_manager.register_plugin(Box::new(ConcurrentPlugin::new()));
+    graph_manager.register_plugin(Box::new(DescribePlugin::new()));
+    let mut options = serde_json::Map::new();
+    options.insert("task".into(), serde_json::Value::String(task.to_string()));
+    graph_manager.run_plugins(Some(PluginConfig {
+        options: Some(options),
+        ..Default::default()
+    }))
+}
+
+/// Print the dependency tree of `task`, or export the graph in `format`.
+fn print_graph(
+    graph_manager: &mut GraphManager,
+    task: Option<&str>,
+    format: Option<&str>,
+) -> Result<(), BodoError> {
+    let format = format
+        .map(str::parse)
+        .transpose()?
+        .unwrap_or(GraphFormat::Tree);
+    graph_manager.register_plugin(Box::new(ConcurrentPlugin::new()));
+    graph_manager.register_plugin(Box::new(GraphPlugin::new().with_format(format)));
+    let mut options = serde_json::Map::new();
+    if let Some(task) = task {
+        options.insert("task".into(), serde_json::Value::String(task.to_string()));
+    }
+    graph_manager.run_plugins(Some(PluginConfig {
+        options: Some(options),
+        ..Default::default()
+    }))
+}
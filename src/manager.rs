@@ -1,12 +1,12 @@
 use crate::{
+    arguments::parse_task_arguments,
     config::{BodoConfig, TaskConfig},
     errors::BodoError,
-    graph::{Graph, NodeKind},
+    graph::{EdgeKind, Graph, NodeId, NodeKind},
     plugin::{PluginConfig, PluginManager},
     script_loader::ScriptLoader,
     Result,
 };
-use std::collections::HashMap;
 
 pub struct GraphManager {
     pub config: BodoConfig,
@@ -49,7 +49,7 @@ impl GraphManager {
             .graph
             .task_registry
             .get(task_name)
-            .ok_or_else(|| BodoError::TaskNotFound(task_name.to_string()))?;
+            .ok_or_else(|| self.graph.task_not_found(task_name))?;
 
         let node = self.graph.nodes.get(*node_id as usize).ok_or_else(|| {
             BodoError::PluginError(format!("Invalid node ID for task '{}'", task_name))
@@ -74,6 +74,7 @@ impl GraphManager {
             post_deps: Vec::new(),
             watch: None,
             timeout: None,
+            sandbox: None,
             concurrently_options: Default::default(),
             concurrently: vec![],
             exec_paths: task_data.exec_paths.clone(),
@@ -108,40 +109,48 @@ impl GraphManager {
     pub fn apply_task_arguments(&mut self, task_name: &str, args: &[String]) -> Result<()> {
         let task_config = self.get_task_config(task_name)?;
 
-        // Take the arguments from the task_config.arguments
-        let arg_defs = task_config.arguments;
-
-        // Map arguments to env variables
-        let mut env_vars = HashMap::new();
-
-        for (i, arg_def) in arg_defs.iter().enumerate() {
-            if i < args.len() {
-                env_vars.insert(arg_def.name.clone(), args[i].clone());
-            } else if let Some(default) = &arg_def.default {
-                env_vars.insert(arg_def.name.clone(), default.clone());
-            } else if arg_def.required {
-                return Err(BodoError::PluginError(format!(
-                    "Missing required argument: {}",
-                    arg_def.name
-                )));
-            }
-        }
+        // Map arguments to env variables, by position or by `--name` flag
+        let env_vars = parse_task_arguments(&task_config.arguments, args)?;
 
         // Update the task's env in the graph
         let node_id = self
             .graph
             .task_registry
             .get(task_name)
-            .ok_or_else(|| BodoError::TaskNotFound(task_name.to_string()))?;
+            .ok_or_else(|| self.graph.task_not_found(task_name))?;
 
-        if let NodeKind::Task(task_data) = &mut self.graph.nodes[*node_id as usize].kind {
-            task_data.env.extend(env_vars);
+        let node_id = *node_id;
+        let node = &mut self.graph.nodes[node_id as usize];
+        let cli_keys: Vec<String> = env_vars.keys().cloned().collect();
+        if let NodeKind::Task(task_data) = &mut node.kind {
+            task_data.env.extend(env_vars.clone());
         } else {
             return Err(BodoError::PluginError(format!(
                 "Node '{}' is not a Task node",
                 task_name
             )));
         }
+        for key in &cli_keys {
+            node.set_env_source(key, "cli");
+        }
+
+        // Inline pre and post commands copied the task's env when they were loaded
+        let commands: Vec<NodeId> = self
+            .graph
+            .edges
+            .iter()
+            .filter(|edge| edge.from == node_id && edge.kind != EdgeKind::Concurrent)
+            .map(|edge| edge.to)
+            .collect();
+        for command_id in commands {
+            let node = &mut self.graph.nodes[command_id as usize];
+            if let NodeKind::Command(cmd_data) = &mut node.kind {
+                cmd_data.env.extend(env_vars.clone());
+                for key in &cli_keys {
+                    node.set_env_source(key, "cli");
+                }
+            }
+        }
 
         Ok(())
     }
@@ -1,15 +1,24 @@
+use crate::errors::BodoError;
 use crate::graph::Graph;
 use crate::Result;
+use serde::{Deserialize, Serialize};
 use std::any::Any;
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::time::Duration;
 
 /// Represents a side effect that would occur during execution
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "target", rename_all = "snake_case")]
 pub enum SideEffect {
     /// Writing to a file
     FileWrite(PathBuf),
+    /// Changing an existing file
+    FileModify(PathBuf),
+    /// Deleting a file or directory
+    FileDelete(PathBuf),
+    /// Creating a directory
+    DirectoryCreate(PathBuf),
     /// Reading from a file
     FileRead(PathBuf),
     /// Making a network request
@@ -20,26 +29,155 @@ pub enum SideEffect {
     EnvironmentChange(String, String),
 }
 
+/// Version of the serialized [`DryRunPlan`]. Bump it whenever its shape changes.
+pub const DRY_RUN_PLAN_VERSION: u32 = 1;
+
+/// Everything `bodo --dry-run --format json|yaml` prints: the commands the task would run,
+/// in execution order.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DryRunPlan {
+    pub version: u32,
+    /// The task that was planned
+    pub task: String,
+    pub commands: Vec<DryRunReport>,
+}
+
+impl DryRunPlan {
+    /// The plan as it is serialized: without history estimates, which change from run to
+    /// run, and with paths under `root` relative to it, so the same plan serializes the same
+    /// way on every machine.
+    pub fn new(task: String, commands: Vec<DryRunReport>, root: &Path) -> Self {
+        let relative = |path: PathBuf| match path.strip_prefix(root) {
+            Ok(rest) if rest.as_os_str().is_empty() => PathBuf::from("."),
+            Ok(rest) => rest.to_path_buf(),
+            Err(_) => path,
+        };
+        let commands = commands
+            .into_iter()
+            .map(|mut report| {
+                report.estimated_duration = None;
+                report.working_directory = relative(report.working_directory);
+                if let Some(path) = report.environment.get_mut("PATH") {
+                    let entries: Vec<PathBuf> = std::env::split_paths(path).map(relative).collect();
+                    if let Ok(joined) = std::env::join_paths(entries) {
+                        *path = joined.to_string_lossy().into_owned();
+                    }
+                }
+                report.side_effects = report
+                    .side_effects
+                    .into_iter()
+                    .map(|effect| effect.map_path(relative))
+                    .collect();
+                report
+            })
+            .collect();
+        Self {
+            version: DRY_RUN_PLAN_VERSION,
+            task,
+            commands,
+        }
+    }
+}
+
+impl SideEffect {
+    /// The file or directory the side effect is about, if any.
+    pub fn path(&self) -> Option<&std::path::Path> {
+        match self {
+            SideEffect::FileWrite(path)
+            | SideEffect::FileModify(path)
+            | SideEffect::FileDelete(path)
+            | SideEffect::DirectoryCreate(path)
+            | SideEffect::FileRead(path) => Some(path),
+            _ => None,
+        }
+    }
+
+    /// The same side effect, about `f(path)` instead of its path.
+    pub fn map_path(self, f: impl FnOnce(PathBuf) -> PathBuf) -> Self {
+        match self {
+            SideEffect::FileWrite(path) => SideEffect::FileWrite(f(path)),
+            SideEffect::FileModify(path) => SideEffect::FileModify(f(path)),
+            SideEffect::FileDelete(path) => SideEffect::FileDelete(f(path)),
+            SideEffect::DirectoryCreate(path) => SideEffect::DirectoryCreate(f(path)),
+            SideEffect::FileRead(path) => SideEffect::FileRead(f(path)),
+            other => other,
+        }
+    }
+}
+
 /// Report generated by dry-run execution containing details about what would be executed
-#[derive(Debug, Clone)]
+///
+/// Maps serialize with sorted keys and durations as humantime strings (`1m 30s`).
+/// `estimated_duration` is never serialized: it comes from run history, not the plan.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct DryRunReport {
+    /// The node id, as in `bodo graph --format json`
+    pub id: String,
+    /// The task the command belongs to, or `command` for an inline command
+    pub name: String,
     /// The command that would be executed
     pub command: String,
     /// Environment variables that would be set
+    #[serde(serialize_with = "sorted")]
     pub environment: HashMap<String, String>,
+    /// Where each environment variable came from (`global`, `script`, `task`, `cli`, ...)
+    #[serde(serialize_with = "sorted")]
+    pub environment_sources: HashMap<String, String>,
     /// Working directory for the command
     pub working_directory: PathBuf,
-    /// List of dependencies that would be resolved
+    /// Ids of the nodes that finish before this command starts
     pub dependencies: Vec<String>,
     /// Estimated time the command would take to run
+    #[serde(skip)]
     pub estimated_duration: Option<Duration>,
-    /// List of potential side effects from execution
+    /// Timeout the command would be killed after
+    #[serde(with = "humantime_option")]
+    pub timeout: Option<Duration>,
+    /// The concurrent group the command runs in, e.g. `release: concurrently (fail_fast, max 2)`
+    pub group: Option<String>,
+    /// Potential side effects, only filled in when side-effect analysis is requested
     pub side_effects: Vec<SideEffect>,
 }
 
+fn sorted<S: serde::Serializer>(
+    map: &HashMap<String, String>,
+    serializer: S,
+) -> std::result::Result<S::Ok, S::Error> {
+    serializer.collect_map(map.iter().collect::<std::collections::BTreeMap<_, _>>())
+}
+
+mod humantime_option {
+    use serde::{Deserialize, Deserializer, Serializer};
+    use std::time::Duration;
+
+    pub fn serialize<S: Serializer>(
+        duration: &Option<Duration>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match duration {
+            Some(duration) => {
+                // Whole milliseconds: timeouts are not more precise
+                let duration = Duration::from_millis(duration.as_millis() as u64);
+                serializer.serialize_some(&humantime::format_duration(duration).to_string())
+            }
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<Duration>, D::Error> {
+        Option::<String>::deserialize(deserializer)?
+            .map(|s| humantime::parse_duration(&s).map_err(serde::de::Error::custom))
+            .transpose()
+    }
+}
+
 /// Execution context for dry-run operations
 #[derive(Debug, Clone)]
 pub struct ExecutionContext {
+    /// The command to plan
+    pub command: String,
     /// Working directory for execution
     pub working_directory: PathBuf,
     /// Environment variables
@@ -137,6 +275,10 @@ impl PluginManager {
         for plugin in &mut self.plugins {
             plugin.on_graph_build(graph)?;
         }
+        // Plugins add edges of their own, e.g. for `concurrently`, which can close a cycle
+        if let Some(cycle) = graph.detect_cycle() {
+            return Err(BodoError::PluginError(graph.format_cycle_error(&cycle)));
+        }
         // on_after_run
         for plugin in &mut self.plugins {
             plugin.on_after_run(graph)?;
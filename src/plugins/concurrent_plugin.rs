@@ -1,7 +1,8 @@
 use crate::{
     errors::BodoError,
-    graph::{CommandData, ConcurrentGroupData, Graph, NodeKind},
+    graph::{CommandData, ConcurrentGroupData, EdgeKind, Graph, NodeId, NodeKind},
     plugin::Plugin,
+    task_resolver::resolve_task_ref,
     Result,
 };
 use serde_json::Value;
@@ -13,6 +14,14 @@ impl ConcurrentPlugin {
     pub fn new() -> Self {
         Self
     }
+
+    /// Resolve a concurrent task reference, reporting failures as plugin errors.
+    fn resolve(graph: &Graph, parent_id: NodeId, task_name: &str) -> Result<NodeId> {
+        resolve_task_ref(graph, parent_id, task_name).map_err(|e| match e {
+            BodoError::ValidationError(msg) => BodoError::PluginError(msg),
+            other => other,
+        })
+    }
 }
 
 impl Default for ConcurrentPlugin {
@@ -37,7 +46,7 @@ impl Plugin for ConcurrentPlugin {
     fn on_graph_build(&mut self, graph: &mut Graph) -> Result<()> {
         let mut nodes_to_process = Vec::new();
         for node in &graph.nodes {
-            if let NodeKind::Task(_) = &node.kind {
+            if let NodeKind::Task(task) = &node.kind {
                 if let Some(concurrent_meta) = node.metadata.get("concurrently") {
                     // If the concurrently metadata is an object, wrap it in an array.
                     let concur_deps: Vec<Value> = if concurrent_meta.trim().starts_with('{') {
@@ -59,68 +68,58 @@ impl Plugin for ConcurrentPlugin {
                         .metadata
                         .get("max_concurrent")
                         .and_then(|v| v.parse::<usize>().ok());
-                    nodes_to_process.push((node.id, concur_deps, fail_fast, max_concurrent));
+                    nodes_to_process.push(GroupSpec {
+                        parent_id: node.id,
+                        deps: concur_deps,
+                        fail_fast,
+                        max_concurrent,
+                        prefix_color: None,
+                        from_config: false,
+                    });
+                } else if !task.concurrently.is_empty() {
+                    // Dependencies declared in the script file itself.
+                    let options = &task.concurrently_options;
+                    nodes_to_process.push(GroupSpec {
+                        parent_id: node.id,
+                        deps: task
+                            .concurrently
+                            .iter()
+                            .map(serde_json::to_value)
+                            .collect::<std::result::Result<_, _>>()?,
+                        fail_fast: options.fail_fast.unwrap_or(true),
+                        max_concurrent: options.max_concurrent_tasks,
+                        prefix_color: options.prefix_color.clone(),
+                        from_config: true,
+                    });
                 }
             }
         }
-        for (parent_id, concur_deps, fail_fast, max_concurrent) in nodes_to_process {
+        for spec in nodes_to_process {
+            let parent_id = spec.parent_id;
+            let (working_dir, env, exec_paths) = match &graph.nodes[parent_id as usize].kind {
+                NodeKind::Task(task) => (
+                    task.working_dir.clone(),
+                    task.env.clone(),
+                    task.exec_paths.clone(),
+                ),
+                _ => Default::default(),
+            };
             let mut child_ids = Vec::new();
-            for dep in concur_deps {
+            for dep in spec.deps {
                 match dep {
                     Value::String(task_name) => {
-                        if let Some(&dep_id) = graph.task_registry.get(&task_name) {
-                            child_ids.push(dep_id);
-                        } else {
-                            // fallback search: check for keys that equal task_name or end with " {task_name}"
-                            let mut found = None;
-                            for (key, &id) in &graph.task_registry {
-                                if key.as_str() == task_name.as_str()
-                                    || key.ends_with(&format!(" {}", task_name))
-                                {
-                                    found = Some(id);
-                                    break;
-                                }
-                            }
-                            if let Some(dep_id) = found {
-                                child_ids.push(dep_id);
-                            } else {
-                                return Err(BodoError::PluginError(format!(
-                                    "Concurrent task not found: {}",
-                                    task_name
-                                )));
-                            }
-                        }
+                        child_ids.push(Self::resolve(graph, parent_id, &task_name)?);
                     }
                     Value::Object(cmd) => {
                         if let Some(Value::String(task_name)) = cmd.get("task") {
-                            if let Some(&dep_id) = graph.task_registry.get(task_name) {
-                                child_ids.push(dep_id);
-                            } else {
-                                // fallback search for task dependency in object format
-                                let mut found = None;
-                                for (key, &id) in &graph.task_registry {
-                                    if key.as_str() == task_name.as_str()
-                                        || key.ends_with(&format!(" {}", task_name))
-                                    {
-                                        found = Some(id);
-                                        break;
-                                    }
-                                }
-                                if let Some(dep_id) = found {
-                                    child_ids.push(dep_id);
-                                } else {
-                                    return Err(BodoError::PluginError(format!(
-                                        "Concurrent task not found: {}",
-                                        task_name
-                                    )));
-                                }
-                            }
+                            child_ids.push(Self::resolve(graph, parent_id, task_name)?);
                         } else if let Some(Value::String(command)) = cmd.get("command") {
                             let cmd_node_id = graph.add_node(NodeKind::Command(CommandData {
                                 raw_command: command.clone(),
                                 description: None,
-                                working_dir: None,
-                                env: std::collections::HashMap::new(),
+                                working_dir: working_dir.clone(),
+                                env: env.clone(),
+                                exec_paths: exec_paths.clone(),
                                 watch: None,
                             }));
                             child_ids.push(cmd_node_id);
@@ -140,14 +139,24 @@ impl Plugin for ConcurrentPlugin {
             }
             let group_node = NodeKind::ConcurrentGroup(ConcurrentGroupData {
                 child_nodes: child_ids.clone(),
-                fail_fast,
-                max_concurrent,
+                fail_fast: spec.fail_fast,
+                max_concurrent: spec.max_concurrent,
                 timeout_secs: None,
             });
             let group_id = graph.add_node(group_node);
-            graph.add_edge(parent_id, group_id)?;
+            if spec.from_config {
+                // Parallel output is only readable when every line says where it came from.
+                let group = &mut graph.nodes[group_id as usize];
+                group
+                    .metadata
+                    .insert("prefix_output".to_string(), "true".to_string());
+                if let Some(color) = spec.prefix_color {
+                    group.metadata.insert("prefix_color".to_string(), color);
+                }
+            }
+            graph.add_edge_of_kind(parent_id, group_id, EdgeKind::Concurrent)?;
             for child_id in child_ids {
-                graph.add_edge(group_id, child_id)?;
+                graph.add_edge_of_kind(group_id, child_id, EdgeKind::Concurrent)?;
                 let child_node = &mut graph.nodes[child_id as usize];
                 child_node
                     .metadata
@@ -157,3 +166,13 @@ impl Plugin for ConcurrentPlugin {
         Ok(())
     }
 }
+
+/// A concurrent group to be created for a task.
+struct GroupSpec {
+    parent_id: NodeId,
+    deps: Vec<Value>,
+    fail_fast: bool,
+    max_concurrent: Option<usize>,
+    prefix_color: Option<String>,
+    from_config: bool,
+}
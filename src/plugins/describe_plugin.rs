@@ -0,0 +1,214 @@
+use std::any::Any;
+
+use crate::{
+    arguments::task_help,
+    config::SandboxConfig,
+    diagnostic::display_path,
+    graph::{EdgeKind, Graph, NodeId, NodeKind},
+    plugin::{Plugin, PluginConfig},
+    plugins::graph_plugin::stable_ids,
+    sandbox::SANDBOX_KEY,
+    task_resolver::{SCRIPT_LINE_KEY, SCRIPT_PATH_KEY},
+    Result,
+};
+
+/// Everything about one task, for `bodo describe <task>` and `bodo <task> --help`: where it
+/// is defined, what it runs and with which arguments, environment and dependencies.
+pub fn describe_task(graph: &Graph, task: &str) -> Result<String> {
+    let id = graph
+        .task_registry
+        .get(task)
+        .copied()
+        .ok_or_else(|| graph.task_not_found(task))?;
+    let node = &graph.nodes[id as usize];
+    let NodeKind::Task(task_data) = &node.kind else {
+        return Err(graph.task_not_found(task));
+    };
+
+    let mut out = format!("{}\n", task);
+    if let Some(description) = &task_data.description {
+        out.push_str(&format!("{}\n", description));
+    }
+    out.push('\n');
+
+    let mut fields = Vec::new();
+    if let Some(path) = node.metadata.get(SCRIPT_PATH_KEY) {
+        let mut source = display_path(path.as_ref()).display().to_string();
+        if let Some(line) = node.metadata.get(SCRIPT_LINE_KEY) {
+            source.push_str(&format!(":{}", line));
+        }
+        fields.push(("Source", source));
+    }
+    if let Some(command) = &task_data.command {
+        fields.push(("Command", command.clone()));
+    }
+    if let Some(dir) = &task_data.working_dir {
+        fields.push(("Working dir", dir.clone()));
+    }
+    if let Some(timeout) = node.metadata.get("timeout") {
+        fields.push(("Timeout", timeout.clone()));
+    }
+    if let Some(policy) = node.metadata.get(SANDBOX_KEY) {
+        let policy: SandboxConfig = serde_json::from_str(policy)?;
+        let mut rules = vec![if policy.network {
+            "network allowed".to_string()
+        } else {
+            "no network".to_string()
+        }];
+        if !policy.writable.is_empty() {
+            rules.push(format!("writable: {}", policy.writable.join(", ")));
+        }
+        if !policy.read_only.is_empty() {
+            rules.push(format!("read-only: {}", policy.read_only.join(", ")));
+        }
+        fields.push(("Sandbox", rules.join("; ")));
+    }
+    for (label, value) in &fields {
+        out.push_str(&format!("{:<13}{}\n", format!("{}:", label), value));
+    }
+    if !fields.is_empty() {
+        out.push('\n');
+    }
+
+    out.push_str(&task_help(task, None, &task_data.arguments));
+
+    // PATH is built from the exec paths, which are listed on their own
+    let env_sources = node.env_sources();
+    let mut env: Vec<(&String, &String)> = task_data
+        .env
+        .iter()
+        .filter(|(key, _)| {
+            !(*key == "PATH" && env_sources.get(*key).map(String::as_str) == Some("exec_paths"))
+        })
+        .collect();
+    env.sort();
+    section(
+        &mut out,
+        "Environment",
+        env.iter()
+            .map(|(key, value)| with_source(format!("{}={}", key, value), env_sources.get(*key)))
+            .collect(),
+    );
+    let path_sources = node.exec_path_sources();
+    section(
+        &mut out,
+        "Exec paths",
+        task_data
+            .exec_paths
+            .iter()
+            .map(|path| with_source(path.clone(), path_sources.get(path)))
+            .collect(),
+    );
+
+    let ids = stable_ids(graph);
+    let label = |dep: NodeId| match &graph.nodes[dep as usize].kind {
+        NodeKind::Task(_) => ids[&dep].clone(),
+        NodeKind::Command(command) => format!("command: {}", command.raw_command),
+        NodeKind::ConcurrentGroup(_) => ids[&dep].clone(),
+    };
+    let deps = |kind| -> Vec<String> {
+        graph
+            .dependencies_of(id, kind)
+            .into_iter()
+            .flat_map(|dep| match &graph.nodes[dep as usize].kind {
+                NodeKind::ConcurrentGroup(group) => {
+                    group.child_nodes.iter().map(|&c| label(c)).collect()
+                }
+                _ => vec![label(dep)],
+            })
+            .collect()
+    };
+    section(&mut out, "Pre dependencies", deps(EdgeKind::PreDep));
+    let mut concurrently = "Concurrently".to_string();
+    let options = &task_data.concurrently_options;
+    let mut details = Vec::new();
+    if let Some(fail_fast) = options.fail_fast {
+        details.push(if fail_fast {
+            "fail_fast".to_string()
+        } else {
+            "no fail_fast".to_string()
+        });
+    }
+    if let Some(max) = options.max_concurrent_tasks {
+        details.push(format!("max {}", max));
+    }
+    if !details.is_empty() {
+        concurrently.push_str(&format!(" ({})", details.join(", ")));
+    }
+    section(&mut out, &concurrently, deps(EdgeKind::Concurrent));
+    section(&mut out, "Post dependencies", deps(EdgeKind::PostDep));
+
+    if let Some(watch) = &task_data.watch {
+        let mut lines = vec![format!("patterns: {}", watch.patterns.join(", "))];
+        if !watch.ignore_patterns.is_empty() {
+            lines.push(format!("ignore:   {}", watch.ignore_patterns.join(", ")));
+        }
+        lines.push(format!("debounce: {}ms", watch.debounce_ms));
+        if watch.auto_watch {
+            lines.push("auto_watch: true".to_string());
+        }
+        section(&mut out, "Watch", lines);
+    }
+    Ok(out)
+}
+
+/// A titled, indented list, left out when empty.
+fn section(out: &mut String, title: &str, lines: Vec<String>) {
+    if lines.is_empty() {
+        return;
+    }
+    out.push_str(&format!("\n{}:\n", title));
+    for line in lines {
+        out.push_str(&format!("  {}\n", line));
+    }
+}
+
+fn with_source(value: String, source: Option<&String>) -> String {
+    match source {
+        Some(source) => format!("{} ({})", value, source),
+        None => value,
+    }
+}
+
+/// Prints [`describe_task`] for the task in the `task` option. It runs after the other
+/// plugins, so the environment and dependencies shown are the resolved ones.
+#[derive(Default)]
+pub struct DescribePlugin {
+    task_name: Option<String>,
+}
+
+impl DescribePlugin {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Plugin for DescribePlugin {
+    fn name(&self) -> &'static str {
+        "DescribePlugin"
+    }
+
+    fn priority(&self) -> i32 {
+        0
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn on_init(&mut self, config: &PluginConfig) -> Result<()> {
+        self.task_name = config
+            .options
+            .as_ref()
+            .and_then(|options| options.get("task"))
+            .and_then(|task| task.as_str())
+            .map(String::from);
+        Ok(())
+    }
+
+    fn on_graph_build(&mut self, graph: &mut Graph) -> Result<()> {
+        let task = self.task_name.as_deref().unwrap_or("default");
+        print!("{}", describe_task(graph, task)?);
+        Ok(())
+    }
+}
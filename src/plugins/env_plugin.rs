@@ -55,18 +55,22 @@ impl Plugin for EnvPlugin {
     fn on_graph_build(&mut self, graph: &mut Graph) -> Result<()> {
         if let Some(ref extra_env) = self.global_env {
             for node in &mut graph.nodes {
-                match &mut node.kind {
-                    NodeKind::Task(task_data) => {
-                        for (k, v) in extra_env {
-                            task_data.env.insert(k.clone(), v.clone());
-                        }
-                    }
-                    NodeKind::Command(cmd_data) => {
-                        for (k, v) in extra_env {
-                            cmd_data.env.insert(k.clone(), v.clone());
-                        }
-                    }
-                    NodeKind::ConcurrentGroup(_) => {}
+                // Values given on the command line always win.
+                let sources = node.env_sources();
+                let extra: Vec<(&String, &String)> = extra_env
+                    .iter()
+                    .filter(|(k, _)| sources.get(*k).map(String::as_str) != Some("cli"))
+                    .collect();
+                let env = match &mut node.kind {
+                    NodeKind::Task(task_data) => &mut task_data.env,
+                    NodeKind::Command(cmd_data) => &mut cmd_data.env,
+                    NodeKind::ConcurrentGroup(_) => continue,
+                };
+                for (k, v) in &extra {
+                    env.insert((*k).clone(), (*v).clone());
+                }
+                for (k, _) in extra {
+                    node.set_env_source(k, "plugin");
                 }
             }
         }
@@ -1,19 +1,97 @@
 use std::any::Any;
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use std::path::{Path, PathBuf};
-use std::time::Duration;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
 
 use crate::{
+    config::SandboxConfig,
     errors::{BodoError, Result},
-    graph::{Graph, NodeKind},
-    plugin::{DryRunReport, DryRunnable, ExecutionContext, Plugin, PluginConfig, SideEffect},
-    process::ProcessManager,
-    sandbox::Sandbox,
+    graph::{ConcurrentGroupData, EdgeKind, Graph, NodeId, NodeKind},
+    history::{format_secs, History},
+    plugin::{
+        DryRunPlan, DryRunReport, DryRunnable, ExecutionContext, Plugin, PluginConfig, SideEffect,
+    },
+    plugins::graph_plugin::stable_ids,
+    process::{ProcessManager, SpawnOptions},
+    sandbox::{Sandbox, SANDBOX_KEY},
+    task_resolver::project_root,
 };
 
 pub struct ExecutionPlugin {
     pub task_name: Option<String>,
     pub dry_run: bool,
+    /// With `dry_run`, run each command in a sandbox to find its side effects
+    pub side_effect_analysis: bool,
+    /// How the dry-run plan is printed
+    pub plan_format: PlanFormat,
+    /// Run every command in a sandbox, not only tasks with a `sandbox` policy
+    pub sandbox: bool,
+    /// Durations of previous runs: recorded after each command, used for estimates
+    history: Option<Mutex<History>>,
+    /// Set from outside (e.g. by watch mode) to stop every running process
+    cancel: Arc<AtomicBool>,
+    /// Run each process in its own process group so cancelling also stops its children
+    cancellable: bool,
+}
+
+/// How `--dry-run` prints its plan.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PlanFormat {
+    #[default]
+    Text,
+    Json,
+    Yaml,
+}
+
+impl FromStr for PlanFormat {
+    type Err = BodoError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "text" => Ok(Self::Text),
+            "json" => Ok(Self::Json),
+            "yaml" => Ok(Self::Yaml),
+            other => Err(BodoError::ValidationError(format!(
+                "unknown dry-run format '{}'; expected one of: text, json, yaml",
+                other
+            ))),
+        }
+    }
+}
+
+/// Reports collected while walking the graph for a dry run.
+struct PlanState {
+    ids: HashMap<NodeId, String>,
+    visited: std::collections::HashSet<usize>,
+    reports: Vec<DryRunReport>,
+}
+
+/// How a subtree of the graph is run: whether a failure stops everything sharing `cancel`.
+struct RunControl {
+    cancel: Arc<AtomicBool>,
+    fail_fast: bool,
+    progress: Arc<RunProgress>,
+}
+
+/// Shared by every subtree of a run: the stable id of each node and the estimated
+/// duration of the commands that have not finished yet.
+struct RunProgress {
+    ids: HashMap<NodeId, String>,
+    remaining: Mutex<HashMap<String, Duration>>,
+}
+
+impl RunControl {
+    fn process_manager(&self) -> ProcessManager {
+        ProcessManager::new(self.fail_fast).with_cancel_flag(self.cancel.clone())
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.cancel.load(Ordering::SeqCst)
+    }
 }
 
 impl Default for ExecutionPlugin {
@@ -27,9 +105,42 @@ impl ExecutionPlugin {
         Self {
             task_name: None,
             dry_run: false,
+            side_effect_analysis: false,
+            plan_format: PlanFormat::Text,
+            sandbox: false,
+            history: None,
+            cancel: Arc::new(AtomicBool::new(false)),
+            cancellable: false,
         }
     }
 
+    /// Print the dry-run plan as text, JSON or YAML.
+    pub fn with_plan_format(mut self, format: PlanFormat) -> Self {
+        self.plan_format = format;
+        self
+    }
+
+    /// Record how long each command takes in `history` and estimate durations from it.
+    pub fn with_history(mut self, history: History) -> Self {
+        self.history = Some(Mutex::new(history));
+        self
+    }
+
+    /// Stop the run as soon as `cancel` is set; running processes are killed.
+    pub fn with_cancel_flag(mut self, cancel: Arc<AtomicBool>) -> Self {
+        self.cancel = cancel;
+        self.cancellable = true;
+        self
+    }
+
+    /// Stop the run when `interrupt` is set, e.g. on Ctrl-C. Unlike
+    /// [`Self::with_cancel_flag`], processes stay in bodo's process group unless a timeout
+    /// puts them in their own; those are terminated with everything they spawned.
+    pub fn with_interrupt_flag(mut self, interrupt: Arc<AtomicBool>) -> Self {
+        self.cancel = interrupt;
+        self
+    }
+
     /// Changed the visibility of the method to `pub`
     pub fn get_prefix_settings(
         &self,
@@ -103,6 +214,14 @@ impl ExecutionPlugin {
         result
     }
 
+    /// The median of the previous runs of node `id`, or a guess from the command.
+    pub fn estimate(&self, id: &str, command: &str) -> Duration {
+        self.history
+            .as_ref()
+            .and_then(|history| history.lock().unwrap().estimate(id))
+            .unwrap_or_else(|| self.estimate_duration(command))
+    }
+
     /// Estimate command execution duration based on command patterns
     pub fn estimate_duration(&self, command: &str) -> Duration {
         // Simple heuristics for estimation
@@ -184,21 +303,21 @@ impl ExecutionPlugin {
         if command.contains("rm ") || command.contains("rm -") {
             // File deletion detected
             if let Some(path) = self.extract_file_path_from_rm_command(command) {
-                effects.push(SideEffect::FileWrite(working_dir.join(path))); // Treat deletion as write
+                effects.push(SideEffect::FileDelete(working_dir.join(path)));
             }
         }
 
         if command.contains("mkdir") {
             // Directory creation detected
             if let Some(path) = self.extract_file_path_from_mkdir_command(command) {
-                effects.push(SideEffect::FileWrite(working_dir.join(path)));
+                effects.push(SideEffect::DirectoryCreate(working_dir.join(path)));
             }
         }
 
         if command.contains("sed -i") || command.contains("awk") {
             // In-place file modification detected
             if let Some(path) = self.extract_file_path_from_sed_command(command) {
-                effects.push(SideEffect::FileWrite(working_dir.join(path)));
+                effects.push(SideEffect::FileModify(working_dir.join(path)));
             }
         }
 
@@ -278,17 +397,24 @@ impl ExecutionPlugin {
 
 impl DryRunnable for ExecutionPlugin {
     fn dry_run(&self, context: &ExecutionContext) -> Result<DryRunReport> {
-        let command = "mock-command".to_string(); // Will be replaced with actual command
-        let estimated_duration = self.estimate_duration(&command);
-        let side_effects =
-            self.analyze_side_effects(&command, &context.working_directory, &context.environment);
+        let command = self.expand_env_vars(&context.command, &context.environment);
+        let side_effects = if self.side_effect_analysis {
+            self.analyze_side_effects(&command, &context.working_directory, &context.environment)
+        } else {
+            vec![]
+        };
 
         Ok(DryRunReport {
+            id: "command".to_string(),
+            name: "command".to_string(),
+            estimated_duration: Some(self.estimate_duration(&command)),
             command,
             environment: context.environment.clone(),
+            environment_sources: HashMap::new(),
             working_directory: context.working_directory.clone(),
-            dependencies: vec![], // Will be populated with actual dependencies
-            estimated_duration: Some(estimated_duration),
+            dependencies: vec![],
+            timeout: None,
+            group: None,
             side_effects,
         })
     }
@@ -313,6 +439,12 @@ impl Plugin for ExecutionPlugin {
             if let Some(task) = options.get("task").and_then(|v| v.as_str()) {
                 self.task_name = Some(task.to_string());
             }
+            if let Some(analyze) = options.get("side_effects").and_then(|v| v.as_bool()) {
+                self.side_effect_analysis = analyze;
+            }
+            if let Some(sandbox) = options.get("sandbox").and_then(|v| v.as_bool()) {
+                self.sandbox = sandbox;
+            }
         }
         Ok(())
     }
@@ -327,10 +459,19 @@ impl Plugin for ExecutionPlugin {
         let task_id = *graph
             .task_registry
             .get(&task_name)
-            .ok_or_else(|| BodoError::TaskNotFound(task_name.clone()))?;
+            .ok_or_else(|| graph.task_not_found(&task_name))?;
+
+        if graph.nodes[task_id as usize]
+            .metadata
+            .get("watch_managed")
+            .is_some_and(|v| v == "true")
+        {
+            // Watch mode starts (and restarts) this task itself.
+            return Ok(());
+        }
 
         if self.dry_run {
-            // Handle dry-run mode with enhanced analysis
+            // Print the plan; nothing is spawned
             self.execute_dry_run(graph, task_id as usize)?;
         } else {
             // Handle normal execution
@@ -343,119 +484,226 @@ impl Plugin for ExecutionPlugin {
 
 impl ExecutionPlugin {
     fn execute_dry_run(&self, graph: &Graph, task_id: usize) -> Result<()> {
-        let mut visited = std::collections::HashSet::new();
-        let mut dry_run_reports = Vec::new();
-
-        self.collect_dry_run_reports(task_id, graph, &mut visited, &mut dry_run_reports)?;
-
-        // Display dry-run results
-        self.display_dry_run_results(&dry_run_reports)?;
-
+        let reports = self.plan(graph, task_id)?;
+        if self.plan_format == PlanFormat::Text {
+            return self.display_dry_run_results(&reports);
+        }
+        let plan = DryRunPlan::new(
+            self.task_name.clone().unwrap_or_default(),
+            reports,
+            &project_root(),
+        );
+        match self.plan_format {
+            PlanFormat::Json => println!("{}", serde_json::to_string_pretty(&plan)?),
+            _ => print!("{}", serde_yaml::to_string(&plan)?),
+        }
         Ok(())
     }
 
+    /// The commands running `task_id` would spawn, in execution order, without spawning any.
+    ///
+    /// Side effects are only analysed, in a sandbox, when `side_effect_analysis` is set.
+    pub fn plan(&self, graph: &Graph, task_id: usize) -> Result<Vec<DryRunReport>> {
+        let mut state = PlanState {
+            ids: stable_ids(graph),
+            visited: std::collections::HashSet::new(),
+            reports: Vec::new(),
+        };
+        self.collect_dry_run_reports(task_id, graph, None, &mut state);
+        Ok(state.reports)
+    }
+
     fn collect_dry_run_reports(
         &self,
         node_id: usize,
         graph: &Graph,
-        visited: &mut std::collections::HashSet<usize>,
-        reports: &mut Vec<DryRunReport>,
-    ) -> Result<()> {
-        if visited.contains(&node_id) {
-            return Ok(());
+        group: Option<&str>,
+        state: &mut PlanState,
+    ) {
+        if !state.visited.insert(node_id) {
+            return;
         }
-        visited.insert(node_id);
 
-        let node = &graph.nodes[node_id];
-        match &node.kind {
-            NodeKind::Task(task_data) => {
-                // Process pre dependencies
-                for edge in &graph.edges {
-                    if edge.to == node_id as u64 {
-                        self.collect_dry_run_reports(edge.from as usize, graph, visited, reports)?;
+        match &graph.nodes[node_id].kind {
+            NodeKind::Task(_) => {
+                // Pre dependencies and concurrent groups, in declared order
+                for kind in [EdgeKind::PreDep, EdgeKind::Concurrent] {
+                    for dep_id in graph.dependencies_of(node_id as NodeId, kind) {
+                        self.collect_dry_run_reports(dep_id as usize, graph, group, state);
                     }
                 }
-
-                // Generate dry-run report for this task
-                if let Some(cmd) = &task_data.command {
-                    let expanded_cmd = self.expand_env_vars(cmd, &task_data.env);
-                    let working_dir = task_data
-                        .working_dir
-                        .as_ref()
-                        .map(PathBuf::from)
-                        .unwrap_or_else(|| std::env::current_dir().unwrap_or_default());
-
-                    let report = DryRunReport {
-                        command: expanded_cmd.clone(),
-                        environment: task_data.env.clone(),
-                        working_directory: working_dir.clone(),
-                        dependencies: vec![], // Could be enhanced to list actual dependencies
-                        estimated_duration: Some(self.estimate_duration(&expanded_cmd)),
-                        side_effects: self.analyze_side_effects(
-                            &expanded_cmd,
-                            &working_dir,
-                            &task_data.env,
-                        ),
-                    };
-                    reports.push(report);
+                if let Some(report) = self.plan_command(graph, node_id, group, &state.ids) {
+                    state.reports.push(report);
+                }
+                for dep_id in graph.dependencies_of(node_id as NodeId, EdgeKind::PostDep) {
+                    self.collect_dry_run_reports(dep_id as usize, graph, group, state);
                 }
             }
-            NodeKind::Command(cmd_data) => {
-                let expanded_cmd = self.expand_env_vars(&cmd_data.raw_command, &cmd_data.env);
-                let working_dir = cmd_data
-                    .working_dir
-                    .as_ref()
-                    .map(PathBuf::from)
-                    .unwrap_or_else(|| std::env::current_dir().unwrap_or_default());
-
-                let report = DryRunReport {
-                    command: expanded_cmd.clone(),
-                    environment: cmd_data.env.clone(),
-                    working_directory: working_dir.clone(),
-                    dependencies: vec![],
-                    estimated_duration: Some(self.estimate_duration(&expanded_cmd)),
-                    side_effects: self.analyze_side_effects(
-                        &expanded_cmd,
-                        &working_dir,
-                        &cmd_data.env,
-                    ),
-                };
-                reports.push(report);
+            NodeKind::Command(_) => {
+                if let Some(report) = self.plan_command(graph, node_id, group, &state.ids) {
+                    state.reports.push(report);
+                }
             }
             NodeKind::ConcurrentGroup(group_data) => {
-                // Handle concurrent group in dry-run
+                let label = Self::group_label(graph, node_id as NodeId, group_data);
                 for &child_id in &group_data.child_nodes {
-                    self.collect_dry_run_reports(child_id as usize, graph, visited, reports)?;
+                    self.collect_dry_run_reports(child_id as usize, graph, Some(&label), state);
                 }
             }
         }
-        Ok(())
+    }
+
+    /// The report for a Task or Command node's own command, if it has one.
+    fn plan_command(
+        &self,
+        graph: &Graph,
+        node_id: usize,
+        group: Option<&str>,
+        ids: &HashMap<NodeId, String>,
+    ) -> Option<DryRunReport> {
+        let node = &graph.nodes[node_id];
+        let (name, cmd, working_dir, env) = match &node.kind {
+            NodeKind::Task(task_data) => (
+                task_data.name.as_str(),
+                task_data.command.as_ref()?,
+                task_data.working_dir.as_ref(),
+                &task_data.env,
+            ),
+            NodeKind::Command(cmd_data) => (
+                "command",
+                &cmd_data.raw_command,
+                cmd_data.working_dir.as_ref(),
+                &cmd_data.env,
+            ),
+            NodeKind::ConcurrentGroup(_) => return None,
+        };
+        let expanded_cmd = self.expand_env_vars(cmd, env);
+        let working_dir = working_dir
+            .map(PathBuf::from)
+            .unwrap_or_else(|| std::env::current_dir().unwrap_or_default());
+        let side_effects = if self.side_effect_analysis {
+            self.analyze_side_effects(&expanded_cmd, &working_dir, env)
+        } else {
+            vec![]
+        };
+        let id = ids[&(node_id as NodeId)].clone();
+        Some(DryRunReport {
+            estimated_duration: Some(self.estimate(&id, &expanded_cmd)),
+            id,
+            name: name.to_string(),
+            command: expanded_cmd,
+            environment: env.clone(),
+            environment_sources: node.env_sources(),
+            working_directory: working_dir,
+            dependencies: Self::plan_dependencies(graph, node_id as NodeId, ids),
+            timeout: Self::get_timeout(node),
+            group: group.map(String::from),
+            side_effects,
+        })
+    }
+
+    /// What has to finish before `node_id` starts: its pre dependencies, the children of
+    /// its concurrent groups, and the task it is a post dependency of.
+    fn plan_dependencies(
+        graph: &Graph,
+        node_id: NodeId,
+        ids: &HashMap<NodeId, String>,
+    ) -> Vec<String> {
+        let mut pending: VecDeque<NodeId> = graph
+            .dependencies_of(node_id, EdgeKind::PreDep)
+            .into_iter()
+            .chain(graph.dependencies_of(node_id, EdgeKind::Concurrent))
+            .collect();
+        let mut dependencies = Vec::new();
+        while let Some(dep_id) = pending.pop_front() {
+            match &graph.nodes[dep_id as usize].kind {
+                NodeKind::ConcurrentGroup(group_data) => {
+                    pending.extend(group_data.child_nodes.iter().copied())
+                }
+                _ => dependencies.push(ids[&dep_id].clone()),
+            }
+        }
+        dependencies.extend(
+            graph
+                .edges
+                .iter()
+                .filter(|edge| edge.to == node_id && edge.kind == EdgeKind::PostDep)
+                .map(|edge| ids[&edge.from].clone()),
+        );
+        dependencies
+    }
+
+    /// `owner: concurrently (fail_fast, max N)`, naming the task that declared the group.
+    fn group_label(graph: &Graph, group_id: NodeId, group_data: &ConcurrentGroupData) -> String {
+        let owner = graph
+            .edges
+            .iter()
+            .find(|edge| edge.to == group_id && edge.kind == EdgeKind::Concurrent)
+            .and_then(|edge| match &graph.nodes[edge.from as usize].kind {
+                NodeKind::Task(task_data) => Some(task_data.name.clone()),
+                _ => None,
+            })
+            .unwrap_or_else(|| "group".to_string());
+        let mut options = vec![if group_data.fail_fast {
+            "fail_fast".to_string()
+        } else {
+            "no fail_fast".to_string()
+        }];
+        if let Some(max) = group_data.max_concurrent {
+            options.push(format!("max {}", max));
+        }
+        format!("{}: concurrently ({})", owner, options.join(", "))
     }
 
     pub fn display_dry_run_results(&self, reports: &[DryRunReport]) -> Result<()> {
-        println!("🔍 Dry Run Results (Enhanced with Sandbox Analysis)");
-        println!("==================================================");
+        println!("🔍 Dry Run Plan");
+        println!("===============");
         println!();
 
         let mut total_duration = Duration::new(0, 0);
 
         for (i, report) in reports.iter().enumerate() {
-            println!("📋 Command {}: {}", i + 1, report.command);
+            println!("📋 Command {} ({}): {}", i + 1, report.name, report.command);
+            if let Some(group) = &report.group {
+                println!("🔀 Runs in parallel: {}", group);
+            }
             println!(
                 "📁 Working Directory: {}",
                 report.working_directory.display()
             );
 
-            if !report.environment.is_empty() {
+            if let Some(path) = report.environment.get("PATH") {
+                println!("🛤️  PATH:");
+                for entry in path.split(':').filter(|entry| !entry.is_empty()) {
+                    println!("   {}", entry);
+                }
+            }
+
+            let mut keys: Vec<&String> = report
+                .environment
+                .keys()
+                .filter(|key| *key != "PATH")
+                .collect();
+            if !keys.is_empty() {
                 println!("🌍 Environment Variables:");
-                for (key, value) in &report.environment {
-                    println!("   {}={}", key, value);
+                keys.sort();
+                for key in keys {
+                    match report.environment_sources.get(key) {
+                        Some(source) => {
+                            println!("   {}={} ({})", key, report.environment[key], source)
+                        }
+                        None => println!("   {}={}", key, report.environment[key]),
+                    }
                 }
             }
 
+            if let Some(timeout) = report.timeout {
+                println!("⏰ Timeout: {}", humantime::format_duration(timeout));
+            }
+
             if let Some(duration) = report.estimated_duration {
                 total_duration += duration;
-                println!("⏱️  Estimated Duration: {:?}", duration);
+                println!("⏱️  Estimated Duration: {}", format_secs(duration));
             }
 
             if !report.side_effects.is_empty() {
@@ -465,6 +713,15 @@ impl ExecutionPlugin {
                         SideEffect::FileWrite(path) => {
                             println!("   📝 Write to: {}", path.display())
                         }
+                        SideEffect::FileModify(path) => {
+                            println!("   ✏️  Modify: {}", path.display())
+                        }
+                        SideEffect::FileDelete(path) => {
+                            println!("   🗑️  Delete: {}", path.display())
+                        }
+                        SideEffect::DirectoryCreate(path) => {
+                            println!("   📂 Create directory: {}", path.display())
+                        }
                         SideEffect::FileRead(path) => {
                             println!("   📖 Read from: {}", path.display())
                         }
@@ -477,7 +734,7 @@ impl ExecutionPlugin {
                         }
                     }
                 }
-            } else {
+            } else if self.side_effect_analysis {
                 println!("✅ No side effects detected");
             }
             println!();
@@ -486,132 +743,288 @@ impl ExecutionPlugin {
         println!("📊 Summary");
         println!("----------");
         println!("Total Commands: {}", reports.len());
-        println!("Estimated Total Duration: {:?}", total_duration);
+        println!("Estimated Total Duration: {}", format_secs(total_duration));
         println!();
-        println!("✅ No commands were actually executed (dry-run mode)");
-        println!("🔒 All analysis performed in isolated sandbox environment");
+        println!("✅ No commands were executed (dry-run mode)");
+        if self.side_effect_analysis {
+            println!("🔒 Side effects were analysed by running each command in a sandbox");
+        }
 
         Ok(())
     }
 
     fn execute_normal(&self, graph: &Graph, task_id: usize) -> Result<()> {
         let mut visited = std::collections::HashSet::new();
-        let mut pm = ProcessManager::new(true);
-
-        #[allow(clippy::type_complexity)]
-        fn run_node(
-            node_id: usize,
-            graph: &Graph,
-            pm: &mut ProcessManager,
-            visited: &mut std::collections::HashSet<usize>,
-            expand_env_vars_fn: &dyn Fn(&str, &HashMap<String, String>) -> String,
-            get_prefix_settings_fn: &dyn Fn(
-                &crate::graph::Node,
-            ) -> (bool, Option<String>, Option<String>),
-        ) -> Result<()> {
-            if visited.contains(&node_id) {
-                return Ok(());
-            }
-            visited.insert(node_id);
-
-            let node = &graph.nodes[node_id];
-            match &node.kind {
-                NodeKind::Task(task_data) => {
-                    // Run pre dependencies
-                    for edge in &graph.edges {
-                        if edge.to == node_id as u64 {
-                            run_node(
-                                edge.from as usize,
-                                graph,
-                                pm,
-                                visited,
-                                expand_env_vars_fn,
-                                get_prefix_settings_fn,
-                            )?;
-                        }
-                    }
-                    // Execute the task command
-                    if let Some(cmd) = &task_data.command {
-                        let expanded_cmd = expand_env_vars_fn(cmd, &task_data.env);
-                        let (prefix_enabled, prefix_label, prefix_color) =
-                            get_prefix_settings_fn(node);
-                        pm.spawn_command(
-                            &task_data.name,
-                            &expanded_cmd,
-                            prefix_enabled,
-                            prefix_label,
-                            prefix_color,
-                            task_data.working_dir.as_deref(),
-                        )?;
-                    }
+        let control = RunControl {
+            cancel: self.cancel.clone(),
+            fail_fast: true,
+            progress: Arc::new(self.run_progress(graph, task_id)?),
+        };
+        let result = self.run_node(task_id, graph, &mut visited, &control);
+        if let Some(history) = &self.history {
+            let history = history.lock().unwrap();
+            if let Err(e) = history.save() {
+                log::warn!("Failed to save {}: {}", history.path().display(), e);
+            }
+        }
+        result
+    }
+
+    /// Ids for the run, and the history estimate of every planned command that has one.
+    fn run_progress(&self, graph: &Graph, task_id: usize) -> Result<RunProgress> {
+        let mut remaining = HashMap::new();
+        if let Some(history) = &self.history {
+            let reports = self.plan(graph, task_id)?;
+            let history = history.lock().unwrap();
+            for report in reports {
+                if let Some(estimate) = history.estimate(&report.id) {
+                    remaining.insert(report.id, estimate);
                 }
-                NodeKind::Command(cmd_data) => {
-                    let expanded_cmd = expand_env_vars_fn(&cmd_data.raw_command, &cmd_data.env);
-                    let (prefix_enabled, prefix_label, prefix_color) = get_prefix_settings_fn(node);
-                    pm.spawn_command(
-                        "command",
-                        &expanded_cmd,
-                        prefix_enabled,
-                        prefix_label,
-                        prefix_color,
-                        cmd_data.working_dir.as_deref(),
-                    )?;
+            }
+        }
+        if !remaining.is_empty() {
+            log::info!(
+                "⏳ Estimated duration: {}",
+                format_secs(remaining.values().sum())
+            );
+        }
+        Ok(RunProgress {
+            ids: stable_ids(graph),
+            remaining: Mutex::new(remaining),
+        })
+    }
+
+    /// Spawn the node's command, if it has one, wait for it and record how long it took.
+    fn run_command(
+        &self,
+        graph: &Graph,
+        node: &crate::graph::Node,
+        control: &RunControl,
+    ) -> Result<()> {
+        let has_command = match &node.kind {
+            NodeKind::Task(task_data) => task_data.command.is_some(),
+            NodeKind::Command(_) => true,
+            NodeKind::ConcurrentGroup(_) => false,
+        };
+        if !has_command {
+            return Ok(());
+        }
+        let mut pm = control.process_manager();
+        self.spawn_node(graph, node, &mut pm)?;
+        let started = Instant::now();
+        pm.run_concurrently()?;
+        self.record_run(node.id, started.elapsed(), &control.progress);
+        Ok(())
+    }
+
+    /// Warn when the run was much slower than usual, add it to the history and report
+    /// what is left.
+    fn record_run(&self, node_id: NodeId, took: Duration, progress: &RunProgress) {
+        let (Some(history), Some(id)) = (&self.history, progress.ids.get(&node_id)) else {
+            return;
+        };
+        {
+            let mut history = history.lock().unwrap();
+            if let Some(regression) = history.regression(id, took) {
+                log::warn!("⚠️  '{}' {}", id, regression);
+            }
+            history.record(id, took);
+        }
+        let mut remaining = progress.remaining.lock().unwrap();
+        remaining.remove(id);
+        if !remaining.is_empty() {
+            log::info!(
+                "⏳ '{}' finished in {}, estimated remaining: {}",
+                id,
+                format_secs(took),
+                format_secs(remaining.values().sum())
+            );
+        }
+    }
+
+    /// Build the spawn settings for a node: prefix metadata, working directory and environment.
+    pub fn spawn_options(
+        &self,
+        node: &crate::graph::Node,
+        working_dir: Option<&String>,
+        env: &HashMap<String, String>,
+    ) -> SpawnOptions {
+        let (prefix_enabled, prefix_label, prefix_color) = self.get_prefix_settings(node);
+        SpawnOptions {
+            prefix_enabled,
+            prefix_label,
+            prefix_color,
+            working_dir: working_dir.cloned(),
+            env: env.clone(),
+            timeout: Self::get_timeout(node),
+            own_group: self.cancellable,
+            sandbox: None,
+        }
+    }
+
+    /// The sandbox policy a node runs under: its own, or for an inline command the one of
+    /// the task declaring it. With `--sandbox`, everything else gets the default policy.
+    pub fn sandbox_policy(&self, graph: &Graph, node_id: NodeId) -> Result<Option<SandboxConfig>> {
+        let mut id = node_id;
+        loop {
+            let node = &graph.nodes[id as usize];
+            if let Some(policy) = node.metadata.get(SANDBOX_KEY) {
+                return Ok(Some(serde_json::from_str(policy)?));
+            }
+            if matches!(node.kind, NodeKind::Task(_)) {
+                break;
+            }
+            match graph.edges.iter().find(|edge| edge.to == id) {
+                Some(edge) => id = edge.from,
+                None => break,
+            }
+        }
+        Ok(self.sandbox.then(SandboxConfig::default))
+    }
+
+    /// The task timeout, from the task's `timeout` or else as set by `TimeoutPlugin`.
+    /// `timeout` comes first: `timeout_seconds` is rounded down to whole seconds.
+    pub fn get_timeout(node: &crate::graph::Node) -> Option<Duration> {
+        if let Some(timeout) = node
+            .metadata
+            .get("timeout")
+            .and_then(|t| humantime::parse_duration(t).ok())
+        {
+            return Some(timeout);
+        }
+        node.metadata
+            .get("timeout_seconds")
+            .and_then(|seconds| seconds.parse().ok())
+            .map(Duration::from_secs)
+    }
+
+    /// Spawn the command of a Task or Command node into `pm`, if it has one.
+    fn spawn_node(
+        &self,
+        graph: &Graph,
+        node: &crate::graph::Node,
+        pm: &mut ProcessManager,
+    ) -> Result<()> {
+        match &node.kind {
+            NodeKind::Task(task_data) => {
+                if let Some(cmd) = &task_data.command {
+                    let expanded_cmd = self.expand_env_vars(cmd, &task_data.env);
+                    let mut options =
+                        self.spawn_options(node, task_data.working_dir.as_ref(), &task_data.env);
+                    options.sandbox = self.sandbox_policy(graph, node.id)?;
+                    pm.spawn_with_options(&task_data.name, &expanded_cmd, options)?;
                 }
-                NodeKind::ConcurrentGroup(group_data) => {
-                    // Handle concurrent group execution
-                    let mut group_pm = ProcessManager::new(group_data.fail_fast);
-                    for &child_id in &group_data.child_nodes {
-                        let child_node = &graph.nodes[child_id as usize];
-                        match &child_node.kind {
-                            NodeKind::Task(task_data) => {
-                                if let Some(cmd) = &task_data.command {
-                                    let expanded_cmd = expand_env_vars_fn(cmd, &task_data.env);
-                                    let (prefix_enabled, prefix_label, prefix_color) =
-                                        get_prefix_settings_fn(child_node);
-                                    group_pm.spawn_command(
-                                        &task_data.name,
-                                        &expanded_cmd,
-                                        prefix_enabled,
-                                        prefix_label,
-                                        prefix_color,
-                                        task_data.working_dir.as_deref(),
-                                    )?;
-                                }
-                            }
-                            NodeKind::Command(cmd_data) => {
-                                let expanded_cmd =
-                                    expand_env_vars_fn(&cmd_data.raw_command, &cmd_data.env);
-                                let (prefix_enabled, prefix_label, prefix_color) =
-                                    get_prefix_settings_fn(child_node);
-                                group_pm.spawn_command(
-                                    "command",
-                                    &expanded_cmd,
-                                    prefix_enabled,
-                                    prefix_label,
-                                    prefix_color,
-                                    cmd_data.working_dir.as_deref(),
-                                )?;
-                            }
-                            _ => {}
-                        }
+            }
+            NodeKind::Command(cmd_data) => {
+                let expanded_cmd = self.expand_env_vars(&cmd_data.raw_command, &cmd_data.env);
+                let mut options =
+                    self.spawn_options(node, cmd_data.working_dir.as_ref(), &cmd_data.env);
+                options.sandbox = self.sandbox_policy(graph, node.id)?;
+                pm.spawn_with_options("command", &expanded_cmd, options)?;
+            }
+            NodeKind::ConcurrentGroup(_) => {}
+        }
+        Ok(())
+    }
+
+    fn run_node(
+        &self,
+        node_id: usize,
+        graph: &Graph,
+        visited: &mut std::collections::HashSet<usize>,
+        control: &RunControl,
+    ) -> Result<()> {
+        if visited.contains(&node_id) {
+            return Ok(());
+        }
+        visited.insert(node_id);
+
+        let node = &graph.nodes[node_id];
+        match &node.kind {
+            NodeKind::Task(_) => {
+                // Run pre dependencies and concurrent groups, one after another
+                for kind in [EdgeKind::PreDep, EdgeKind::Concurrent] {
+                    for dep_id in graph.dependencies_of(node_id as NodeId, kind) {
+                        self.run_node(dep_id as usize, graph, visited, control)?;
                     }
-                    group_pm.run_concurrently()?;
                 }
+                // Execute the task command and wait for it
+                self.run_command(graph, node, control)?;
+                // Run post dependencies once the task has finished
+                for dep_id in graph.dependencies_of(node_id as NodeId, EdgeKind::PostDep) {
+                    self.run_node(dep_id as usize, graph, visited, control)?;
+                }
+            }
+            NodeKind::Command(_) => {
+                self.run_command(graph, node, control)?;
+            }
+            NodeKind::ConcurrentGroup(group_data) => {
+                self.run_group(group_data, graph, visited, control)?;
             }
-            Ok(())
         }
+        Ok(())
+    }
 
-        run_node(
-            task_id,
-            graph,
-            &mut pm,
-            &mut visited,
-            &|cmd, env| self.expand_env_vars(cmd, env),
-            &|node| self.get_prefix_settings(node),
-        )?;
+    /// Run the children of a concurrent group on a pool of at most `max_concurrent` workers.
+    ///
+    /// With `fail_fast`, the first failure stops the running children and no new ones start;
+    /// otherwise every child runs and the first error is reported afterwards. Cancelling the
+    /// run stops the group either way.
+    fn run_group(
+        &self,
+        group_data: &ConcurrentGroupData,
+        graph: &Graph,
+        visited: &mut std::collections::HashSet<usize>,
+        control: &RunControl,
+    ) -> Result<()> {
+        let children = group_data.child_nodes.len();
+        if children == 0 {
+            return Ok(());
+        }
+        let workers = group_data
+            .max_concurrent
+            .unwrap_or(children)
+            .clamp(1, children);
+        let queue = Mutex::new(
+            group_data
+                .child_nodes
+                .iter()
+                .copied()
+                .collect::<VecDeque<_>>(),
+        );
+        let finished = Mutex::new(visited.clone());
+        let errors = Mutex::new(Vec::new());
+        let snapshot = visited.clone();
+        let child_control = RunControl {
+            cancel: control.cancel.clone(),
+            fail_fast: group_data.fail_fast,
+            progress: control.progress.clone(),
+        };
 
-        pm.run_concurrently()?;
+        thread::scope(|scope| {
+            for _ in 0..workers {
+                scope.spawn(|| loop {
+                    if control.is_cancelled() {
+                        break;
+                    }
+                    let Some(child_id) = queue.lock().unwrap().pop_front() else {
+                        break;
+                    };
+                    let mut child_visited = snapshot.clone();
+                    let result =
+                        self.run_node(child_id as usize, graph, &mut child_visited, &child_control);
+                    finished.lock().unwrap().extend(child_visited);
+                    if let Err(e) = result {
+                        errors.lock().unwrap().push(e);
+                    }
+                });
+            }
+        });
 
-        Ok(())
+        *visited = finished.into_inner().unwrap();
+        match errors.into_inner().unwrap().into_iter().next() {
+            Some(e) => Err(e),
+            None => Ok(()),
+        }
     }
 }
@@ -0,0 +1,720 @@
+use serde::{Deserialize, Serialize};
+use std::{
+    any::Any,
+    collections::{BTreeMap, HashMap, HashSet},
+    str::FromStr,
+};
+use unicode_width::UnicodeWidthStr;
+
+use crate::{
+    config::WatchConfig,
+    errors::BodoError,
+    graph::{EdgeKind, Graph, NodeId, NodeKind},
+    plugin::{Plugin, PluginConfig},
+    sandbox::SANDBOX_KEY,
+    Result,
+};
+
+/// Characters used to draw the dependency tree.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TreeStyle {
+    Unicode,
+    Ascii,
+}
+
+impl TreeStyle {
+    /// Unicode when the locale says UTF-8, ASCII otherwise.
+    pub fn detect() -> Self {
+        let utf8 = ["LC_ALL", "LC_CTYPE", "LANG"]
+            .iter()
+            .filter_map(|var| std::env::var(var).ok())
+            .find(|value| !value.is_empty())
+            .map(|value| {
+                let value = value.to_lowercase();
+                value.contains("utf-8") || value.contains("utf8")
+            })
+            .unwrap_or(false);
+        if utf8 {
+            TreeStyle::Unicode
+        } else {
+            TreeStyle::Ascii
+        }
+    }
+
+    /// (branch, last branch, continuation) for children that run one after another.
+    fn sequential(self) -> (&'static str, &'static str, &'static str) {
+        match self {
+            TreeStyle::Unicode => ("├── ", "└── ", "│   "),
+            TreeStyle::Ascii => ("|-- ", "`-- ", "|   "),
+        }
+    }
+
+    /// (first, middle, last, bar, only) for the bar above the columns of a concurrent
+    /// group: a junction above each column, joined by the bar.
+    fn columns(
+        self,
+    ) -> (
+        &'static str,
+        &'static str,
+        &'static str,
+        &'static str,
+        &'static str,
+    ) {
+        match self {
+            TreeStyle::Unicode => ("╔", "╦", "╗", "═", "║"),
+            TreeStyle::Ascii => ("+", "+", "+", "=", "|"),
+        }
+    }
+
+    fn marker(self, marker: Marker) -> &'static str {
+        match (self, marker) {
+            (TreeStyle::Unicode, Marker::Task) => "📦",
+            (TreeStyle::Unicode, Marker::CrossFile) => "🌐",
+            (TreeStyle::Unicode, Marker::Command) => "🚀",
+            (TreeStyle::Unicode, Marker::Group) => "🔀",
+            (TreeStyle::Ascii, Marker::Task) => "[task]",
+            (TreeStyle::Ascii, Marker::CrossFile) => "[ref]",
+            (TreeStyle::Ascii, Marker::Command) => "[cmd]",
+            (TreeStyle::Ascii, Marker::Group) => "[parallel]",
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Marker {
+    Task,
+    CrossFile,
+    Command,
+    Group,
+}
+
+/// A child line in the tree: another node, or the task's own command.
+enum Branch {
+    Node(NodeId, Option<&'static str>),
+    Command(String),
+}
+
+/// Render the dependency tree of `root` in the order bodo runs it: pre_deps, concurrent
+/// groups, the task's own command, then post_deps. Children of a concurrent group are
+/// drawn side by side, one column each. A task that appears more than once is expanded
+/// the first time only; later occurrences refer back to it.
+pub fn render_tree(graph: &Graph, root: NodeId, style: TreeStyle) -> String {
+    let names: HashMap<NodeId, &str> = graph
+        .task_registry
+        .iter()
+        .map(|(key, &id)| (id, key.as_str()))
+        .collect();
+    let mut renderer = TreeRenderer {
+        graph,
+        names,
+        style,
+        expanded: HashSet::new(),
+        out: String::new(),
+    };
+    let label = renderer.label(root, None, None);
+    renderer.out.push_str(&label);
+    renderer.out.push('\n');
+    renderer.children(root, "");
+    renderer.out
+}
+
+struct TreeRenderer<'a> {
+    graph: &'a Graph,
+    names: HashMap<NodeId, &'a str>,
+    style: TreeStyle,
+    expanded: HashSet<NodeId>,
+    out: String,
+}
+
+/// Spaces between the columns of a concurrent group.
+const COLUMN_GAP: usize = 3;
+
+impl TreeRenderer<'_> {
+    fn children(&mut self, id: NodeId, prefix: &str) {
+        self.expanded.insert(id);
+        let branches = match &self.graph.nodes[id as usize].kind {
+            NodeKind::Task(task) => {
+                let mut branches: Vec<Branch> = Vec::new();
+                for kind in [EdgeKind::PreDep, EdgeKind::Concurrent] {
+                    for dep in self.graph.dependencies_of(id, kind) {
+                        branches.push(Branch::Node(dep, None));
+                    }
+                }
+                if let Some(cmd) = &task.command {
+                    branches.push(Branch::Command(cmd.clone()));
+                }
+                for dep in self.graph.dependencies_of(id, EdgeKind::PostDep) {
+                    branches.push(Branch::Node(dep, Some("after")));
+                }
+                branches
+            }
+            NodeKind::ConcurrentGroup(group) => {
+                let children = group.child_nodes.clone();
+                self.columns(id, &children, prefix);
+                return;
+            }
+            NodeKind::Command(_) => Vec::new(),
+        };
+
+        let (branch, last_branch, continuation) = self.style.sequential();
+        let count = branches.len();
+        for (i, child) in branches.into_iter().enumerate() {
+            let last = i + 1 == count;
+            self.out.push_str(prefix);
+            self.out.push_str(if last { last_branch } else { branch });
+            match child {
+                Branch::Command(cmd) => {
+                    let label = format!("{} \"{}\"", self.style.marker(Marker::Command), cmd);
+                    self.out.push_str(&label);
+                    self.out.push('\n');
+                }
+                Branch::Node(child, note) => {
+                    let seen = self.expanded.contains(&child);
+                    let label = self.label(child, Some(id), note);
+                    self.out.push_str(&label);
+                    if seen {
+                        self.out.push_str(" (see above)");
+                    }
+                    self.out.push('\n');
+                    if !seen {
+                        let indent = if last { "    " } else { continuation };
+                        self.children(child, &format!("{}{}", prefix, indent));
+                    }
+                }
+            }
+        }
+    }
+
+    /// The children of concurrent group `group` side by side: each child's tree in its own
+    /// column, under a bar joining them, left to right in the order they are declared.
+    fn columns(&mut self, group: NodeId, children: &[NodeId], prefix: &str) {
+        let blocks: Vec<Vec<String>> = children
+            .iter()
+            .map(|&child| self.block(child, group))
+            .collect();
+        let widths: Vec<usize> = blocks
+            .iter()
+            .map(|block| block.iter().map(|line| line.width()).max().unwrap_or(0))
+            .collect();
+
+        let (first, middle, last, bar, only) = self.style.columns();
+        let mut header = String::new();
+        for (i, width) in widths.iter().enumerate() {
+            let junction = match (i == 0, i + 1 == widths.len()) {
+                (true, true) => only,
+                (true, false) => first,
+                (false, false) => middle,
+                (false, true) => last,
+            };
+            header.push_str(junction);
+            if i + 1 < widths.len() {
+                header.push_str(&bar.repeat(width + COLUMN_GAP - 1));
+            }
+        }
+        self.out.push_str(prefix);
+        self.out.push_str(&header);
+        self.out.push('\n');
+
+        let rows = blocks.iter().map(Vec::len).max().unwrap_or(0);
+        for row in 0..rows {
+            let mut line = String::new();
+            for (block, width) in blocks.iter().zip(&widths) {
+                let cell = block.get(row).map(String::as_str).unwrap_or_default();
+                line.push_str(cell);
+                line.push_str(&" ".repeat(width - cell.width() + COLUMN_GAP));
+            }
+            self.out.push_str(prefix);
+            self.out.push_str(line.trim_end());
+            self.out.push('\n');
+        }
+    }
+
+    /// The tree of `id`, a child of `parent`, as lines of its own.
+    fn block(&mut self, id: NodeId, parent: NodeId) -> Vec<String> {
+        let outer = std::mem::take(&mut self.out);
+        let seen = self.expanded.contains(&id);
+        let label = self.label(id, Some(parent), None);
+        self.out.push_str(&label);
+        if seen {
+            self.out.push_str(" (see above)");
+        }
+        self.out.push('\n');
+        if !seen {
+            self.children(id, "");
+        }
+        let block = std::mem::replace(&mut self.out, outer);
+        block.lines().map(String::from).collect()
+    }
+
+    fn label(&self, id: NodeId, parent: Option<NodeId>, note: Option<&str>) -> String {
+        let node = &self.graph.nodes[id as usize];
+        let mut label = match &node.kind {
+            NodeKind::Task(task) => {
+                let cross_file = parent
+                    .and_then(|p| self.owning_script(p))
+                    .is_some_and(|script| script != task.script_id);
+                let marker = if cross_file {
+                    Marker::CrossFile
+                } else {
+                    Marker::Task
+                };
+                let name = self
+                    .names
+                    .get(&id)
+                    .map(|name| name.to_string())
+                    .unwrap_or_else(|| self.graph.node_name(id as usize));
+                format!("{} {}", self.style.marker(marker), name)
+            }
+            NodeKind::Command(cmd) => {
+                format!(
+                    "{} \"{}\"",
+                    self.style.marker(Marker::Command),
+                    cmd.raw_command
+                )
+            }
+            NodeKind::ConcurrentGroup(group) => {
+                let mut options = Vec::new();
+                if group.fail_fast {
+                    options.push("fail_fast".to_string());
+                }
+                if let Some(max) = group.max_concurrent {
+                    options.push(format!("max {}", max));
+                }
+                let mut label = format!("{} concurrently", self.style.marker(Marker::Group));
+                if !options.is_empty() {
+                    label.push_str(&format!(" ({})", options.join(", ")));
+                }
+                label
+            }
+        };
+        if let Some(note) = note {
+            label.push_str(&format!(" ({})", note));
+        }
+        label
+    }
+
+    /// The script of the task a node belongs to, looking through concurrent groups.
+    fn owning_script(&self, id: NodeId) -> Option<&str> {
+        match &self.graph.nodes[id as usize].kind {
+            NodeKind::Task(task) => Some(task.script_id.as_str()),
+            _ => self
+                .graph
+                .edges
+                .iter()
+                .find(|e| e.to == id && e.kind == EdgeKind::Concurrent)
+                .and_then(|e| self.owning_script(e.from)),
+        }
+    }
+}
+
+/// Output formats of `bodo graph`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GraphFormat {
+    /// The dependency tree of one task, for terminals.
+    Tree,
+    Dot,
+    Mermaid,
+    Json,
+}
+
+impl FromStr for GraphFormat {
+    type Err = BodoError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "tree" => Ok(GraphFormat::Tree),
+            "dot" => Ok(GraphFormat::Dot),
+            "mermaid" => Ok(GraphFormat::Mermaid),
+            "json" => Ok(GraphFormat::Json),
+            other => Err(BodoError::ValidationError(format!(
+                "unknown graph format '{}'; expected one of: tree, dot, mermaid, json",
+                other
+            ))),
+        }
+    }
+}
+
+/// Version of the JSON export. Bump it whenever the shape of [`GraphExport`] changes.
+pub const GRAPH_EXPORT_VERSION: u32 = 1;
+
+/// The metadata exports keep: what the scripts declare. Everything else is bookkeeping
+/// for the run, and colors assigned by `PrefixPlugin` depend on load order.
+const EXPORTED_METADATA: &[&str] = &["timeout", "prefix_color", SANDBOX_KEY];
+
+/// A graph with stable node ids, as written by `bodo graph --format json`.
+///
+/// Node ids do not depend on load order: tasks use their registry key (`build`,
+/// `deploy prod`) and other nodes are named after the node that owns them
+/// (`release/concurrent0`, `release/concurrent0/concurrent1`). Nodes are sorted by id
+/// and edges follow their `from` node, in declaration order.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GraphExport {
+    pub version: u32,
+    /// The task the export was limited to, or `None` for the whole graph.
+    pub root: Option<String>,
+    pub nodes: Vec<ExportNode>,
+    pub edges: Vec<ExportEdge>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExportNode {
+    pub id: String,
+    /// `task`, `command` or `concurrent_group`
+    pub kind: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub script: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub command: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub working_dir: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub watch: Option<WatchConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fail_fast: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_concurrent: Option<usize>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub metadata: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExportEdge {
+    pub from: String,
+    pub to: String,
+    /// `pre`, `post` or `concurrent`
+    pub kind: String,
+}
+
+fn edge_kind_name(kind: EdgeKind) -> &'static str {
+    match kind {
+        EdgeKind::PreDep => "pre",
+        EdgeKind::PostDep => "post",
+        EdgeKind::Concurrent => "concurrent",
+    }
+}
+
+/// Export `graph`, or only the part reachable from `root`.
+pub fn export_graph(graph: &Graph, root: Option<NodeId>) -> GraphExport {
+    let ids = stable_ids(graph);
+
+    let included: Vec<NodeId> = match root {
+        Some(root) => {
+            let mut seen = HashSet::from([root]);
+            let mut stack = vec![root];
+            while let Some(id) = stack.pop() {
+                for edge in graph.edges.iter().filter(|e| e.from == id) {
+                    if seen.insert(edge.to) {
+                        stack.push(edge.to);
+                    }
+                }
+            }
+            seen.into_iter().collect()
+        }
+        None => graph.nodes.iter().map(|node| node.id).collect(),
+    };
+    let mut included: Vec<(&String, NodeId)> =
+        included.into_iter().map(|id| (&ids[&id], id)).collect();
+    included.sort();
+
+    let nodes = included
+        .iter()
+        .map(|&(id, node_id)| {
+            let node = &graph.nodes[node_id as usize];
+            let metadata: BTreeMap<String, String> = node
+                .metadata
+                .iter()
+                .filter(|(key, _)| EXPORTED_METADATA.contains(&key.as_str()))
+                .map(|(key, value)| (key.clone(), value.clone()))
+                .collect();
+            let mut export = ExportNode {
+                id: id.clone(),
+                kind: String::new(),
+                script: None,
+                description: None,
+                command: None,
+                working_dir: None,
+                watch: None,
+                fail_fast: None,
+                max_concurrent: None,
+                metadata,
+            };
+            match &node.kind {
+                NodeKind::Task(task) => {
+                    export.kind = "task".to_string();
+                    export.script = Some(task.script_id.clone());
+                    export.description = task.description.clone();
+                    export.command = task.command.clone();
+                    export.working_dir = task.working_dir.clone();
+                    export.watch = task.watch.clone();
+                }
+                NodeKind::Command(cmd) => {
+                    export.kind = "command".to_string();
+                    export.description = cmd.description.clone();
+                    export.command = Some(cmd.raw_command.clone());
+                    export.working_dir = cmd.working_dir.clone();
+                }
+                NodeKind::ConcurrentGroup(group) => {
+                    export.kind = "concurrent_group".to_string();
+                    export.fail_fast = Some(group.fail_fast);
+                    export.max_concurrent = group.max_concurrent;
+                }
+            }
+            export
+        })
+        .collect();
+
+    let mut edges = Vec::new();
+    for &(id, node_id) in &included {
+        for kind in [EdgeKind::PreDep, EdgeKind::Concurrent, EdgeKind::PostDep] {
+            for dep in graph.dependencies_of(node_id, kind) {
+                edges.push(ExportEdge {
+                    from: id.clone(),
+                    to: ids[&dep].clone(),
+                    kind: edge_kind_name(kind).to_string(),
+                });
+            }
+        }
+    }
+
+    GraphExport {
+        version: GRAPH_EXPORT_VERSION,
+        root: root.map(|root| ids[&root].clone()),
+        nodes,
+        edges,
+    }
+}
+
+/// The id [`export_graph`] gives each node: the registry key for tasks, the owning
+/// node and position for everything else.
+pub fn stable_ids(graph: &Graph) -> HashMap<NodeId, String> {
+    let mut ids: HashMap<NodeId, String> = graph
+        .task_registry
+        .iter()
+        .map(|(key, &id)| (id, key.clone()))
+        .collect();
+    for node in &graph.nodes {
+        stable_id(graph, node.id, &mut ids);
+    }
+    ids
+}
+
+/// Name a non-task node after the node that owns it and its position among the
+/// owner's dependencies of that kind.
+fn stable_id(graph: &Graph, id: NodeId, ids: &mut HashMap<NodeId, String>) -> String {
+    if let Some(existing) = ids.get(&id) {
+        return existing.clone();
+    }
+    let name = match graph.edges.iter().find(|e| e.to == id) {
+        Some(edge) => {
+            let owner = stable_id(graph, edge.from, ids);
+            let position = graph
+                .dependencies_of(edge.from, edge.kind)
+                .iter()
+                .position(|&dep| dep == id)
+                .unwrap_or(0);
+            format!("{}/{}{}", owner, edge_kind_name(edge.kind), position)
+        }
+        None => format!("node{}", id),
+    };
+    ids.insert(id, name.clone());
+    name
+}
+
+/// Render an export as a Graphviz digraph. Pre dependencies are solid edges, post
+/// dependencies dashed and concurrent children bold.
+pub fn to_dot(export: &GraphExport) -> String {
+    let quote = |s: &str| {
+        let escaped = s
+            .replace('\\', "\\\\")
+            .replace('"', "\\\"")
+            .replace('\n', "\\n");
+        format!("\"{}\"", escaped)
+    };
+    let mut out = String::from("digraph bodo {\n    rankdir=LR;\n");
+    for node in &export.nodes {
+        let shape = match node.kind.as_str() {
+            "task" => "box",
+            "concurrent_group" => "diamond",
+            _ => "ellipse",
+        };
+        out.push_str(&format!(
+            "    {} [label={}, shape={}];\n",
+            quote(&node.id),
+            quote(&node_label(node).join("\n")),
+            shape
+        ));
+    }
+    for edge in &export.edges {
+        let style = match edge.kind.as_str() {
+            "post" => "dashed",
+            "concurrent" => "bold",
+            _ => "solid",
+        };
+        out.push_str(&format!(
+            "    {} -> {} [label={}, style={}];\n",
+            quote(&edge.from),
+            quote(&edge.to),
+            quote(&edge.kind),
+            style
+        ));
+    }
+    out.push_str("}\n");
+    out
+}
+
+/// Render an export as a Mermaid flowchart. Pre dependencies use `-->`, post
+/// dependencies `-.->` and concurrent children `==>`.
+pub fn to_mermaid(export: &GraphExport) -> String {
+    let index: HashMap<&str, usize> = export
+        .nodes
+        .iter()
+        .enumerate()
+        .map(|(i, node)| (node.id.as_str(), i))
+        .collect();
+    let mut out = String::from("flowchart LR\n");
+    for (i, node) in export.nodes.iter().enumerate() {
+        let label = node_label(node)
+            .iter()
+            .map(|line| line.replace('"', "#quot;"))
+            .collect::<Vec<_>>()
+            .join("<br/>");
+        let (open, close) = match node.kind.as_str() {
+            "task" => ("[\"", "\"]"),
+            "concurrent_group" => ("{{\"", "\"}}"),
+            _ => ("([\"", "\"])"),
+        };
+        out.push_str(&format!("    n{}{}{}{}\n", i, open, label, close));
+    }
+    for edge in &export.edges {
+        let arrow = match edge.kind.as_str() {
+            "post" => "-.->",
+            "concurrent" => "==>",
+            _ => "-->",
+        };
+        out.push_str(&format!(
+            "    n{} {}|{}| n{}\n",
+            index[edge.from.as_str()],
+            arrow,
+            edge.kind,
+            index[edge.to.as_str()]
+        ));
+    }
+    out
+}
+
+/// Label lines for DOT and Mermaid: the node itself, then timeout, prefix color and
+/// watch patterns when set.
+fn node_label(node: &ExportNode) -> Vec<String> {
+    let mut lines = vec![match node.kind.as_str() {
+        "task" => node.id.clone(),
+        "concurrent_group" => {
+            let mut options = Vec::new();
+            if node.fail_fast == Some(true) {
+                options.push("fail_fast".to_string());
+            }
+            if let Some(max) = node.max_concurrent {
+                options.push(format!("max {}", max));
+            }
+            if options.is_empty() {
+                "concurrently".to_string()
+            } else {
+                format!("concurrently ({})", options.join(", "))
+            }
+        }
+        _ => node.command.clone().unwrap_or_default(),
+    }];
+    if let Some(timeout) = node.metadata.get("timeout") {
+        lines.push(format!("timeout: {}", timeout));
+    }
+    if let Some(color) = node.metadata.get("prefix_color") {
+        lines.push(format!("prefix_color: {}", color));
+    }
+    if let Some(watch) = &node.watch {
+        lines.push(format!("watch: {}", watch.patterns.join(", ")));
+    }
+    lines
+}
+
+/// Prints the dependency tree of the requested task (`bodo --graph`), or exports the
+/// graph in another [`GraphFormat`] (`bodo graph --format dot`).
+pub struct GraphPlugin {
+    task_name: Option<String>,
+    style: TreeStyle,
+    format: GraphFormat,
+}
+
+impl Default for GraphPlugin {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GraphPlugin {
+    pub fn new() -> Self {
+        Self {
+            task_name: None,
+            style: TreeStyle::detect(),
+            format: GraphFormat::Tree,
+        }
+    }
+
+    pub fn with_format(mut self, format: GraphFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    pub fn with_style(mut self, style: TreeStyle) -> Self {
+        self.style = style;
+        self
+    }
+}
+
+impl Plugin for GraphPlugin {
+    fn name(&self) -> &'static str {
+        "GraphPlugin"
+    }
+
+    fn priority(&self) -> i32 {
+        0
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn on_init(&mut self, config: &PluginConfig) -> Result<()> {
+        self.task_name = config
+            .options
+            .as_ref()
+            .and_then(|options| options.get("task"))
+            .and_then(|task| task.as_str())
+            .map(String::from);
+        Ok(())
+    }
+
+    fn on_graph_build(&mut self, graph: &mut Graph) -> Result<()> {
+        let task_node = |task: &str| {
+            graph
+                .task_registry
+                .get(task)
+                .copied()
+                .ok_or_else(|| graph.task_not_found(task))
+        };
+        // The tree always starts from a task; exports cover the whole graph by default.
+        if self.format == GraphFormat::Tree {
+            let root = task_node(self.task_name.as_deref().unwrap_or("default"))?;
+            print!("{}", render_tree(graph, root, self.style));
+            return Ok(());
+        }
+        let root = self.task_name.as_deref().map(task_node).transpose()?;
+        let export = export_graph(graph, root);
+        match self.format {
+            GraphFormat::Dot => print!("{}", to_dot(&export)),
+            GraphFormat::Mermaid => print!("{}", to_mermaid(&export)),
+            _ => println!("{}", serde_json::to_string_pretty(&export)?),
+        }
+        Ok(())
+    }
+}
@@ -1,8 +1,10 @@
 pub use crate::plugin::{Plugin, PluginConfig, PluginManager};
 
 pub mod concurrent_plugin;
+pub mod describe_plugin;
 pub mod env_plugin;
 pub mod execution_plugin;
+pub mod graph_plugin;
 pub mod path_plugin;
 pub mod prefix_plugin;
 pub mod print_list_plugin;
@@ -102,21 +102,22 @@ impl Plugin for PathPlugin {
 
     fn on_graph_build(&mut self, graph: &mut Graph) -> Result<()> {
         for node in &mut graph.nodes {
-            match &mut node.kind {
+            let (env, path_str) = match &mut node.kind {
                 NodeKind::Task(task_data) => {
                     let path_str =
                         self.build_path(task_data.working_dir.as_ref(), &task_data.exec_paths);
-                    if !path_str.is_empty() {
-                        task_data.env.insert("PATH".to_string(), path_str);
-                    }
+                    (&mut task_data.env, path_str)
                 }
                 NodeKind::Command(cmd_data) => {
-                    let path_str = self.build_path(cmd_data.working_dir.as_ref(), &[]);
-                    if !path_str.is_empty() {
-                        cmd_data.env.insert("PATH".to_string(), path_str);
-                    }
+                    let path_str =
+                        self.build_path(cmd_data.working_dir.as_ref(), &cmd_data.exec_paths);
+                    (&mut cmd_data.env, path_str)
                 }
-                _ => {}
+                _ => continue,
+            };
+            if !path_str.is_empty() {
+                env.insert("PATH".to_string(), path_str);
+                node.set_env_source("PATH", "exec_paths");
             }
         }
         Ok(())
@@ -1,7 +1,7 @@
 use crate::{
     config::WatchConfig,
     errors::BodoError,
-    graph::{Graph, NodeKind},
+    graph::{Graph, NodeId, NodeKind},
     plugin::{Plugin, PluginConfig},
     Result,
 };
@@ -10,19 +10,107 @@ use log::{debug, error, warn};
 use notify::{Config as NotifyConfig, Event, RecommendedWatcher, RecursiveMode, Watcher};
 use std::{
     any::Any,
-    collections::HashSet,
+    collections::{BTreeSet, HashSet},
     path::{Path, PathBuf},
-    sync::mpsc::{self, Receiver},
+    sync::atomic::{AtomicBool, Ordering},
+    sync::mpsc::{self, Receiver, RecvTimeoutError},
+    sync::Arc,
+    thread::{self, JoinHandle},
     time::{Duration, Instant},
 };
 
+/// Runs the watched task once from the original configuration, CLI arguments and plugins,
+/// given the files that changed since the previous run (empty for the first run).
+/// The run must stop as soon as the given flag is set.
+pub type TaskRunner = Arc<dyn Fn(Arc<AtomicBool>, Vec<PathBuf>) -> Result<()> + Send + Sync>;
+
+/// Environment variable listing the changed files, one per line, for a watched rerun.
+pub const CHANGED_FILES_VAR: &str = "BODO_CHANGED_FILES";
+
+/// Trailing-edge debouncer: collects changed paths and fires once, after no change has
+/// arrived for the whole window.
+#[derive(Debug)]
+pub struct Debouncer {
+    window: Duration,
+    pending: BTreeSet<PathBuf>,
+    last_change: Option<Instant>,
+}
+
+impl Debouncer {
+    pub fn new(window: Duration) -> Self {
+        Self {
+            window,
+            pending: BTreeSet::new(),
+            last_change: None,
+        }
+    }
+
+    /// Record changed paths; any change restarts the quiet window.
+    pub fn add(&mut self, paths: impl IntoIterator<Item = PathBuf>, now: Instant) {
+        let mut changed = false;
+        for path in paths {
+            self.pending.insert(path);
+            changed = true;
+        }
+        if changed {
+            self.last_change = Some(now);
+        }
+    }
+
+    /// Whether changes are pending and the window has been quiet since the last one.
+    pub fn ready(&self, now: Instant) -> bool {
+        self.last_change
+            .is_some_and(|last| now.duration_since(last) >= self.window)
+    }
+
+    /// How long until `ready` turns true, if anything is pending.
+    pub fn time_until_ready(&self, now: Instant) -> Option<Duration> {
+        self.last_change
+            .map(|last| self.window.saturating_sub(now.duration_since(last)))
+    }
+
+    /// Take every pending path, in sorted order, and reset the debouncer.
+    pub fn take(&mut self) -> Vec<PathBuf> {
+        self.last_change = None;
+        std::mem::take(&mut self.pending).into_iter().collect()
+    }
+}
+
 pub struct WatchPlugin {
     watch_entries: Vec<WatchEntry>,
     watch_mode: bool,
     stop_on_fail: bool,
-    // We'll store a pointer to whether we need to re-run the entire pipeline. In a real setup
-    // you might want a reference to the GraphManager or some approach to re-run tasks.
-    // Here we'll keep it simpler and just store a flag we can read in on_after_run.
+    task_name: Option<String>,
+    runner: Option<TaskRunner>,
+    shutdown: Arc<AtomicBool>,
+}
+
+/// A run of the watched task in the background.
+struct ActiveRun {
+    cancel: Arc<AtomicBool>,
+    handle: JoinHandle<Result<()>>,
+}
+
+impl ActiveRun {
+    fn start(runner: &TaskRunner, changed_files: Vec<PathBuf>) -> Self {
+        let cancel = Arc::new(AtomicBool::new(false));
+        let runner = runner.clone();
+        let flag = cancel.clone();
+        let handle = thread::spawn(move || runner(flag, changed_files));
+        Self { cancel, handle }
+    }
+
+    /// Kill whatever is still running and wait for the run to wind down.
+    fn stop(self) {
+        self.cancel.store(true, Ordering::SeqCst);
+        let _ = self.handle.join();
+    }
+
+    fn finish(self) -> Result<()> {
+        self.handle
+            .join()
+            .unwrap_or_else(|_| Err(BodoError::PluginError("watched run panicked".to_string())))
+    }
 }
 
 #[derive(Debug)]
@@ -41,9 +129,24 @@ impl WatchPlugin {
             watch_entries: Vec::new(),
             watch_mode,
             stop_on_fail,
+            task_name: None,
+            runner: None,
+            shutdown: Arc::new(AtomicBool::new(false)),
         }
     }
 
+    /// Let watch mode run the requested task itself: once at start and again on every change,
+    /// killing the previous run first.
+    pub fn with_runner(mut self, runner: TaskRunner) -> Self {
+        self.runner = Some(runner);
+        self
+    }
+
+    /// A flag that ends the watch loop (and the current run) when set.
+    pub fn shutdown_handle(&self) -> Arc<AtomicBool> {
+        self.shutdown.clone()
+    }
+
     pub fn get_watch_entry_count(&self) -> usize {
         self.watch_entries.len()
     }
@@ -52,10 +155,6 @@ impl WatchPlugin {
         self.watch_mode
     }
 
-    // We'll store a pointer to whether we need to re-run the entire pipeline. In a real setup
-    // you might want a reference to the GraphManager or some approach to re-run tasks.
-    // Here we'll keep it simpler and just store a flag we can read in on_after_run.
-
     fn create_watcher() -> Result<(RecommendedWatcher, Receiver<notify::Result<Event>>)> {
         debug!("Creating file watcher with 1s poll interval");
         let (tx, rx) = mpsc::channel();
@@ -168,18 +267,6 @@ impl WatchPlugin {
             Some(base)
         }
     }
-
-    // Rest of the implementation...
-    // ... (Rest of the methods remain unchanged)
-}
-
-// Function moved outside of impl block
-// A small helper that just returns some BodoConfig with "scripts/" as script dirs
-fn graph_manager_config_snapshot() -> Result<crate::config::BodoConfig> {
-    Ok(crate::config::BodoConfig {
-        scripts_dirs: Some(vec!["scripts/".into()]),
-        ..Default::default()
-    })
 }
 
 impl Default for WatchPlugin {
@@ -206,6 +293,12 @@ impl Plugin for WatchPlugin {
         if config.watch {
             self.watch_mode = true;
         }
+        self.task_name = config
+            .options
+            .as_ref()
+            .and_then(|options| options.get("task"))
+            .and_then(|task| task.as_str())
+            .map(String::from);
         Ok(())
     }
 
@@ -233,8 +326,18 @@ impl Plugin for WatchPlugin {
             return Ok(());
         }
 
+        // Only the requested task and what it depends on are watched.
+        let task_id = self
+            .task_name
+            .as_ref()
+            .and_then(|name| graph.task_registry.get(name).copied());
+        let relevant = task_id.map(|id| Self::reachable_from(graph, id));
+
         // Process watch entries for all tasks that have watch configs
         for node in &graph.nodes {
+            if relevant.as_ref().is_some_and(|ids| !ids.contains(&node.id)) {
+                continue;
+            }
             if let NodeKind::Task(task_data) = &node.kind {
                 if let Some(WatchConfig {
                     patterns,
@@ -293,6 +396,15 @@ impl Plugin for WatchPlugin {
                 }
             }
         }
+
+        // The watch loop takes over running the task, so the normal run must skip it.
+        if let (Some(id), Some(_)) = (task_id, &self.runner) {
+            if !self.watch_entries.is_empty() {
+                graph.nodes[id as usize]
+                    .metadata
+                    .insert("watch_managed".to_string(), "true".to_string());
+            }
+        }
         Ok(())
     }
 
@@ -303,10 +415,10 @@ impl Plugin for WatchPlugin {
 
         let (mut watcher, rx) = WatchPlugin::create_watcher()?;
         let mut all_dirs = HashSet::new();
-        let mut max_debounce = 500;
+        let mut debounce_ms = 0;
 
         for entry in &self.watch_entries {
-            max_debounce = max_debounce.max(entry.debounce_ms);
+            debounce_ms = debounce_ms.max(entry.debounce_ms);
             all_dirs.extend(entry.directories_to_watch.iter().cloned());
         }
 
@@ -318,15 +430,55 @@ impl Plugin for WatchPlugin {
             }
         }
 
+        let Some(runner) = self.runner.clone() else {
+            warn!("WatchPlugin: nothing to re-run on changes, not watching");
+            return Ok(());
+        };
+        let task_name = self.task_name.clone().unwrap_or_default();
+        let cwd = std::env::current_dir().unwrap_or_default();
+
         println!("Watching for file changes. Press Ctrl-C to stop...");
 
-        let mut last_run = Instant::now();
+        let mut run = Some(ActiveRun::start(&runner, Vec::new()));
+        let mut debouncer = Debouncer::new(Duration::from_millis(debounce_ms));
+        let poll = Duration::from_millis(100);
 
         // We block here until the user kills the process
-        loop {
-            let event = match rx.recv() {
+        while !self.shutdown.load(Ordering::SeqCst) {
+            if run.as_ref().is_some_and(|r| r.handle.is_finished()) {
+                if let Err(e) = run.take().map_or(Ok(()), ActiveRun::finish) {
+                    // Stopping hands the failure on, so `bodo --watch` exits with an error.
+                    if self.stop_on_fail {
+                        warn!("WatchPlugin: Stopping watch loop due to re-run failure");
+                        return Err(e);
+                    }
+                    error!("WatchPlugin: run of '{}' failed: {}", task_name, e);
+                }
+            }
+
+            // Changes made while a run is going are not lost: once things are quiet, the
+            // current run is replaced by one that sees every change collected so far.
+            if debouncer.ready(Instant::now()) {
+                let changed = debouncer.take();
+                println!(
+                    "{} file(s) changed: restarting '{}'...",
+                    changed.len(),
+                    task_name
+                );
+                if let Some(previous) = run.take() {
+                    previous.stop();
+                }
+                run = Some(ActiveRun::start(&runner, changed));
+                continue;
+            }
+
+            let wait = debouncer
+                .time_until_ready(Instant::now())
+                .map_or(poll, |left| left.min(poll));
+            let event = match rx.recv_timeout(wait) {
                 Ok(e) => e,
-                Err(_) => {
+                Err(RecvTimeoutError::Timeout) => continue,
+                Err(RecvTimeoutError::Disconnected) => {
                     debug!("WatchPlugin: Watcher channel closed. Exiting loop.");
                     break;
                 }
@@ -339,78 +491,38 @@ impl Plugin for WatchPlugin {
                 }
             };
 
-            let now = Instant::now();
-            let since_last = now.duration_since(last_run);
-            if since_last < Duration::from_millis(max_debounce) {
-                debug!("Debouncing event (too soon after last run)");
-                continue;
-            }
-            last_run = now;
+            let matched: Vec<PathBuf> = self
+                .watch_entries
+                .iter()
+                .flat_map(|entry| self.filter_changed_paths(&event.paths, entry))
+                .map(|path| {
+                    path.strip_prefix(&cwd)
+                        .map(Path::to_path_buf)
+                        .unwrap_or(path)
+                })
+                .collect();
+            debouncer.add(matched, Instant::now());
+        }
 
-            let changed_paths = event.paths;
-            if changed_paths.is_empty() {
-                continue;
-            }
-            // For each watch entry, see if anything matched
-            for entry in &self.watch_entries {
-                let matched = self.filter_changed_paths(&changed_paths, entry);
-                if !matched.is_empty() {
-                    println!(
-                        "Files changed for task '{}': re-running pipeline...",
-                        entry.task_name
-                    );
-                    // Re-run the entire plugin pipeline if desired.
-                    // We'll forcibly rebuild everything in a fresh manager or re-run the same manager.
-
-                    // For demonstration, let's do a trivial approach:
-                    // Re-run the entire pipeline from scratch.
-                    // Usually you'd keep a reference to GraphManager:
-                    let mut new_manager = crate::manager::GraphManager::new();
-                    new_manager.build_graph(graph_manager_config_snapshot()?)?;
-                    // Re-register the same plugins with updated watch mode, etc.
-                    new_manager
-                        .register_plugin(Box::new(crate::plugins::env_plugin::EnvPlugin::new()));
-                    new_manager
-                        .register_plugin(Box::new(crate::plugins::path_plugin::PathPlugin::new()));
-                    new_manager.register_plugin(Box::new(
-                        crate::plugins::concurrent_plugin::ConcurrentPlugin::new(),
-                    ));
-                    new_manager.register_plugin(Box::new(
-                        crate::plugins::prefix_plugin::PrefixPlugin::new(),
-                    ));
-                    new_manager
-                        .register_plugin(Box::new(WatchPlugin::new(true, self.stop_on_fail)));
-                    new_manager.register_plugin(Box::new(
-                        crate::plugins::execution_plugin::ExecutionPlugin::new(),
-                    ));
-                    new_manager.register_plugin(Box::new(
-                        crate::plugins::timeout_plugin::TimeoutPlugin::new(),
-                    ));
-
-                    // If we had some way to remember which task triggered, we could pass that again.
-                    // For demonstration, we pass the same 'entry.task_name':
-                    let mut options = serde_json::Map::new();
-                    options.insert(
-                        "task".to_string(),
-                        serde_json::Value::String(entry.task_name.clone()),
-                    );
-                    let plugin_config = PluginConfig {
-                        fail_fast: true,
-                        watch: true,
-                        list: false,
-                        dry_run: false,
-                        options: Some(options),
-                    };
-                    if let Err(e) = new_manager.run_plugins(Some(plugin_config)) {
-                        error!("WatchPlugin: re-run failed: {}", e);
-                        if self.stop_on_fail {
-                            warn!("WatchPlugin: Stopping watch loop due to re-run failure");
-                            return Ok(());
-                        }
-                    }
+        if let Some(current) = run.take() {
+            current.stop();
+        }
+        Ok(())
+    }
+}
+
+impl WatchPlugin {
+    /// Every node `start` depends on, directly or indirectly, including `start` itself.
+    fn reachable_from(graph: &Graph, start: NodeId) -> HashSet<NodeId> {
+        let mut seen = HashSet::from([start]);
+        let mut stack = vec![start];
+        while let Some(id) = stack.pop() {
+            for edge in graph.edges.iter().filter(|e| e.from == id) {
+                if seen.insert(edge.to) {
+                    stack.push(edge.to);
                 }
             }
         }
-        Ok(())
+        seen
     }
 }
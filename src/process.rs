@@ -1,13 +1,17 @@
 use log::{debug, error, info, warn};
 use std::{
+    collections::HashMap,
     io::{BufRead, BufReader},
     process::{Child, Command, Stdio},
     sync::atomic::{AtomicBool, Ordering},
-    sync::Arc,
+    sync::{Arc, Mutex},
     thread::{self, JoinHandle},
+    time::{Duration, Instant},
 };
 
+use crate::config::SandboxConfig;
 use crate::errors::BodoError;
+use crate::sandbox::sandboxed_command;
 use colored::{Color, Colorize};
 
 pub struct ChildProcess {
@@ -15,11 +19,51 @@ pub struct ChildProcess {
     pub child: Child,
     pub stdout_handle: Option<JoinHandle<()>>,
     pub stderr_handle: Option<JoinHandle<()>>,
+    pub timeout: Option<Duration>,
+    pub started: Instant,
+    /// Whether the child leads its own process group
+    pub own_group: bool,
 }
 
 pub struct ProcessManager {
     pub children: Vec<ChildProcess>,
     pub fail_fast: bool,
+    /// How long a timed-out process group gets between SIGTERM and SIGKILL
+    pub grace_period: Duration,
+    /// Set to stop every running child; shared with other managers via `with_cancel_flag`
+    cancel: Arc<AtomicBool>,
+}
+
+/// Default time between SIGTERM and SIGKILL when a task times out.
+pub const DEFAULT_GRACE_PERIOD: Duration = Duration::from_secs(5);
+
+/// How long output is still read after a run is cancelled.
+const OUTPUT_DRAIN: Duration = Duration::from_millis(200);
+
+/// How a child process finished.
+enum Exit {
+    Code(i32),
+    TimedOut(Duration),
+    /// Stopped because a sibling failed or the run was cancelled
+    Cancelled,
+}
+
+/// Everything needed to spawn a single command besides its name and command line.
+#[derive(Debug, Clone, Default)]
+pub struct SpawnOptions {
+    pub prefix_enabled: bool,
+    pub prefix_label: Option<String>,
+    pub prefix_color: Option<String>,
+    pub working_dir: Option<String>,
+    /// Variables set on the child on top of the inherited environment (including PATH)
+    pub env: HashMap<String, String>,
+    /// Kill the child (and everything it spawned) once it runs longer than this
+    pub timeout: Option<Duration>,
+    /// Start the child in its own process group, so stopping it also stops everything it
+    /// spawned. Always the case when `timeout` is set.
+    pub own_group: bool,
+    /// Run the command in a sandbox with this policy
+    pub sandbox: Option<SandboxConfig>,
 }
 
 impl ProcessManager {
@@ -28,9 +72,18 @@ impl ProcessManager {
         Self {
             children: Vec::new(),
             fail_fast,
+            grace_period: DEFAULT_GRACE_PERIOD,
+            cancel: Arc::new(AtomicBool::new(false)),
         }
     }
 
+    /// Share a cancellation flag with other managers: when any of them fails fast, all
+    /// their children are stopped.
+    pub fn with_cancel_flag(mut self, cancel: Arc<AtomicBool>) -> Self {
+        self.cancel = cancel;
+        self
+    }
+
     pub fn spawn_command(
         &mut self,
         name: &str,
@@ -40,12 +93,44 @@ impl ProcessManager {
         prefix_color: Option<String>,
         working_dir: Option<&str>,
     ) -> std::io::Result<()> {
+        self.spawn_with_options(
+            name,
+            cmd,
+            SpawnOptions {
+                prefix_enabled,
+                prefix_label,
+                prefix_color,
+                working_dir: working_dir.map(String::from),
+                ..Default::default()
+            },
+        )
+    }
+
+    pub fn spawn_with_options(
+        &mut self,
+        name: &str,
+        cmd: &str,
+        options: SpawnOptions,
+    ) -> std::io::Result<()> {
+        let SpawnOptions {
+            prefix_enabled,
+            prefix_label,
+            prefix_color,
+            working_dir,
+            env,
+            timeout,
+            own_group,
+            sandbox,
+        } = options;
+        let own_group = cfg!(unix) && (own_group || timeout.is_some());
         debug!(
             "Spawning command '{}' (prefix={}, label={:?}, color={:?}, working_dir={:?})",
             cmd, prefix_enabled, prefix_label, prefix_color, working_dir
         );
 
-        let mut command = if cfg!(target_os = "windows") {
+        let mut command = if let Some(policy) = &sandbox {
+            sandboxed_command(policy, cmd, working_dir.as_deref())?
+        } else if cfg!(target_os = "windows") {
             let mut cmd_command = Command::new("cmd");
             cmd_command.arg("/C").arg(cmd);
             cmd_command
@@ -59,9 +144,19 @@ impl ProcessManager {
             command.current_dir(dir);
         }
 
+        command.envs(&env);
+
+        // Give the child its own process group so the whole tree can be killed at once.
+        #[cfg(unix)]
+        if own_group {
+            use std::os::unix::process::CommandExt;
+            command.process_group(0);
+        }
+
         command.stdout(Stdio::piped()).stderr(Stdio::piped());
 
         let mut child = command.spawn()?;
+        let started = Instant::now();
 
         let stdout = child.stdout.take();
         let stderr = child.stderr.take();
@@ -107,30 +202,46 @@ impl ProcessManager {
             child,
             stdout_handle,
             stderr_handle,
+            timeout,
+            started,
+            own_group,
         });
 
         Ok(())
     }
 
-    pub fn run_concurrently(&mut self) -> std::io::Result<()> {
+    pub fn run_concurrently(&mut self) -> Result<(), BodoError> {
         debug!("Running {} processes concurrently", self.children.len());
 
         let children = std::mem::take(&mut self.children);
         let len = children.len();
 
-        // Create a shared flag for fail-fast coordination
-        let should_terminate = Arc::new(AtomicBool::new(false));
+        // Shared flag for fail-fast coordination
+        let should_terminate = self.cancel.clone();
+        // Children that failed, in the order they did: the first one is the one to blame
+        let failures = Arc::new(Mutex::new(Vec::new()));
 
         // Create a vector to store the wait futures
         let mut wait_handles = Vec::with_capacity(len);
         let mut io_handles = Vec::with_capacity(len);
 
         // Move each child into its own thread
-        for mut child_info in children {
+        for (index, mut child_info) in children.into_iter().enumerate() {
             let name = child_info.name.clone();
             let stdout_handle = child_info.stdout_handle.take();
             let stderr_handle = child_info.stderr_handle.take();
             let fail_fast = self.fail_fast;
+            let grace_period = self.grace_period;
+            let fail = {
+                let failures = failures.clone();
+                let should_terminate = should_terminate.clone();
+                move || {
+                    failures.lock().unwrap().push(index);
+                    if fail_fast {
+                        should_terminate.store(true, Ordering::SeqCst);
+                    }
+                }
+            };
             let should_terminate = should_terminate.clone();
 
             let handle = thread::spawn(move || {
@@ -138,21 +249,38 @@ impl ProcessManager {
                 loop {
                     if should_terminate.load(Ordering::SeqCst) {
                         debug!("Process '{}' received termination signal", name);
-                        let _ = child_info.child.kill();
-                        break Ok::<(String, i32, bool), std::io::Error>((name, -1, fail_fast));
+                        // Nothing else stops a child in its own group: a terminal's Ctrl-C
+                        // only reaches bodo's group.
+                        if child_info.own_group {
+                            terminate_tree(&mut child_info.child, grace_period)?;
+                        } else {
+                            let _ = child_info.child.kill();
+                            child_info.child.wait()?;
+                        }
+                        break Ok::<(String, Exit), std::io::Error>((name, Exit::Cancelled));
+                    }
+
+                    if let Some(timeout) = child_info.timeout {
+                        let elapsed = child_info.started.elapsed();
+                        if elapsed >= timeout {
+                            warn!("Process '{}' timed out after {:?}", name, elapsed);
+                            terminate_tree(&mut child_info.child, grace_period)?;
+                            fail();
+                            break Ok((name, Exit::TimedOut(elapsed)));
+                        }
                     }
 
                     match child_info.child.try_wait()? {
                         Some(status) => {
                             let code = status.code().unwrap_or(-1);
-                            if code != 0 && fail_fast {
-                                should_terminate.store(true, Ordering::SeqCst);
+                            if code != 0 {
+                                fail();
                             }
-                            break Ok((name, code, fail_fast));
+                            break Ok((name, Exit::Code(code)));
                         }
                         None => {
                             // Process still running, sleep briefly then check again
-                            thread::sleep(std::time::Duration::from_millis(10));
+                            thread::sleep(Duration::from_millis(10));
                         }
                     }
                 }
@@ -169,29 +297,43 @@ impl ProcessManager {
             }
         }
 
-        // Wait for all processes to complete
-        for handle in wait_handles {
-            match handle.join().unwrap() {
-                Ok((name, code, _)) => {
-                    if code != 0 {
-                        return Err(std::io::Error::other(format!(
-                            "Process '{}' failed with exit code {}",
-                            name, code
-                        )));
-                    }
-                }
-                Err(e) => {
-                    return Err(e);
-                }
+        // Wait for every process, even after a failure, so none is left unreaped and all
+        // of their output is printed
+        let exits: Vec<std::io::Result<(String, Exit)>> = wait_handles
+            .into_iter()
+            .map(|handle| handle.join().unwrap())
+            .collect();
+        // What a cancelled child spawned outside its own group may outlive it and keep its
+        // output open: let what is left drain for a moment, then stop waiting for it.
+        let cancelled = exits
+            .iter()
+            .any(|exit| matches!(exit, Ok((_, Exit::Cancelled))));
+        let deadline = Instant::now() + OUTPUT_DRAIN;
+        for handle in io_handles {
+            while cancelled && !handle.is_finished() && Instant::now() < deadline {
+                thread::sleep(Duration::from_millis(10));
+            }
+            if !cancelled || handle.is_finished() {
+                let _ = handle.join();
             }
         }
 
-        // Wait for all IO threads to complete
-        for handle in io_handles {
-            let _ = handle.join();
+        let first_failure = failures.lock().unwrap().first().copied();
+        let mut exits = exits.into_iter();
+        let blamed = match first_failure {
+            Some(index) => exits.nth(index),
+            None => exits.find(|exit| !matches!(exit, Ok((_, Exit::Code(0))))),
+        };
+        match blamed.transpose()? {
+            None | Some((_, Exit::Code(0))) => Ok(()),
+            Some((name, Exit::Code(code))) => Err(BodoError::IoError(std::io::Error::other(
+                format!("Process '{}' failed with exit code {}", name, code),
+            ))),
+            Some((name, Exit::TimedOut(elapsed))) => Err(BodoError::Timeout(name, elapsed)),
+            Some((name, Exit::Cancelled)) => Err(BodoError::IoError(std::io::Error::other(
+                format!("Process '{}' was stopped", name),
+            ))),
         }
-
-        Ok(())
     }
 
     pub fn kill_all(&mut self) -> Result<(), BodoError> {
@@ -205,6 +347,68 @@ impl ProcessManager {
     }
 }
 
+/// Send SIGTERM to the child's process group, give it `grace_period` to exit, then SIGKILL it.
+fn terminate_tree(child: &mut Child, grace_period: Duration) -> std::io::Result<()> {
+    signal_tree(child, Signal::Term);
+    let deadline = Instant::now() + grace_period;
+    while Instant::now() < deadline && !has_exited(child)? {
+        thread::sleep(Duration::from_millis(10));
+    }
+    // Until the leader is reaped its pid, and so the group id, cannot be reused: make sure
+    // nothing it spawned survives before reaping it.
+    signal_tree(child, Signal::Kill);
+    child.wait()?;
+    Ok(())
+}
+
+/// Whether the child has exited, without reaping it.
+#[cfg(unix)]
+fn has_exited(child: &mut Child) -> std::io::Result<bool> {
+    // SAFETY: `info` is a valid siginfo_t to write to; with WNOWAIT the child is left
+    // waitable, so `Child` still owns its exit status.
+    unsafe {
+        let mut info: libc::siginfo_t = std::mem::zeroed();
+        if libc::waitid(
+            libc::P_PID,
+            child.id() as libc::id_t,
+            &mut info,
+            libc::WEXITED | libc::WNOHANG | libc::WNOWAIT,
+        ) == -1
+        {
+            return Err(std::io::Error::last_os_error());
+        }
+        Ok(info.si_pid() != 0)
+    }
+}
+
+#[cfg(not(unix))]
+fn has_exited(child: &mut Child) -> std::io::Result<bool> {
+    Ok(child.try_wait()?.is_some())
+}
+
+enum Signal {
+    Term,
+    Kill,
+}
+
+#[cfg(unix)]
+fn signal_tree(child: &mut Child, signal: Signal) {
+    let signal = match signal {
+        Signal::Term => libc::SIGTERM,
+        Signal::Kill => libc::SIGKILL,
+    };
+    // SAFETY: kill(2) has no memory-safety preconditions; a negative pid targets the
+    // process group the child leads (see `process_group(0)` in `spawn_with_options`).
+    unsafe {
+        libc::kill(-(child.id() as libc::pid_t), signal);
+    }
+}
+
+#[cfg(not(unix))]
+fn signal_tree(child: &mut Child, _signal: Signal) {
+    let _ = child.kill();
+}
+
 pub fn color_line(
     prefix: &str,
     prefix_color: &Option<String>,
@@ -220,6 +424,26 @@ pub fn color_line(
     format!("{} {}", colored_prefix, line)
 }
 
+/// The names [`parse_color`] knows, in any case.
+pub const COLOR_NAMES: &[&str] = &[
+    "black",
+    "red",
+    "green",
+    "yellow",
+    "blue",
+    "magenta",
+    "cyan",
+    "white",
+    "brightblack",
+    "brightred",
+    "brightgreen",
+    "brightyellow",
+    "brightblue",
+    "brightmagenta",
+    "brightcyan",
+    "brightwhite",
+];
+
 pub fn parse_color(c: &str) -> Option<Color> {
     debug!("Parsing color: {}", c);
     match c.to_lowercase().as_str() {
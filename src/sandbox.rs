@@ -1,17 +1,140 @@
-//! Sandbox module for safe command execution in dry-run mode
+//! Sandbox module for safe command execution
 //!
-//! This module provides sandboxing capabilities using bubblewrap (bwrap) or firejail
-//! to execute commands in an isolated environment where filesystem changes can be
-//! monitored without affecting the actual system.
-
-use std::collections::HashSet;
+//! This module provides sandboxing capabilities using bubblewrap (bwrap) or firejail.
+//! [`SandboxBackend`] runs tasks under a [`SandboxConfig`] policy for `bodo --sandbox`;
+//! [`Sandbox`] executes commands in an isolated environment where filesystem changes can
+//! be monitored without affecting the actual system, for `--dry-run --side-effects`. When
+//! `strace` works, [`Sandbox`] also traces the command's system calls (see [`crate::trace`]).
+
+use std::collections::{HashMap, HashSet};
+use std::ffi::OsString;
 use std::fs;
+use std::hash::Hasher;
+use std::io::Read;
 use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::sync::OnceLock;
+use std::time::SystemTime;
 use tempfile::TempDir;
+use walkdir::WalkDir;
 
+use crate::config::SandboxConfig;
 use crate::errors::{BodoError, Result};
 use crate::plugin::SideEffect;
+use crate::trace::{read_traces, Strace};
+
+/// Node metadata holding a task's sandbox policy, as JSON.
+pub const SANDBOX_KEY: &str = "sandbox";
+
+/// Where traces are written inside the sandbox's `tmp/`, one file per process.
+const TRACE_FILE: &str = ".bodo-trace";
+
+/// Directories left out when the working directory is copied into the analysis sandbox:
+/// they are large and rarely what a side effect is about.
+const COPY_SKIP: &[&str] = &[".git", "target", "node_modules"];
+
+/// How many bytes of the working directory are copied into the analysis sandbox per
+/// command. Larger trees are copied only in part, so analysis stays fast and bounded.
+const COPY_LIMIT: u64 = 64 * 1024 * 1024;
+
+/// System directories every sandboxed command can read, when they exist.
+pub(crate) const SYSTEM_DIRS: &[&str] =
+    &["/usr", "/bin", "/sbin", "/lib", "/lib32", "/lib64", "/etc"];
+
+/// The tool that isolates sandboxed tasks.
+///
+/// With bubblewrap only the system directories, the working directory and the policy's
+/// paths exist inside the sandbox. Firejail cannot hide paths: the whole filesystem stays
+/// readable and only the writable paths can be changed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SandboxBackend {
+    Bwrap,
+    Firejail,
+}
+
+impl SandboxBackend {
+    /// The first of bwrap and firejail found on PATH, looked up once.
+    pub fn detect() -> Option<Self> {
+        static DETECTED: OnceLock<Option<SandboxBackend>> = OnceLock::new();
+        *DETECTED.get_or_init(|| {
+            let available = |tool: &str| {
+                Command::new("which")
+                    .arg(tool)
+                    .output()
+                    .map(|o| o.status.success())
+                    .unwrap_or(false)
+            };
+            if available("bwrap") {
+                Some(Self::Bwrap)
+            } else if available("firejail") {
+                Some(Self::Firejail)
+            } else {
+                None
+            }
+        })
+    }
+
+    /// A command running `cmd` through `sh -c` in `working_dir`, confined by `policy`.
+    pub fn command(self, policy: &SandboxConfig, cmd: &str, working_dir: &Path) -> Command {
+        let resolve = |path: &String| working_dir.join(path);
+        let mut command = match self {
+            Self::Bwrap => {
+                let mut command = Command::new("bwrap");
+                command
+                    .args(["--die-with-parent", "--new-session", "--unshare-all"])
+                    .args(policy.network.then_some("--share-net"));
+                for dir in SYSTEM_DIRS {
+                    command.args(["--ro-bind-try", dir, dir]);
+                }
+                command.args(["--dev", "/dev", "--proc", "/proc", "--tmpfs", "/tmp"]);
+                for path in policy.read_only.iter().map(resolve) {
+                    command.arg("--ro-bind-try").arg(&path).arg(&path);
+                }
+                command.arg("--bind").arg(working_dir).arg(working_dir);
+                for path in policy.writable.iter().map(resolve) {
+                    command.arg("--bind-try").arg(&path).arg(&path);
+                }
+                command.arg("--chdir").arg(working_dir);
+                command
+            }
+            Self::Firejail => {
+                let mut command = Command::new("firejail");
+                command
+                    .args(["--quiet", "--noprofile", "--private-tmp", "--read-only=/"])
+                    .args((!policy.network).then_some("--net=none"));
+                command.arg(format!("--read-write={}", working_dir.display()));
+                for path in policy.writable.iter().map(resolve) {
+                    command.arg(format!("--read-write={}", path.display()));
+                }
+                command.arg("--");
+                command
+            }
+        };
+        command.current_dir(working_dir).args(["sh", "-c", cmd]);
+        command
+    }
+}
+
+/// A command running `cmd` under `policy` with the detected backend. Fails when neither
+/// bwrap nor firejail is installed, rather than running the command unconfined.
+pub fn sandboxed_command(
+    policy: &SandboxConfig,
+    cmd: &str,
+    working_dir: Option<&str>,
+) -> std::io::Result<Command> {
+    let backend = SandboxBackend::detect().ok_or_else(|| {
+        std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            "sandboxed tasks need bubblewrap (bwrap) or firejail, and neither is on PATH",
+        )
+    })?;
+    let cwd = std::env::current_dir()?;
+    let working_dir = match working_dir {
+        Some(dir) => cwd.join(dir),
+        None => cwd,
+    };
+    Ok(backend.command(policy, cmd, &working_dir))
+}
 
 /// Sandbox implementation for safe command execution
 pub struct Sandbox {
@@ -20,10 +143,8 @@ pub struct Sandbox {
     temp_dir: TempDir,
     /// Path to the sandbox root
     sandbox_root: PathBuf,
-    /// Whether bubblewrap is available
-    has_bwrap: bool,
-    /// Whether firejail is available
-    has_firejail: bool,
+    /// The tool commands are analysed in, if any is installed
+    backend: Option<SandboxBackend>,
 }
 
 impl Sandbox {
@@ -34,24 +155,10 @@ impl Sandbox {
 
         let sandbox_root = temp_dir.path().to_path_buf();
 
-        // Check for available sandboxing tools
-        let has_bwrap = Command::new("which")
-            .arg("bwrap")
-            .output()
-            .map(|o| o.status.success())
-            .unwrap_or(false);
-
-        let has_firejail = Command::new("which")
-            .arg("firejail")
-            .output()
-            .map(|o| o.status.success())
-            .unwrap_or(false);
-
         Ok(Self {
             temp_dir,
             sandbox_root,
-            has_bwrap,
-            has_firejail,
+            backend: SandboxBackend::detect(),
         })
     }
 
@@ -62,22 +169,41 @@ impl Sandbox {
         working_dir: &Path,
         env: &std::collections::HashMap<String, String>,
     ) -> Result<Vec<SideEffect>> {
+        // Running the command without isolation would let it change the real system
+        let Some(backend) = self.backend else {
+            return Err(BodoError::PluginError(
+                "side-effect analysis needs bubblewrap (bwrap) or firejail, and neither is on \
+                 PATH; the command was not run"
+                    .to_string(),
+            ));
+        };
+
         // Create sandbox directories
         self.setup_sandbox_dirs(working_dir)?;
 
         // Take snapshot of sandbox before execution
         let before_snapshot = self.take_filesystem_snapshot()?;
 
+        // Trace system calls when strace works. Firejail's private /tmp would hide the traces.
+        let tracer = match backend {
+            SandboxBackend::Bwrap => Strace::detect(),
+            SandboxBackend::Firejail => None,
+        };
+
         // Execute command in sandbox
-        let execution_result = if self.has_bwrap {
-            self.execute_with_bwrap(command, working_dir, env)?
-        } else if self.has_firejail {
-            self.execute_with_firejail(command, working_dir, env)?
-        } else {
-            // Fallback to restricted execution without containerization
-            self.execute_with_restrictions(command, working_dir, env)?
+        let execution_result = match backend {
+            SandboxBackend::Bwrap => self.execute_with_bwrap(command, working_dir, env, tracer)?,
+            SandboxBackend::Firejail => self.execute_with_firejail(command, working_dir, env)?,
         };
 
+        // Read the traces before the snapshot, which would report them as written files
+        let traced = tracer.and_then(|_| {
+            read_traces(
+                &self.sandbox_root.join("tmp").join(TRACE_FILE),
+                &self.bwrap_cwd(working_dir),
+            )
+        });
+
         // Take snapshot after execution
         let after_snapshot = self.take_filesystem_snapshot()?;
 
@@ -88,15 +214,34 @@ impl Sandbox {
         // Add process spawn side effect
         side_effects.push(SideEffect::ProcessSpawn(command.to_string()));
 
-        // Analyze command output for additional side effects
-        if let Some(additional) = self.analyze_command_output(&execution_result) {
-            side_effects.extend(additional);
+        match traced {
+            // The snapshot knows best how a path changed; the trace adds reads, network
+            // access, spawned programs and changes outside the sandbox.
+            Some(traced) => {
+                let shell = SideEffect::ProcessSpawn(format!("/bin/sh -c {}", command));
+                for effect in traced {
+                    let effect = effect.map_path(|path| self.traced_real_path(path));
+                    let covered = effect.path().is_some_and(|path| {
+                        side_effects.iter().any(|known| known.path() == Some(path))
+                    });
+                    if !covered && effect != shell && !side_effects.contains(&effect) {
+                        side_effects.push(effect);
+                    }
+                }
+            }
+            // Without a trace, guess network access from the command's output
+            None => {
+                if let Some(additional) = self.analyze_command_output(&execution_result) {
+                    side_effects.extend(additional);
+                }
+            }
         }
 
         Ok(side_effects)
     }
 
-    /// Setup sandbox directory structure
+    /// Setup sandbox directory structure, with a copy of the working directory at its
+    /// absolute path under `work/`, so commands see the project's files.
     fn setup_sandbox_dirs(&self, working_dir: &Path) -> Result<()> {
         // Create basic directory structure
         let dirs = ["tmp", "home", "work"];
@@ -107,47 +252,64 @@ impl Sandbox {
         }
 
         // Create working directory in sandbox
-        if working_dir.is_absolute() {
-            let relative_path = working_dir.strip_prefix("/").unwrap_or(working_dir);
-            let sandbox_work_dir = self.sandbox_root.join("work").join(relative_path);
-            fs::create_dir_all(&sandbox_work_dir)
-                .map_err(|e| BodoError::PluginError(format!("Failed to create work dir: {}", e)))?;
+        let sandbox_work_dir = self.work_dir(working_dir);
+        fs::create_dir_all(&sandbox_work_dir)
+            .map_err(|e| BodoError::PluginError(format!("Failed to create work dir: {}", e)))?;
+        if working_dir.is_dir() {
+            copy_tree(working_dir, &sandbox_work_dir, &self.sandbox_root);
         }
 
         Ok(())
     }
 
+    /// Where `working_dir` is mirrored inside the sandbox.
+    fn work_dir(&self, working_dir: &Path) -> PathBuf {
+        let absolute = std::env::current_dir()
+            .unwrap_or_default()
+            .join(working_dir);
+        let relative_path = absolute.strip_prefix("/").unwrap_or(&absolute);
+        self.sandbox_root.join("work").join(relative_path)
+    }
+
+    /// Where the copy of `working_dir` is inside bwrap, which mounts `work/` at `/work`.
+    fn bwrap_cwd(&self, working_dir: &Path) -> PathBuf {
+        Path::new("/work").join(
+            self.work_dir(working_dir)
+                .strip_prefix(self.sandbox_root.join("work"))
+                .unwrap_or(working_dir),
+        )
+    }
+
+    /// `sh -c command`, under strace when `tracer` is set, with traces written to
+    /// `trace_dir`/[`TRACE_FILE`] as the command sees it.
+    fn shell(&self, command: &str, tracer: Option<&Strace>, trace_dir: &Path) -> Vec<OsString> {
+        match tracer {
+            Some(strace) => strace.command_line(&trace_dir.join(TRACE_FILE), command),
+            None => ["/bin/sh", "-c", command].map(OsString::from).to_vec(),
+        }
+    }
+
     /// Execute command using bubblewrap
     fn execute_with_bwrap(
         &self,
         command: &str,
         working_dir: &Path,
         env: &std::collections::HashMap<String, String>,
+        tracer: Option<&Strace>,
     ) -> Result<CommandOutput> {
         let mut cmd = Command::new("bwrap");
 
-        // Basic isolation flags
+        // Isolation flags. The network is unshared too: connecting fails, and the attempt
+        // still shows up in the trace.
         cmd.arg("--unshare-all")
-            .arg("--share-net") // Allow network for analysis but could be disabled
+            .arg("--unshare-net")
             .arg("--die-with-parent")
             .arg("--new-session");
 
-        // Mount points
-        cmd.arg("--ro-bind")
-            .arg("/usr")
-            .arg("/usr")
-            .arg("--ro-bind")
-            .arg("/bin")
-            .arg("/bin")
-            .arg("--ro-bind")
-            .arg("/lib")
-            .arg("/lib")
-            .arg("--ro-bind")
-            .arg("/lib64")
-            .arg("/lib64")
-            .arg("--ro-bind")
-            .arg("/etc/resolv.conf")
-            .arg("/etc/resolv.conf");
+        // Mount points, skipping the ones this system does not have (e.g. /lib64)
+        for dir in SYSTEM_DIRS {
+            cmd.arg("--ro-bind-try").arg(dir).arg(dir);
+        }
 
         // Sandbox directories
         cmd.arg("--bind")
@@ -161,12 +323,7 @@ impl Sandbox {
             .arg("/work");
 
         // Set working directory
-        let sandbox_cwd = if working_dir.is_absolute() {
-            PathBuf::from("/work").join(working_dir.strip_prefix("/").unwrap_or(working_dir))
-        } else {
-            PathBuf::from("/work").join(working_dir)
-        };
-        cmd.arg("--chdir").arg(&sandbox_cwd);
+        cmd.arg("--chdir").arg(self.bwrap_cwd(working_dir));
 
         // Environment variables
         for (key, value) in env {
@@ -185,7 +342,7 @@ impl Sandbox {
             .arg("/usr/bin:/bin");
 
         // Execute the actual command
-        cmd.arg("/bin/sh").arg("-c").arg(command);
+        cmd.args(self.shell(command, tracer, Path::new("/tmp")));
 
         let output = cmd
             .output()
@@ -218,13 +375,11 @@ impl Sandbox {
             .arg("--nosound")
             .arg("--no3d");
 
-        // Network isolation (optional)
-        // cmd.arg("--net=none");
+        // No network, as under bwrap
+        cmd.arg("--net=none");
 
-        // Set working directory
-        if working_dir.is_absolute() {
-            cmd.current_dir(working_dir);
-        }
+        // Run in the copy of the working directory
+        cmd.current_dir(self.work_dir(working_dir));
 
         // Environment variables
         for (key, value) in env {
@@ -245,49 +400,9 @@ impl Sandbox {
         })
     }
 
-    /// Fallback execution with basic restrictions (when no sandbox tool is available)
-    fn execute_with_restrictions(
-        &self,
-        command: &str,
-        _working_dir: &Path,
-        env: &std::collections::HashMap<String, String>,
-    ) -> Result<CommandOutput> {
-        // Create a restricted environment
-        let mut cmd = Command::new("/bin/sh");
-        cmd.arg("-c").arg(command);
-
-        // Change to sandbox directory
-        let sandbox_work = self.sandbox_root.join("work");
-        cmd.current_dir(&sandbox_work);
-
-        // Set restricted environment
-        cmd.env_clear();
-        cmd.env("HOME", self.sandbox_root.join("home"))
-            .env("TMPDIR", self.sandbox_root.join("tmp"))
-            .env("PATH", "/usr/bin:/bin");
-
-        // Add user-specified environment variables
-        for (key, value) in env {
-            cmd.env(key, value);
-        }
-
-        // Note: This is less secure than bwrap/firejail but better than nothing
-        // Real filesystem operations will still occur but in the temp directory
-
-        let output = cmd
-            .output()
-            .map_err(|e| BodoError::PluginError(format!("Failed to execute command: {}", e)))?;
-
-        Ok(CommandOutput {
-            stdout: String::from_utf8_lossy(&output.stdout).to_string(),
-            stderr: String::from_utf8_lossy(&output.stderr).to_string(),
-            exit_code: output.status.code().unwrap_or(-1),
-        })
-    }
-
     /// Take a snapshot of the filesystem state
     fn take_filesystem_snapshot(&self) -> Result<FilesystemSnapshot> {
-        let mut files = HashSet::new();
+        let mut files = HashMap::new();
         let mut directories = HashSet::new();
 
         self.scan_directory(&self.sandbox_root, &mut files, &mut directories)?;
@@ -300,7 +415,7 @@ impl Sandbox {
     fn scan_directory(
         &self,
         dir: &Path,
-        files: &mut HashSet<PathBuf>,
+        files: &mut HashMap<PathBuf, FileState>,
         directories: &mut HashSet<PathBuf>,
     ) -> Result<()> {
         if !dir.exists() {
@@ -315,18 +430,26 @@ impl Sandbox {
                 .map_err(|e| BodoError::PluginError(format!("Failed to read entry: {}", e)))?;
             let path = entry.path();
 
-            if path.is_dir() {
+            let file_type = entry
+                .file_type()
+                .map_err(|e| BodoError::PluginError(format!("Failed to read entry: {}", e)))?;
+            if file_type.is_dir() {
                 directories.insert(path.clone());
                 self.scan_directory(&path, files, directories)?;
             } else {
-                files.insert(path);
+                let state = FileState::read(&path).map_err(|e| {
+                    BodoError::PluginError(format!("Failed to read {}: {}", path.display(), e))
+                })?;
+                files.insert(path, state);
             }
         }
 
         Ok(())
     }
 
-    /// Analyze filesystem changes between snapshots
+    /// Analyze filesystem changes between snapshots: created, modified and deleted files
+    /// and created directories, mapped back to the paths they mirror. Files count as
+    /// modified when their size, mtime or content hash changed.
     fn analyze_filesystem_changes(
         &self,
         before: &FilesystemSnapshot,
@@ -334,33 +457,50 @@ impl Sandbox {
     ) -> Result<Vec<SideEffect>> {
         let mut side_effects = Vec::new();
 
-        // Find new files (writes)
-        for file in &after.files {
-            if !before.files.contains(file) {
-                // Convert sandbox path to relative path
-                if let Ok(relative) = file.strip_prefix(&self.sandbox_root) {
-                    side_effects.push(SideEffect::FileWrite(PathBuf::from("/").join(relative)));
+        for (file, state) in &after.files {
+            match before.files.get(file) {
+                None => side_effects.push(SideEffect::FileWrite(self.real_path(file))),
+                Some(previous) if previous != state => {
+                    side_effects.push(SideEffect::FileModify(self.real_path(file)))
                 }
+                Some(_) => {}
             }
         }
-
-        // Find deleted files (could be tracked as a different side effect type)
-        for file in &before.files {
-            if !after.files.contains(file) {
-                // Could add a FileDelete side effect type if needed
-                if let Ok(relative) = file.strip_prefix(&self.sandbox_root) {
-                    // For now, we'll note it as a write operation (modification)
-                    side_effects.push(SideEffect::FileWrite(PathBuf::from("/").join(relative)));
-                }
+        for directory in after.directories.difference(&before.directories) {
+            side_effects.push(SideEffect::DirectoryCreate(self.real_path(directory)));
+        }
+        for file in before.files.keys() {
+            if !after.files.contains_key(file) {
+                side_effects.push(SideEffect::FileDelete(self.real_path(file)));
             }
         }
+        for directory in before.directories.difference(&after.directories) {
+            side_effects.push(SideEffect::FileDelete(self.real_path(directory)));
+        }
 
-        // Find modified files (simplified: checking if content changed would require checksums)
-        // This is a simplified version; a full implementation would compare file contents/timestamps
-
+        side_effects.sort_by(|a, b| a.path().cmp(&b.path()));
         Ok(side_effects)
     }
 
+    /// The path a file in the sandbox stands for: `work/` mirrors absolute paths, and
+    /// `tmp/` and `home/` stand for `/tmp` and `/home`.
+    fn real_path(&self, path: &Path) -> PathBuf {
+        let relative = path.strip_prefix(&self.sandbox_root).unwrap_or(path);
+        match relative.strip_prefix("work") {
+            Ok(mirrored) => Path::new("/").join(mirrored),
+            Err(_) => Path::new("/").join(relative),
+        }
+    }
+
+    /// The path a traced path stands for. Commands are only traced inside bwrap, which
+    /// mounts `work/`, `tmp/` and `home/` at `/work`, `/tmp` and `/home`.
+    fn traced_real_path(&self, path: PathBuf) -> PathBuf {
+        match path.strip_prefix("/work") {
+            Ok(mirrored) => Path::new("/").join(mirrored),
+            Err(_) => path,
+        }
+    }
+
     /// Analyze command output for additional side effects
     fn analyze_command_output(&self, output: &CommandOutput) -> Option<Vec<SideEffect>> {
         let mut side_effects = Vec::new();
@@ -399,11 +539,92 @@ struct CommandOutput {
 
 /// Snapshot of filesystem state
 struct FilesystemSnapshot {
-    files: HashSet<PathBuf>,
-    #[allow(dead_code)]
+    files: HashMap<PathBuf, FileState>,
     directories: HashSet<PathBuf>,
 }
 
+/// What a file looked like when the snapshot was taken.
+#[derive(Debug, PartialEq, Eq)]
+struct FileState {
+    size: u64,
+    modified: Option<SystemTime>,
+    hash: u64,
+}
+
+impl FileState {
+    fn read(path: &Path) -> std::io::Result<Self> {
+        let metadata = fs::symlink_metadata(path)?;
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        if metadata.is_file() {
+            let mut file = fs::File::open(path)?;
+            let mut buffer = [0u8; 8192];
+            loop {
+                let read = file.read(&mut buffer)?;
+                if read == 0 {
+                    break;
+                }
+                hasher.write(&buffer[..read]);
+            }
+        } else if metadata.file_type().is_symlink() {
+            hasher.write(fs::read_link(path)?.as_os_str().as_encoded_bytes());
+        }
+        Ok(Self {
+            size: metadata.len(),
+            modified: metadata.modified().ok(),
+            hash: hasher.finish(),
+        })
+    }
+}
+
+/// Copy the tree at `src` into `dst`, leaving out [`COPY_SKIP`] directories and the
+/// sandbox at `exclude` itself, up to [`COPY_LIMIT`] bytes. Symlinks are replaced by a copy
+/// of the file they point to, so writes cannot escape the copy; symlinks to directories are
+/// left out. Files that cannot be read are skipped, as the command could not read them either.
+fn copy_tree(src: &Path, dst: &Path, exclude: &Path) {
+    let entries = WalkDir::new(src)
+        .min_depth(1)
+        .into_iter()
+        .filter_entry(|entry| {
+            let skipped = entry.file_type().is_dir()
+                && COPY_SKIP.contains(&entry.file_name().to_string_lossy().as_ref());
+            !skipped && !entry.path().starts_with(exclude)
+        })
+        .filter_map(|entry| entry.ok());
+    let mut copied_bytes = 0;
+    let mut truncated = false;
+    for entry in entries {
+        let target = dst.join(entry.path().strip_prefix(src).unwrap_or(entry.path()));
+        let copied = if entry.file_type().is_dir() {
+            fs::create_dir_all(&target)
+        } else if entry.path().is_file() {
+            let size = fs::metadata(entry.path()).map_or(0, |m| m.len());
+            if copied_bytes + size > COPY_LIMIT {
+                truncated = true;
+                continue;
+            }
+            copied_bytes += size;
+            fs::copy(entry.path(), &target).map(|_| ())
+        } else {
+            Ok(())
+        };
+        if let Err(e) = copied {
+            log::debug!(
+                "Not copying {} into the sandbox: {}",
+                entry.path().display(),
+                e
+            );
+        }
+    }
+    if truncated {
+        log::warn!(
+            "{} holds more than {} MiB; only part of it was copied into the sandbox, so \
+             side effects on the other files may be misreported",
+            src.display(),
+            COPY_LIMIT / (1024 * 1024)
+        );
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1,11 +1,32 @@
-use crate::config::validate_task_name;
+use crate::config::{validate_task_name, Dependency, TaskConfig};
+use crate::diagnostic::{display_path, Diagnostic, KeyLocation, YamlKeys};
 use crate::errors::BodoError;
-use crate::graph::{Graph, NodeKind, TaskData};
+use crate::graph::{CommandData, EdgeKind, Graph, NodeId, NodeKind, TaskData};
+use crate::sandbox::SANDBOX_KEY;
+use crate::task_resolver::{resolve_task_ref, SCRIPT_LINE_KEY, SCRIPT_PATH_KEY};
 use crate::{BodoConfig, Result};
 use std::collections::HashMap;
 use std::fs;
+use std::path::{Path, PathBuf};
+use validator::{Validate, ValidationError, ValidationErrors, ValidationErrorsKind};
+use walkdir::WalkDir;
 
-pub struct ScriptLoader;
+pub struct ScriptLoader {
+    /// When checking, problems are collected here and loading goes on; otherwise the
+    /// first problem is returned as an error.
+    problems: Option<Vec<Diagnostic>>,
+}
+
+/// Settings a script file passes down to each of its tasks.
+struct ScriptScope<'a> {
+    namespace: &'a str,
+    /// Canonical path of the script file, used to resolve relative task references
+    script_path: Option<&'a Path>,
+    global_env: &'a HashMap<String, String>,
+    global_exec_paths: &'a Vec<String>,
+    script_env: &'a HashMap<String, String>,
+    script_exec_paths: &'a Vec<String>,
+}
 
 impl Default for ScriptLoader {
     fn default() -> Self {
@@ -15,124 +36,531 @@ impl Default for ScriptLoader {
 
 impl ScriptLoader {
     pub fn new() -> Self {
-        ScriptLoader
+        ScriptLoader { problems: None }
+    }
+
+    /// Load every script like [`build_graph`](Self::build_graph), but collect every problem
+    /// instead of stopping at the first: parse and validation errors, duplicate tasks and
+    /// dependencies that do not resolve. The graph holds whatever did load.
+    pub fn check_graph(config: BodoConfig) -> (Graph, Vec<Diagnostic>) {
+        let mut loader = ScriptLoader {
+            problems: Some(Vec::new()),
+        };
+        let graph = loader.build_graph(config);
+        let mut problems = loader.problems.unwrap_or_default();
+        match graph {
+            Ok(graph) => (graph, problems),
+            Err(err) => {
+                problems.push(Diagnostic::new(err.to_string(), Path::new(""), "", 1, 1, 1));
+                (Graph::new(), problems)
+            }
+        }
+    }
+
+    /// In strict mode `diagnostic` is returned as an error; when checking it is recorded.
+    fn report(&mut self, diagnostic: Diagnostic) -> Result<()> {
+        match &mut self.problems {
+            Some(problems) => {
+                problems.push(diagnostic);
+                Ok(())
+            }
+            None => Err(BodoError::ConfigError(Box::new(diagnostic))),
+        }
+    }
+
+    /// Like [`report`](Self::report) for an error about `field` of a loaded task: strict
+    /// mode returns `err` as is, checking records it at the field in the task's script.
+    pub(crate) fn report_task_error(
+        &mut self,
+        graph: &Graph,
+        task_id: NodeId,
+        field: &[&str],
+        err: BodoError,
+    ) -> Result<()> {
+        if self.problems.is_none() {
+            return Err(err);
+        }
+        let message = match err {
+            BodoError::ValidationError(message) => message,
+            other => other.to_string(),
+        };
+        self.report(Self::task_diagnostic(graph, task_id, field, message))
+    }
+
+    /// A diagnostic at `field` of the task `task_id`, or at the task itself when the field
+    /// is not written out in its script.
+    pub fn task_diagnostic(
+        graph: &Graph,
+        task_id: NodeId,
+        field: &[&str],
+        message: String,
+    ) -> Diagnostic {
+        let node = &graph.nodes[task_id as usize];
+        let path = node
+            .metadata
+            .get(SCRIPT_PATH_KEY)
+            .map(PathBuf::from)
+            .unwrap_or_default();
+        let content = fs::read_to_string(&path).unwrap_or_default();
+        let keys = YamlKeys::new(&content);
+        let mut full_path = match &node.kind {
+            NodeKind::Task(task) if task.is_default => vec!["default_task"],
+            NodeKind::Task(task) => vec!["tasks", task.name.as_str()],
+            _ => vec![],
+        };
+        let task_len = full_path.len();
+        full_path.extend(field);
+        let location = (task_len.max(1)..=full_path.len())
+            .rev()
+            .find_map(|len| keys.find(&full_path[..len]));
+        Self::diagnostic_at(message, &path, &content, location)
     }
 
     pub fn build_graph(&mut self, config: BodoConfig) -> Result<Graph> {
         let mut graph = Graph::new();
+        let mut root_path = None;
+        let global_env = config.env.clone();
+        let global_exec_paths = config.exec_paths.clone();
+
         // If a root_script is specified, load tasks from that file.
-        if let Some(root_script) = config.root_script {
-            let content = fs::read_to_string(&root_script)?;
-            let parsed: BodoConfig = serde_yaml::from_str(&content)?;
-            for (task_name, task_config) in parsed.tasks {
-                // Validate task name
-                validate_task_name(&task_name)
-                    .map_err(|e| BodoError::ValidationError(e.to_string()))?;
-                let full_key = format!("{} {}", root_script, task_name);
-                if graph.task_registry.contains_key(&full_key) {
-                    return Err(BodoError::ValidationError(format!(
-                        "duplicate task: {}",
-                        task_name
-                    )));
-                }
-                let task_data = TaskData {
-                    name: task_name.clone(),
-                    description: task_config.description,
-                    command: task_config.command,
-                    working_dir: task_config.cwd,
-                    env: task_config.env,
-                    exec_paths: task_config.exec_paths,
-                    arguments: task_config.arguments,
-                    is_default: false,
-                    script_id: root_script.clone(),
-                    script_display_name: root_script.clone(),
-                    watch: task_config.watch,
-                    pre_deps: task_config.pre_deps,
-                    post_deps: task_config.post_deps,
-                    concurrently: task_config.concurrently,
-                    concurrently_options: task_config.concurrently_options,
-                };
-                let node_id = graph.add_node(NodeKind::Task(task_data));
-                graph.task_registry.insert(full_key, node_id);
-            }
-            if let Some(default_task) = parsed.default_task {
-                let task_data = TaskData {
-                    name: "default".to_string(),
-                    description: default_task.description,
-                    command: default_task.command,
-                    working_dir: default_task.cwd,
-                    env: default_task.env,
-                    exec_paths: default_task.exec_paths,
-                    arguments: default_task.arguments,
-                    is_default: true,
-                    script_id: root_script.clone(),
-                    script_display_name: root_script.clone(),
-                    watch: default_task.watch,
-                    pre_deps: default_task.pre_deps,
-                    post_deps: default_task.post_deps,
-                    concurrently: default_task.concurrently,
-                    concurrently_options: default_task.concurrently_options,
-                };
-                let node_id = graph.add_node(NodeKind::Task(task_data));
-                graph.task_registry.insert("default".to_string(), node_id);
+        if let Some(root_script) = &config.root_script {
+            let path = PathBuf::from(root_script);
+            // When checking, a missing root script is fine as long as other scripts load.
+            if self.problems.is_none() || path.exists() {
+                self.load_script(&mut graph, &path, "", &global_env, &global_exec_paths)?;
             }
+            root_path = Some(path.canonicalize().unwrap_or(path));
         } else {
             // Process tasks directly from the configuration if no root_script is given.
+            let no_env = HashMap::new();
+            let no_paths = Vec::new();
+            let scope = ScriptScope {
+                namespace: "",
+                script_path: None,
+                global_env: &global_env,
+                global_exec_paths: &global_exec_paths,
+                script_env: &no_env,
+                script_exec_paths: &no_paths,
+            };
             for (task_name, task_config) in config.tasks {
                 validate_task_name(&task_name)
                     .map_err(|e| BodoError::ValidationError(e.to_string()))?;
-                if graph.task_registry.contains_key(&task_name) {
-                    return Err(BodoError::ValidationError(format!(
-                        "duplicate task: {}",
-                        task_name
-                    )));
-                }
-                let task_data = TaskData {
-                    name: task_name.clone(),
-                    description: task_config.description,
-                    command: task_config.command,
-                    working_dir: task_config.cwd,
-                    env: task_config.env,
-                    exec_paths: task_config.exec_paths,
-                    arguments: task_config.arguments,
-                    is_default: false,
-                    script_id: "".to_string(),
-                    script_display_name: "".to_string(),
-                    watch: task_config.watch,
-                    pre_deps: task_config.pre_deps,
-                    post_deps: task_config.post_deps,
-                    concurrently: task_config.concurrently,
-                    concurrently_options: task_config.concurrently_options,
-                };
-                let node_id = graph.add_node(NodeKind::Task(task_data));
-                graph.task_registry.insert(task_name, node_id);
+                Self::add_task(
+                    &mut graph,
+                    task_name.clone(),
+                    task_name,
+                    task_config,
+                    false,
+                    &scope,
+                )?;
             }
             if let Some(default_task) = config.default_task {
-                let task_data = TaskData {
-                    name: "default".to_string(),
-                    description: default_task.description,
-                    command: default_task.command,
-                    working_dir: default_task.cwd,
-                    env: default_task.env,
-                    exec_paths: default_task.exec_paths,
-                    arguments: default_task.arguments,
-                    is_default: true,
-                    script_id: "".to_string(),
-                    script_display_name: "".to_string(),
-                    watch: default_task.watch,
-                    pre_deps: default_task.pre_deps,
-                    post_deps: default_task.post_deps,
-                    concurrently: default_task.concurrently,
-                    concurrently_options: default_task.concurrently_options,
-                };
-                let node_id = graph.add_node(NodeKind::Task(task_data));
-                graph.task_registry.insert("default".to_string(), node_id);
+                Self::add_task(
+                    &mut graph,
+                    "default".to_string(),
+                    "default".to_string(),
+                    default_task,
+                    true,
+                    &scope,
+                )?;
             }
         }
+
+        // Discover every other script file under the configured scripts directories.
+        for scripts_dir in config.scripts_dirs.iter().flatten() {
+            let dir = Path::new(scripts_dir);
+            if !dir.is_dir() {
+                continue;
+            }
+            for script in Self::discover_scripts(dir) {
+                let canonical = script.canonicalize().unwrap_or_else(|_| script.clone());
+                if root_path.as_ref() == Some(&canonical) {
+                    continue;
+                }
+                let namespace = Self::script_namespace(dir, &script);
+                self.load_script(
+                    &mut graph,
+                    &script,
+                    &namespace,
+                    &global_env,
+                    &global_exec_paths,
+                )?;
+            }
+        }
+
+        self.resolve_dependencies(&mut graph)?;
         Ok(graph)
     }
 
-    // This function is intended for testing purposes.
+    /// Turn every task's `pre_deps` and `post_deps` into nodes and edges, keeping their declared order.
+    fn resolve_dependencies(&mut self, graph: &mut Graph) -> Result<()> {
+        let tasks: Vec<(NodeId, TaskData)> = graph
+            .nodes
+            .iter()
+            .filter_map(|node| match &node.kind {
+                NodeKind::Task(task) => Some((node.id, task.clone())),
+                _ => None,
+            })
+            .collect();
+
+        for (task_id, task) in tasks {
+            for (deps, kind, field) in [
+                (&task.pre_deps, EdgeKind::PreDep, "pre_deps"),
+                (&task.post_deps, EdgeKind::PostDep, "post_deps"),
+            ] {
+                for dep in deps {
+                    let dep_id = match dep {
+                        Dependency::Task { task: name } => {
+                            match resolve_task_ref(graph, task_id, name) {
+                                Ok(id) => id,
+                                Err(err) => {
+                                    self.report_task_error(graph, task_id, &[field], err)?;
+                                    continue;
+                                }
+                            }
+                        }
+                        Dependency::Command { command } => {
+                            let cmd_id = graph.add_node(NodeKind::Command(CommandData {
+                                raw_command: command.clone(),
+                                description: None,
+                                working_dir: task.working_dir.clone(),
+                                env: task.env.clone(),
+                                exec_paths: task.exec_paths.clone(),
+                                watch: None,
+                            }));
+                            // Commands run in the context of the task declaring them.
+                            if let Some(sources) = graph.nodes[task_id as usize]
+                                .metadata
+                                .get("env_sources")
+                                .cloned()
+                            {
+                                graph.nodes[cmd_id as usize]
+                                    .metadata
+                                    .insert("env_sources".to_string(), sources);
+                            }
+                            cmd_id
+                        }
+                    };
+                    graph.add_edge_of_kind(task_id, dep_id, kind)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Recursively find all YAML script files under `dir`, in a stable order.
+    pub fn discover_scripts(dir: &Path) -> Vec<PathBuf> {
+        WalkDir::new(dir)
+            .sort_by_file_name()
+            .into_iter()
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.file_type().is_file())
+            .map(|entry| entry.into_path())
+            .filter(|path| {
+                matches!(
+                    path.extension().and_then(|ext| ext.to_str()),
+                    Some("yaml") | Some("yml")
+                )
+            })
+            .collect()
+    }
+
+    /// Derive the namespace a script's tasks are registered under.
+    ///
+    /// `scripts/build/script.yaml` becomes `build`, `scripts/ci/test.yaml` becomes `ci/test`,
+    /// and `scripts/script.yaml` has an empty namespace.
+    pub fn script_namespace(scripts_dir: &Path, script: &Path) -> String {
+        let relative = script.strip_prefix(scripts_dir).unwrap_or(script);
+        let stem = relative.file_stem().unwrap_or_default();
+        let namespace = if stem == "script" {
+            relative.parent().map(Path::to_path_buf).unwrap_or_default()
+        } else {
+            relative.with_extension("")
+        };
+        namespace
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy().into_owned())
+            .collect::<Vec<_>>()
+            .join("/")
+    }
+
+    fn load_script(
+        &mut self,
+        graph: &mut Graph,
+        path: &Path,
+        namespace: &str,
+        global_env: &HashMap<String, String>,
+        global_exec_paths: &Vec<String>,
+    ) -> Result<()> {
+        let content = match fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(err) if self.problems.is_some() => {
+                return self.report(Diagnostic::new(err.to_string(), path, "", 1, 1, 1));
+            }
+            Err(err) => return Err(err.into()),
+        };
+        let parsed: BodoConfig = match serde_yaml::from_str(&content) {
+            Ok(parsed) => parsed,
+            Err(e) => return self.report(Diagnostic::from_yaml_error(&e, path, &content)),
+        };
+        // When checking, the tasks of a script with problems are still registered, so
+        // references to them do not show up as more problems.
+        for diagnostic in Self::check_script(path, &content, &parsed) {
+            self.report(diagnostic)?;
+        }
+        let canonical = path.canonicalize()?;
+        let scope = ScriptScope {
+            namespace,
+            script_path: Some(&canonical),
+            global_env,
+            global_exec_paths,
+            script_env: &parsed.env,
+            script_exec_paths: &parsed.exec_paths,
+        };
+
+        let keys = YamlKeys::new(&content);
+        for (task_name, task_config) in parsed.tasks.clone() {
+            let key = if namespace.is_empty() {
+                task_name.clone()
+            } else {
+                format!("{} {}", namespace, task_name)
+            };
+            if let Some(&existing) = graph.task_registry.get(&key) {
+                let location = keys.find(&["tasks", &task_name]);
+                let mut diagnostic = Self::diagnostic_at(
+                    format!("task '{}' is defined more than once", key),
+                    path,
+                    &content,
+                    location,
+                );
+                if let Some(other) = graph.nodes[existing as usize].metadata.get(SCRIPT_PATH_KEY) {
+                    diagnostic = diagnostic.with_note(format!(
+                        "also defined in {}",
+                        display_path(Path::new(other)).display()
+                    ));
+                }
+                self.report(diagnostic)?;
+                continue;
+            }
+            let node_id =
+                Self::add_task(graph, key, task_name.clone(), task_config, false, &scope)?;
+            Self::set_line(graph, node_id, keys.find(&["tasks", &task_name]));
+        }
+        if let Some(default_task) = parsed.default_task.clone() {
+            let key = if namespace.is_empty() {
+                "default".to_string()
+            } else {
+                namespace.to_string()
+            };
+            if graph.task_registry.contains_key(&key) {
+                return self.report(Self::diagnostic_at(
+                    format!("task '{}' is defined more than once", key),
+                    path,
+                    &content,
+                    keys.find(&["default_task"]),
+                ));
+            }
+            let node_id = Self::add_task(
+                graph,
+                key,
+                "default".to_string(),
+                default_task,
+                true,
+                &scope,
+            )?;
+            Self::set_line(graph, node_id, keys.find(&["default_task"]));
+        }
+        Ok(())
+    }
+
+    fn set_line(graph: &mut Graph, node_id: NodeId, location: Option<KeyLocation>) {
+        if let Some(location) = location {
+            graph.nodes[node_id as usize]
+                .metadata
+                .insert(SCRIPT_LINE_KEY.to_string(), location.line.to_string());
+        }
+    }
+
+    /// Everything wrong with a parsed script file that serde does not catch: duplicate
+    /// and invalid task names, tasks that do nothing, bad timeouts and other field checks.
+    /// Diagnostics are in source order.
+    pub fn check_script(path: &Path, content: &str, parsed: &BodoConfig) -> Vec<Diagnostic> {
+        let keys = YamlKeys::new(content);
+        let mut diagnostics: Vec<Diagnostic> = keys
+            .duplicates(&["tasks"])
+            .into_iter()
+            .map(|(name, first, repeated)| {
+                Self::diagnostic_at(
+                    format!("task '{}' is defined more than once", name),
+                    path,
+                    content,
+                    Some(repeated),
+                )
+                .with_note(format!("first defined on line {}", first.line))
+            })
+            .collect();
+
+        // Script-level fields; tasks are checked one by one below to point at each of them.
+        if let Err(errors) = parsed.validate() {
+            for (field, err) in flatten_validation_errors(&errors) {
+                if matches!(field[0].as_str(), "tasks" | "default_task") {
+                    continue;
+                }
+                diagnostics.push(Self::diagnostic_at(
+                    validation_message(&err, &field.join(".")),
+                    path,
+                    content,
+                    keys.find(&[field[0].as_str()]),
+                ));
+            }
+        }
+
+        let mut tasks: Vec<(Vec<&str>, &TaskConfig)> = parsed
+            .tasks
+            .iter()
+            .map(|(name, task)| (vec!["tasks", name.as_str()], task))
+            .collect();
+        if let Some(default_task) = &parsed.default_task {
+            tasks.push((vec!["default_task"], default_task));
+        }
+        for (task_path, task) in tasks {
+            if let ["tasks", name] = task_path.as_slice() {
+                if let Err(err) = validate_task_name(name) {
+                    diagnostics.push(Self::diagnostic_at(
+                        validation_message(&err, name),
+                        path,
+                        content,
+                        keys.find(&task_path),
+                    ));
+                }
+            }
+            if let Err(errors) = task.validate() {
+                for (field, err) in flatten_validation_errors(&errors) {
+                    // Schema-level errors belong to the task, except bad timeouts and args.
+                    let field: Vec<&str> = field.iter().map(String::as_str).collect();
+                    let field = match field.as_slice() {
+                        ["__all__"] if err.code == "invalid_timeout" => vec!["timeout"],
+                        ["__all__"] if err.code == "invalid_argument" => vec!["args"],
+                        ["__all__"] => vec![],
+                        _ => field,
+                    };
+                    let mut full_path = task_path.clone();
+                    full_path.extend(field.iter().copied());
+                    // Point at the closest key that is written out in the file.
+                    let location = (task_path.len()..=full_path.len())
+                        .rev()
+                        .find_map(|len| keys.find(&full_path[..len]));
+                    diagnostics.push(Self::diagnostic_at(
+                        validation_message(&err, &field.join(".")),
+                        path,
+                        content,
+                        location,
+                    ));
+                }
+            }
+        }
+        diagnostics.sort_by_key(|d| (d.line, d.column));
+        diagnostics
+    }
+
+    fn diagnostic_at(
+        message: String,
+        path: &Path,
+        content: &str,
+        location: Option<KeyLocation>,
+    ) -> Diagnostic {
+        let location = location.unwrap_or(KeyLocation {
+            line: 1,
+            column: 1,
+            width: 1,
+        });
+        Diagnostic::new(
+            message,
+            path,
+            content,
+            location.line,
+            location.column,
+            location.width,
+        )
+    }
+
+    /// Register a task, merging env and exec_paths from the global config, its script and
+    /// the task itself (later levels win) and recording where each value came from.
+    fn add_task(
+        graph: &mut Graph,
+        key: String,
+        name: String,
+        task_config: TaskConfig,
+        is_default: bool,
+        scope: &ScriptScope,
+    ) -> Result<NodeId> {
+        if graph.task_registry.contains_key(&key) {
+            return Err(BodoError::ValidationError(format!(
+                "duplicate task: {}",
+                key
+            )));
+        }
+        let env = Self::merge_envs(scope.global_env, scope.script_env, &task_config.env);
+        // The most specific paths come first so they win the PATH lookup.
+        let exec_paths = Self::merge_exec_paths(
+            &task_config.exec_paths,
+            scope.script_exec_paths,
+            scope.global_exec_paths,
+        );
+        let task_data = TaskData {
+            name,
+            description: task_config.description,
+            command: task_config.command,
+            working_dir: task_config.cwd,
+            env,
+            exec_paths,
+            arguments: task_config.arguments,
+            is_default,
+            script_id: scope.namespace.to_string(),
+            script_display_name: scope.namespace.to_string(),
+            watch: task_config.watch,
+            pre_deps: task_config.pre_deps,
+            post_deps: task_config.post_deps,
+            concurrently: task_config.concurrently,
+            concurrently_options: task_config.concurrently_options,
+        };
+        let node_id = graph.add_node(NodeKind::Task(task_data));
+        let node = &mut graph.nodes[node_id as usize];
+        if let Some(script_path) = scope.script_path {
+            node.metadata.insert(
+                SCRIPT_PATH_KEY.to_string(),
+                script_path.to_string_lossy().into_owned(),
+            );
+        }
+        if let Some(timeout) = task_config.timeout {
+            node.metadata.insert("timeout".to_string(), timeout);
+        }
+        if let Some(sandbox) = &task_config.sandbox {
+            node.metadata
+                .insert(SANDBOX_KEY.to_string(), serde_json::to_string(sandbox)?);
+        }
+        for (source, env) in [
+            ("global", scope.global_env),
+            ("script", scope.script_env),
+            ("task", &task_config.env),
+        ] {
+            for key in env.keys() {
+                node.set_env_source(key, source);
+            }
+        }
+        for (source, paths) in [
+            ("global", scope.global_exec_paths),
+            ("script", scope.script_exec_paths),
+            ("task", &task_config.exec_paths),
+        ] {
+            for path in paths {
+                node.set_exec_path_source(path, source);
+            }
+        }
+        graph.task_registry.insert(key, node_id);
+        Ok(node_id)
+    }
+
     pub fn merge_envs(
         global: &HashMap<String, String>,
         script: &HashMap<String, String>,
@@ -149,13 +577,13 @@ impl ScriptLoader {
     }
 
     pub fn merge_exec_paths(
-        global: &[String],
+        task: &[String],
         script: &Vec<String>,
-        task: &Vec<String>,
+        global: &Vec<String>,
     ) -> Vec<String> {
         let mut seen = std::collections::HashSet::new();
         let mut result = Vec::new();
-        for path in global.iter().chain(script).chain(task) {
+        for path in task.iter().chain(script).chain(global) {
             if seen.insert(path.clone()) {
                 result.push(path.clone());
             }
@@ -163,3 +591,43 @@ impl ScriptLoader {
         result
     }
 }
+
+/// Every error in `errors` with the path of the field it belongs to (`["watch", "patterns"]`,
+/// or `["__all__"]` for checks on the whole struct).
+fn flatten_validation_errors(errors: &ValidationErrors) -> Vec<(Vec<String>, ValidationError)> {
+    let mut flat = Vec::new();
+    let mut fields: Vec<_> = errors.errors().iter().collect();
+    fields.sort_by_key(|(field, _)| **field);
+    for (field, kind) in fields {
+        match kind {
+            ValidationErrorsKind::Field(field_errors) => {
+                for err in field_errors {
+                    flat.push((vec![field.to_string()], err.clone()));
+                }
+            }
+            ValidationErrorsKind::Struct(nested) => {
+                for (mut path, err) in flatten_validation_errors(nested) {
+                    path.insert(0, field.to_string());
+                    flat.push((path, err));
+                }
+            }
+            ValidationErrorsKind::List(items) => {
+                for nested in items.values() {
+                    for (mut path, err) in flatten_validation_errors(nested) {
+                        path.insert(0, field.to_string());
+                        flat.push((path, err));
+                    }
+                }
+            }
+        }
+    }
+    flat
+}
+
+fn validation_message(err: &ValidationError, field: &str) -> String {
+    match &err.message {
+        Some(message) => message.to_string(),
+        None if field.is_empty() => format!("invalid task ({})", err.code),
+        None => format!("invalid value for '{}' ({})", field, err.code),
+    }
+}
@@ -0,0 +1,38 @@
+//! "Did you mean?" suggestions for misspelled task names.
+
+/// Candidates close enough to `name` to be a likely typo, best match first (at most three).
+///
+/// Two names are close when their Damerau-Levenshtein distance is at most a third of the
+/// longer one (and at least 1), or when they only differ in case.
+pub fn suggest<'a>(name: &str, candidates: impl IntoIterator<Item = &'a str>) -> Vec<String> {
+    let mut scored: Vec<(usize, &str)> = candidates
+        .into_iter()
+        .filter(|candidate| *candidate != name)
+        .filter_map(|candidate| {
+            let distance = if candidate.eq_ignore_ascii_case(name) {
+                0
+            } else {
+                strsim::damerau_levenshtein(name, candidate)
+            };
+            let limit = (name.len().max(candidate.len()) / 3).max(1);
+            (distance <= limit).then_some((distance, candidate))
+        })
+        .collect();
+    scored.sort();
+    scored.dedup_by(|a, b| a.1 == b.1);
+    scored
+        .into_iter()
+        .take(3)
+        .map(|(_, candidate)| candidate.to_string())
+        .collect()
+}
+
+/// ` — did you mean 'build'?` for appending to an error message, or an empty string.
+pub fn did_you_mean(suggestions: &[String]) -> String {
+    let quoted: Vec<String> = suggestions.iter().map(|s| format!("'{}'", s)).collect();
+    match quoted.as_slice() {
+        [] => String::new(),
+        [only] => format!(" — did you mean {}?", only),
+        [rest @ .., last] => format!(" — did you mean {} or {}?", rest.join(", "), last),
+    }
+}
@@ -0,0 +1,224 @@
+//! Resolution of `task:` references in `pre_deps`, `post_deps` and `concurrently`.
+//!
+//! A reference is either a task name (`build`, `deploy prod`) or a path to another
+//! script file, optionally followed by a task name (`../ci/test.yaml`,
+//! `$BODO_PROJECT_ROOT/ci/test.yaml/unit_tests`). Paths are relative to the
+//! referencing file.
+
+use crate::errors::{BodoError, Result};
+use crate::graph::{Graph, NodeId, NodeKind};
+use crate::suggest::{did_you_mean, suggest};
+use std::path::{Path, PathBuf};
+
+/// Metadata key holding the canonical path of the script file a task was loaded from.
+pub const SCRIPT_PATH_KEY: &str = "script_path";
+
+/// Metadata key holding the 1-based line of the script file where a task is defined.
+pub const SCRIPT_LINE_KEY: &str = "script_line";
+
+/// Resolve `reference`, written in the task `from`, to a task node.
+///
+/// Plain names are looked up in the referencing task's own script first, then as a full
+/// registry key, then as a short name in any other script. A short name matching tasks in
+/// several scripts is an error listing every candidate.
+pub fn resolve_task_ref(graph: &Graph, from: NodeId, reference: &str) -> Result<NodeId> {
+    let (namespace, script_path) = match &graph.nodes[from as usize].kind {
+        NodeKind::Task(task) => (
+            task.script_id.as_str(),
+            graph.nodes[from as usize].metadata.get(SCRIPT_PATH_KEY),
+        ),
+        _ => ("", None),
+    };
+
+    if let Some((file, task)) = split_path_ref(reference) {
+        return resolve_path_ref(graph, from, script_path.map(Path::new), &file, task);
+    }
+
+    if !namespace.is_empty() {
+        if let Some(&id) = graph
+            .task_registry
+            .get(&format!("{} {}", namespace, reference))
+        {
+            return Ok(id);
+        }
+    }
+    if let Some(&id) = graph.task_registry.get(reference) {
+        return Ok(id);
+    }
+
+    let suffix = format!(" {}", reference);
+    let mut candidates: Vec<(&String, NodeId)> = graph
+        .task_registry
+        .iter()
+        .filter(|(key, _)| key.ends_with(&suffix))
+        .map(|(key, &id)| (key, id))
+        .collect();
+    candidates.sort();
+    match candidates.as_slice() {
+        [(_, id)] => Ok(*id),
+        [] => {
+            // Tasks of the same script can be referenced by their short name.
+            let prefix = format!("{} ", namespace);
+            let short_names = graph
+                .task_registry
+                .keys()
+                .filter_map(|key| key.strip_prefix(&prefix));
+            let candidates = graph
+                .task_registry
+                .keys()
+                .map(String::as_str)
+                .chain(short_names);
+            Err(BodoError::ValidationError(format!(
+                "task '{}' referenced by '{}' does not exist{}",
+                reference,
+                graph.node_name(from as usize),
+                did_you_mean(&suggest(reference, candidates))
+            )))
+        }
+        _ => Err(BodoError::ValidationError(format!(
+            "task '{}' referenced by '{}' is ambiguous; use one of: {}",
+            reference,
+            graph.node_name(from as usize),
+            candidates
+                .iter()
+                .map(|(key, _)| key.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        ))),
+    }
+}
+
+/// Split `../ci/test.yaml/unit_tests` into the script file and the optional task name.
+/// Returns `None` for references that do not point at a `.yaml`/`.yml` file.
+fn split_path_ref(reference: &str) -> Option<(String, Option<&str>)> {
+    let parts: Vec<&str> = reference.split('/').collect();
+    let file_end = parts
+        .iter()
+        .rposition(|part| part.ends_with(".yaml") || part.ends_with(".yml"))?;
+    let task = match &parts[file_end + 1..] {
+        [] => None,
+        [task] if !task.is_empty() => Some(*task),
+        _ => return None,
+    };
+    Some((parts[..=file_end].join("/"), task))
+}
+
+fn resolve_path_ref(
+    graph: &Graph,
+    from: NodeId,
+    script_path: Option<&Path>,
+    file: &str,
+    task: Option<&str>,
+) -> Result<NodeId> {
+    let expanded = PathBuf::from(expand_env(file));
+    let base = script_path
+        .and_then(Path::parent)
+        .map(Path::to_path_buf)
+        .unwrap_or_else(project_root);
+    let path = if expanded.is_absolute() {
+        expanded
+    } else {
+        base.join(expanded)
+    };
+    let canonical = path.canonicalize().ok();
+
+    // Find the namespace of the referenced file from the tasks loaded out of it.
+    let namespace = graph.nodes.iter().find_map(|node| match &node.kind {
+        NodeKind::Task(data)
+            if canonical.is_some()
+                && node.metadata.get(SCRIPT_PATH_KEY).map(PathBuf::from) == canonical =>
+        {
+            Some(data.script_id.clone())
+        }
+        _ => None,
+    });
+    let Some(namespace) = namespace else {
+        return Err(BodoError::ValidationError(format!(
+            "script '{}' referenced by '{}' does not exist or has no tasks",
+            path.display(),
+            graph.node_name(from as usize)
+        )));
+    };
+
+    let key = match (namespace.is_empty(), task) {
+        (true, None) => "default".to_string(),
+        (false, None) => namespace.clone(),
+        (true, Some(task)) => task.to_string(),
+        (false, Some(task)) => format!("{} {}", namespace, task),
+    };
+    graph.task_registry.get(&key).copied().ok_or_else(|| {
+        let prefix = format!("{} ", namespace);
+        let suggestions = match task {
+            Some(task) => suggest(
+                task,
+                graph.task_registry.keys().filter_map(|key| {
+                    if namespace.is_empty() {
+                        (!key.contains(' ')).then_some(key.as_str())
+                    } else {
+                        key.strip_prefix(&prefix)
+                    }
+                }),
+            ),
+            None => Vec::new(),
+        };
+        BodoError::ValidationError(format!(
+            "{} '{}' referenced by '{}' does not exist in {}{}",
+            if task.is_some() {
+                "task"
+            } else {
+                "default_task"
+            },
+            task.unwrap_or("default_task"),
+            graph.node_name(from as usize),
+            path.display(),
+            did_you_mean(&suggestions)
+        ))
+    })
+}
+
+/// The directory references without a referencing file are relative to:
+/// `$BODO_PROJECT_ROOT`, or the current directory.
+pub(crate) fn project_root() -> PathBuf {
+    std::env::var("BODO_PROJECT_ROOT")
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| std::env::current_dir().unwrap_or_default())
+}
+
+/// Expand `$VAR` and `${VAR}` from the process environment. `BODO_PROJECT_ROOT`
+/// defaults to the current directory; other unknown variables are left as written.
+fn expand_env(input: &str) -> String {
+    let lookup = |name: &str| -> Option<String> {
+        match std::env::var(name) {
+            Ok(value) => Some(value),
+            Err(_) if name == "BODO_PROJECT_ROOT" => {
+                Some(project_root().to_string_lossy().into_owned())
+            }
+            Err(_) => None,
+        }
+    };
+
+    let mut result = String::with_capacity(input.len());
+    let mut rest = input;
+    while let Some(pos) = rest.find('$') {
+        result.push_str(&rest[..pos]);
+        let after = &rest[pos + 1..];
+        let (name, consumed) = if let Some(braced) = after.strip_prefix('{') {
+            match braced.find('}') {
+                Some(end) => (&braced[..end], end + 2),
+                None => ("", 0),
+            }
+        } else {
+            let end = after
+                .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                .unwrap_or(after.len());
+            (&after[..end], end)
+        };
+        match (name.is_empty(), lookup(name)) {
+            (false, Some(value)) => result.push_str(&value),
+            _ => result.push_str(&rest[pos..pos + 1 + consumed]),
+        }
+        rest = &rest[pos + 1 + consumed..];
+    }
+    result.push_str(rest);
+    result
+}
@@ -0,0 +1,404 @@
+//! Side effects recorded from system calls with `strace`.
+//!
+//! When `strace` works on the system, side-effect analysis runs each command under it
+//! and maps the `open`, `unlink`, `mkdir`, `rename`, `connect` and `execve` calls it
+//! records to [`SideEffect`]s, instead of guessing them from the command's output.
+//!
+//! ```text
+//! openat(AT_FDCWD, "src/main.rs", O_RDONLY|O_CLOEXEC) = 3</work/src/main.rs>
+//! connect(3, {sa_family=AF_INET, sin_port=htons(443), sin_addr=inet_addr("93.184.215.14")}, 16) = 0
+//! ```
+
+use std::ffi::OsString;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::OnceLock;
+
+use crate::plugin::SideEffect;
+use crate::sandbox::SYSTEM_DIRS;
+
+/// The system calls that are traced: `chdir` to resolve relative paths, the rest to
+/// report side effects.
+pub const TRACED_SYSCALLS: &str = "trace=chdir,execve,open,openat,creat,unlink,unlinkat,\
+rmdir,mkdir,mkdirat,rename,renameat,renameat2,connect";
+
+/// Pseudo-filesystems, never reported. Reads from [`SYSTEM_DIRS`] are not reported
+/// either: every program reads them while starting up.
+const IGNORED_DIRS: &[&str] = &["/dev", "/proc", "/sys"];
+
+/// An `strace` binary that can trace processes here. Containers often forbid `ptrace`,
+/// so a trial run decides, not just whether `strace` is installed.
+#[derive(Debug)]
+pub struct Strace {
+    program: PathBuf,
+}
+
+impl Strace {
+    /// A working `strace` on PATH, looked up once.
+    pub fn detect() -> Option<&'static Strace> {
+        static DETECTED: OnceLock<Option<Strace>> = OnceLock::new();
+        DETECTED
+            .get_or_init(|| {
+                let output = Command::new("which").arg("strace").output().ok()?;
+                if !output.status.success() {
+                    return None;
+                }
+                let program = PathBuf::from(String::from_utf8_lossy(&output.stdout).trim());
+                let works = Command::new(&program)
+                    .args(["-f", "-qq", "-o", "/dev/null", "true"])
+                    .stdout(Stdio::null())
+                    .stderr(Stdio::null())
+                    .status()
+                    .is_ok_and(|status| status.success());
+                works.then_some(Strace { program })
+            })
+            .as_ref()
+    }
+
+    /// The program and arguments running `sh -c cmd` under strace, one trace file per
+    /// process at `output.<pid>`.
+    pub fn command_line(&self, output: &Path, cmd: &str) -> Vec<OsString> {
+        let mut args: Vec<OsString> = vec![self.program.clone().into()];
+        args.extend(
+            [
+                "-ff",
+                "-qq",
+                "-y",
+                "-s",
+                "4096",
+                "-e",
+                TRACED_SYSCALLS,
+                "-o",
+            ]
+            .map(OsString::from),
+        );
+        args.push(output.into());
+        args.extend(["/bin/sh", "-c", cmd].map(OsString::from));
+        args
+    }
+}
+
+/// Read and delete the trace files written to `output.<pid>`, returning the side effects
+/// they record, or `None` when there are none (strace did not run).
+pub fn read_traces(output: &Path, cwd: &Path) -> Option<Vec<SideEffect>> {
+    let dir = output.parent()?;
+    let prefix = format!("{}.", output.file_name()?.to_string_lossy());
+    let mut traces: Vec<PathBuf> = fs::read_dir(dir)
+        .ok()?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.file_name()
+                .is_some_and(|name| name.to_string_lossy().starts_with(&prefix))
+        })
+        .collect();
+    if traces.is_empty() {
+        return None;
+    }
+    // Process ids grow, so this is roughly the order the processes started in
+    traces.sort_by_key(|path| {
+        path.extension()
+            .and_then(|pid| pid.to_str()?.parse::<u32>().ok())
+            .unwrap_or(0)
+    });
+    let mut side_effects = Vec::new();
+    for trace in traces {
+        if let Ok(content) = fs::read_to_string(&trace) {
+            for effect in parse_trace(&content, cwd) {
+                if !side_effects.contains(&effect) {
+                    side_effects.push(effect);
+                }
+            }
+        }
+        let _ = fs::remove_file(&trace);
+    }
+    Some(side_effects)
+}
+
+/// The side effects in the trace of one process. Relative paths are resolved against
+/// `cwd`, following the process's own `chdir` calls; a child process is assumed to start
+/// in `cwd` too. Failed calls are left out, except `connect`, which counts as an attempt
+/// to reach the network.
+pub fn parse_trace(trace: &str, cwd: &Path) -> Vec<SideEffect> {
+    let mut cwd = cwd.to_path_buf();
+    let mut side_effects = Vec::new();
+    for line in trace.lines() {
+        let Some(call) = Syscall::parse(line) else {
+            continue;
+        };
+        let path = |index: usize| call.path(index, &cwd);
+        let effects = match call.name {
+            "chdir" if call.succeeded() => {
+                if let Some(dir) = path(0) {
+                    cwd = dir;
+                }
+                vec![]
+            }
+            "execve" if call.succeeded() => call
+                .args
+                .get(1)
+                .map(|argv| {
+                    let argv: Vec<String> = split_args(strip_delimiters(argv, '[', ']'))
+                        .iter()
+                        .map(|arg| unquote(arg))
+                        .collect();
+                    vec![SideEffect::ProcessSpawn(argv.join(" "))]
+                })
+                .unwrap_or_default(),
+            "open" | "openat" | "creat" if call.succeeded() => {
+                let (path_index, flags) = match call.name {
+                    "open" => (0, call.args.get(1).map(String::as_str)),
+                    "openat" => (1, call.args.get(2).map(String::as_str)),
+                    _ => (0, Some("O_CREAT|O_WRONLY|O_TRUNC")),
+                };
+                let flags = flags.unwrap_or_default();
+                let path = call.opened_path().or_else(|| path(path_index));
+                match path {
+                    _ if flags.contains("O_DIRECTORY") => vec![],
+                    Some(path) if is_write(flags) => vec![SideEffect::FileWrite(path)],
+                    Some(path) if !is_system(&path) => vec![SideEffect::FileRead(path)],
+                    _ => vec![],
+                }
+            }
+            "unlink" | "rmdir" if call.succeeded() => {
+                path(0).map(SideEffect::FileDelete).into_iter().collect()
+            }
+            "unlinkat" if call.succeeded() => {
+                path(1).map(SideEffect::FileDelete).into_iter().collect()
+            }
+            "mkdir" if call.succeeded() => path(0)
+                .map(SideEffect::DirectoryCreate)
+                .into_iter()
+                .collect(),
+            "mkdirat" if call.succeeded() => path(1)
+                .map(SideEffect::DirectoryCreate)
+                .into_iter()
+                .collect(),
+            "rename" | "renameat" | "renameat2" if call.succeeded() => {
+                let (from, to) = match call.name {
+                    "rename" => (path(0), path(1)),
+                    _ => (path(1), path(3)),
+                };
+                from.map(SideEffect::FileDelete)
+                    .into_iter()
+                    .chain(to.map(SideEffect::FileWrite))
+                    .collect()
+            }
+            "connect" => call
+                .args
+                .get(1)
+                .and_then(|address| socket_address(address))
+                .map(SideEffect::NetworkRequest)
+                .into_iter()
+                .collect(),
+            _ => vec![],
+        };
+        for effect in effects {
+            let ignored = effect
+                .path()
+                .is_some_and(|path| IGNORED_DIRS.iter().any(|dir| path.starts_with(dir)));
+            if !ignored && !side_effects.contains(&effect) {
+                side_effects.push(effect);
+            }
+        }
+    }
+    side_effects
+}
+
+/// One line of strace output: `name(arg, arg, ...) = result`.
+struct Syscall<'a> {
+    name: &'a str,
+    args: Vec<String>,
+    result: &'a str,
+}
+
+impl<'a> Syscall<'a> {
+    fn parse(line: &'a str) -> Option<Self> {
+        let open = line.find('(')?;
+        let name = &line[..open];
+        if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            return None;
+        }
+        let close = line.rfind(") = ")?;
+        Some(Self {
+            name,
+            args: split_args(&line[open + 1..close]),
+            result: line[close + 4..].trim(),
+        })
+    }
+
+    fn succeeded(&self) -> bool {
+        !self.result.starts_with('-') && !self.result.starts_with('?')
+    }
+
+    /// The path argument at `index`, resolved against the directory file descriptor
+    /// before it (`3</some/dir>` with `strace -y`) or `cwd`.
+    fn path(&self, index: usize, cwd: &Path) -> Option<PathBuf> {
+        let arg = self.args.get(index)?;
+        if !arg.starts_with('"') {
+            return None;
+        }
+        let path = PathBuf::from(unquote(arg));
+        let base = index
+            .checked_sub(1)
+            .and_then(|dirfd| self.args.get(dirfd))
+            .and_then(|dirfd| fd_path(dirfd))
+            .unwrap_or_else(|| cwd.to_path_buf());
+        Some(normalize(&base.join(path)))
+    }
+
+    /// The absolute path strace shows for the returned file descriptor: `3</path>`.
+    fn opened_path(&self) -> Option<PathBuf> {
+        fd_path(self.result)
+    }
+}
+
+/// The path in a file descriptor decoded by `strace -y`, as in `3</tmp/x>`.
+fn fd_path(text: &str) -> Option<PathBuf> {
+    let start = text.find('<')?;
+    let end = text.rfind('>')?;
+    (start < end && text[..start].chars().all(|c| c.is_ascii_digit()))
+        .then(|| PathBuf::from(unquote(&text[start + 1..end])))
+}
+
+/// Whether open flags allow writing.
+fn is_write(flags: &str) -> bool {
+    ["O_WRONLY", "O_RDWR", "O_CREAT", "O_TRUNC", "O_APPEND"]
+        .iter()
+        .any(|flag| flags.contains(flag))
+}
+
+fn is_system(path: &Path) -> bool {
+    SYSTEM_DIRS.iter().any(|dir| path.starts_with(dir))
+}
+
+/// `93.184.215.14:443` or `[::1]:8080` for an internet socket address; other families,
+/// such as Unix sockets, are not network access.
+fn socket_address(address: &str) -> Option<String> {
+    let field = |name: &str| {
+        let start = address.find(name)? + name.len();
+        let end = address[start..].find(')')? + start;
+        Some(&address[start..end])
+    };
+    let port = field("_port=htons(")?;
+    if address.contains("sa_family=AF_INET6") {
+        let start = address.find("inet_pton(AF_INET6, \"")? + "inet_pton(AF_INET6, \"".len();
+        let end = address[start..].find('"')? + start;
+        Some(format!("[{}]:{}", &address[start..end], port))
+    } else if address.contains("sa_family=AF_INET") {
+        let ip = field("sin_addr=inet_addr(")?;
+        Some(format!("{}:{}", ip.trim_matches('"'), port))
+    } else {
+        None
+    }
+}
+
+/// Split a list of arguments at the commas that are not inside quotes or brackets.
+fn split_args(text: &str) -> Vec<String> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut depth = 0usize;
+    let mut in_string = false;
+    let mut escaped = false;
+    for c in text.chars() {
+        if in_string {
+            current.push(c);
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match c {
+            '"' => {
+                in_string = true;
+                current.push(c);
+            }
+            '(' | '[' | '{' => {
+                depth += 1;
+                current.push(c);
+            }
+            ')' | ']' | '}' => {
+                depth = depth.saturating_sub(1);
+                current.push(c);
+            }
+            ',' if depth == 0 => args.push(std::mem::take(&mut current).trim().to_string()),
+            _ => current.push(c),
+        }
+    }
+    if !current.trim().is_empty() {
+        args.push(current.trim().to_string());
+    }
+    args
+}
+
+fn strip_delimiters(text: &str, open: char, close: char) -> &str {
+    text.trim()
+        .strip_prefix(open)
+        .and_then(|text| text.strip_suffix(close))
+        .unwrap_or(text)
+}
+
+/// The contents of a quoted strace string, with C escapes decoded. A trailing `...`
+/// (a truncated string) is dropped.
+fn unquote(arg: &str) -> String {
+    let text = arg.trim_end_matches("...");
+    let text = strip_delimiters(text, '"', '"');
+    let mut bytes = Vec::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            let mut buffer = [0u8; 4];
+            bytes.extend_from_slice(c.encode_utf8(&mut buffer).as_bytes());
+            continue;
+        }
+        match chars.next() {
+            Some('n') => bytes.push(b'\n'),
+            Some('t') => bytes.push(b'\t'),
+            Some('r') => bytes.push(b'\r'),
+            Some('v') => bytes.push(0x0b),
+            Some('f') => bytes.push(0x0c),
+            Some('x') => {
+                let hex: String = (0..2).filter_map(|_| chars.next()).collect();
+                bytes.push(u8::from_str_radix(&hex, 16).unwrap_or(b'?'));
+            }
+            Some(digit @ '0'..='7') => {
+                let mut value = digit.to_digit(8).unwrap_or(0);
+                for _ in 0..2 {
+                    match chars.peek().and_then(|c| c.to_digit(8)) {
+                        Some(next) => {
+                            value = value * 8 + next;
+                            chars.next();
+                        }
+                        None => break,
+                    }
+                }
+                bytes.push(value as u8);
+            }
+            Some(other) => {
+                let mut buffer = [0u8; 4];
+                bytes.extend_from_slice(other.encode_utf8(&mut buffer).as_bytes());
+            }
+            None => bytes.push(b'\\'),
+        }
+    }
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+/// Remove `.` and `..` components without touching the filesystem.
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            std::path::Component::CurDir => {}
+            std::path::Component::ParentDir => {
+                normalized.pop();
+            }
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
@@ -44,6 +44,13 @@ mod new_tests {
             auto_watch: false,
             debug: false,
             dry_run: false,
+            side_effects: false,
+            sandbox: false,
+            interactive: false,
+            help: false,
+            command: None,
+            graph: false,
+            format: None,
             task: None,
             subtask: None,
             args: vec![],
@@ -84,6 +91,13 @@ mod new_tests {
             auto_watch: false,
             debug: false,
             dry_run: false,
+            side_effects: false,
+            sandbox: false,
+            interactive: false,
+            help: false,
+            command: None,
+            graph: false,
+            format: None,
             task: Some("build".to_string()),
             subtask: None,
             args: vec![],
@@ -100,8 +114,8 @@ mod new_tests {
         let watcher_err = BodoError::WatcherError("watcher error".to_string());
         assert_eq!(format!("{}", watcher_err), "watcher error");
 
-        let task_not_found = BodoError::TaskNotFound("not_found".to_string());
-        assert_eq!(format!("{}", task_not_found), "not found");
+        let task_not_found = BodoError::TaskNotFound("not_found".to_string(), vec![]);
+        assert_eq!(format!("{}", task_not_found), "task 'not_found' not found");
 
         let plugin_err = BodoError::PluginError("plugin fail".to_string());
         assert_eq!(format!("{}", plugin_err), "Plugin error: plugin fail");
@@ -174,6 +188,7 @@ mod new_tests {
                 description: Some("Greeting msg".to_string()),
                 required: true,
                 default: Some("Hello".to_string()),
+                ..Default::default()
             }],
             is_default: false,
             script_id: "".to_string(),
@@ -55,8 +55,8 @@ fn test_execution_plugin_run_node_chain() -> Result<()> {
     graph.task_registry.insert("A".to_string(), a_id);
     graph.task_registry.insert("B".to_string(), b_id);
 
-    // Add edge: Task B is a prerequisite for Task A.
-    graph.add_edge(b_id, a_id).unwrap();
+    // Add edge: Task A depends on Task B, so B runs first.
+    graph.add_edge(a_id, b_id).unwrap();
 
     // Create an ExecutionPlugin and set task_name to "A".
     let mut plugin = ExecutionPlugin::new();
@@ -37,6 +37,13 @@ fn test_all_public_functions() -> Result<()> {
         auto_watch: false,
         debug: false,
         dry_run: false,
+        side_effects: false,
+        sandbox: false,
+        interactive: false,
+        help: false,
+        command: None,
+        graph: false,
+        format: None,
         task: None,
         subtask: None,
         args: vec![],
@@ -1,9 +1,18 @@
 #[test]
 fn test_build_rs_cfg_flags() {
-    // The build.rs prints cfg flags for "tokio", "petgraph", "dialoguer", and "serde_json"
-    // so that in the compiled code, these features are enabled.
-    assert!(cfg!(feature = "tokio"));
-    assert!(cfg!(feature = "petgraph"));
-    assert!(cfg!(feature = "dialoguer"));
-    assert!(cfg!(feature = "serde_json"));
+    // build.rs always sets the "tokio", "petgraph" and "serde_json" cfg flags.
+    const { assert!(cfg!(feature = "tokio")) };
+    const { assert!(cfg!(feature = "petgraph")) };
+    const { assert!(cfg!(feature = "serde_json")) };
+}
+
+#[cfg(feature = "dialoguer")]
+#[test]
+fn test_dialoguer_feature_enables_interactive_mode() {
+    use bodo::cli::Args;
+    use clap::CommandFactory;
+
+    assert!(Args::command()
+        .get_arguments()
+        .any(|arg| arg.get_long() == Some("interactive")));
 }
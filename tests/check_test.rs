@@ -0,0 +1,180 @@
+use bodo::check::{check_project, CheckFormat};
+use bodo::config::BodoConfig;
+use std::fs;
+use tempfile::tempdir;
+
+fn config(scripts_dir: &std::path::Path) -> BodoConfig {
+    BodoConfig {
+        root_script: Some(
+            scripts_dir
+                .join("script.yaml")
+                .to_str()
+                .unwrap()
+                .to_string(),
+        ),
+        scripts_dirs: Some(vec![scripts_dir.to_str().unwrap().to_string()]),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_check_reports_every_problem() {
+    let temp_dir = tempdir().unwrap();
+    let scripts_dir = temp_dir.path().join("scripts");
+    fs::create_dir_all(scripts_dir.join("ci")).unwrap();
+    fs::write(
+        scripts_dir.join("script.yaml"),
+        r#"
+tasks:
+  build:
+    command: cargo build
+    cwd: /does/not/exist
+    pre_deps:
+      - task: lint
+  lint:
+    command: echo lint
+    pre_deps:
+      - task: build
+  test:
+    command: cargo test
+    timeout: soon
+    watch:
+      patterns: ["src/[*.rs"]
+  docs:
+    concurrently:
+      - task: nope
+    concurrently_options:
+      prefix_color: pink
+"#,
+    )
+    .unwrap();
+    fs::write(
+        scripts_dir.join("ci").join("script.yaml"),
+        "tasks:\n  unit:\n    command: echo unit\n    post_deps:\n      - task: biuld\n",
+    )
+    .unwrap();
+
+    let report = check_project(config(&scripts_dir));
+    assert!(!report.is_ok());
+    assert_eq!((report.scripts, report.tasks), (2, 5));
+    let problems: Vec<(String, usize)> = report
+        .problems
+        .iter()
+        .map(|problem| (problem.message.clone(), problem.line))
+        .collect();
+    assert_eq!(
+        problems,
+        [
+            (
+                "task 'biuld' referenced by 'ci/unit' does not exist — did you mean 'build'?"
+                    .to_string(),
+                4
+            ),
+            (
+                "found cyclical dependency: build -> lint -> build".to_string(),
+                3
+            ),
+            (
+                "working directory '/does/not/exist' does not exist".to_string(),
+                5
+            ),
+            ("Invalid timeout format: soon".to_string(), 14),
+            (
+                "invalid watch pattern 'src/[*.rs': unclosed character class; missing ']'"
+                    .to_string(),
+                16
+            ),
+            (
+                "task 'nope' referenced by 'docs' does not exist".to_string(),
+                18
+            ),
+            (
+                "unknown prefix_color 'pink'; expected one of: black, red, green, yellow, blue, \
+                 magenta, cyan, white, brightblack, brightred, brightgreen, brightyellow, \
+                 brightblue, brightmagenta, brightcyan, brightwhite"
+                    .to_string(),
+                21
+            ),
+        ]
+    );
+    assert_eq!(report.summary(), "7 problems in 2 scripts");
+
+    let json: serde_json::Value =
+        serde_json::from_str(&report.render(CheckFormat::Json).unwrap()).unwrap();
+    assert_eq!(json["version"], 1);
+    assert_eq!(json["problems"][0]["line"], 4);
+    assert!(json["problems"][0]["path"]
+        .as_str()
+        .unwrap()
+        .ends_with("script.yaml"));
+}
+
+#[test]
+fn test_check_passes_and_survives_broken_files() {
+    let temp_dir = tempdir().unwrap();
+    let scripts_dir = temp_dir.path().join("scripts");
+    fs::create_dir_all(&scripts_dir).unwrap();
+    fs::write(
+        scripts_dir.join("script.yaml"),
+        "tasks:\n  build:\n    command: cargo build\n    pre_deps:\n      - task: fmt\n  fmt:\n    command: cargo fmt\n",
+    )
+    .unwrap();
+
+    let report = check_project(config(&scripts_dir));
+    assert!(report.is_ok(), "{:?}", report.problems);
+    assert_eq!(
+        report.summary(),
+        "No problems found in 2 tasks across 1 script"
+    );
+    assert_eq!(report.render(CheckFormat::Text).unwrap(), "");
+
+    // A file that does not parse is one problem; the other scripts are still checked
+    fs::write(scripts_dir.join("broken.yaml"), "tasks: [\n").unwrap();
+    fs::write(
+        scripts_dir.join("other.yaml"),
+        "tasks:\n  run:\n    description: does nothing\n",
+    )
+    .unwrap();
+    let report = check_project(config(&scripts_dir));
+    assert_eq!(report.problems.len(), 2, "{:?}", report.problems);
+    assert!(report.problems[0].path.ends_with("broken.yaml"));
+    assert_eq!(
+        report.problems[1].message,
+        "A task must have a command or some dependencies"
+    );
+    assert!(report.render(CheckFormat::Text).unwrap().contains("--> "));
+
+    assert_eq!(
+        "xml".parse::<CheckFormat>().unwrap_err().to_string(),
+        "Validation error: unknown check format 'xml'; expected one of: text, json"
+    );
+}
+
+#[test]
+fn test_check_finds_cycles_through_concurrently() {
+    let temp_dir = tempdir().unwrap();
+    let scripts_dir = temp_dir.path().join("scripts");
+    fs::create_dir_all(&scripts_dir).unwrap();
+    fs::write(
+        scripts_dir.join("script.yaml"),
+        r#"
+tasks:
+  a:
+    concurrently:
+      - task: b
+  b:
+    command: echo b
+    pre_deps:
+      - task: a
+"#,
+    )
+    .unwrap();
+
+    let report = check_project(config(&scripts_dir));
+    let problems: Vec<&str> = report
+        .problems
+        .iter()
+        .map(|problem| problem.message.as_str())
+        .collect();
+    assert_eq!(problems, ["found cyclical dependency: a -> b -> a"]);
+}
@@ -12,6 +12,13 @@ fn test_get_task_name_no_default_and_no_argument() {
         auto_watch: false,
         debug: false,
         dry_run: false,
+        side_effects: false,
+        sandbox: false,
+        interactive: false,
+        help: false,
+        command: None,
+        graph: false,
+        format: None,
         task: None,
         subtask: None,
         args: vec![],
@@ -57,13 +64,20 @@ fn test_get_task_name_task_not_found() {
         auto_watch: false,
         debug: false,
         dry_run: false,
+        side_effects: false,
+        sandbox: false,
+        interactive: false,
+        help: false,
+        command: None,
+        graph: false,
+        format: None,
         task: Some("nonexistent".to_string()),
         subtask: None,
         args: vec![],
     };
     let res = get_task_name(&args, &manager);
     match res {
-        Err(BodoError::TaskNotFound(_)) => {}
+        Err(BodoError::TaskNotFound(..)) => {}
         _ => panic!("Expected TaskNotFound error for a task that does not exist"),
     }
 }
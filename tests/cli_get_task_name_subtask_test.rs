@@ -38,6 +38,13 @@ fn test_get_task_name_with_subtask_exists() {
         auto_watch: false,
         debug: false,
         dry_run: false,
+        side_effects: false,
+        sandbox: false,
+        interactive: false,
+        help: false,
+        command: None,
+        graph: false,
+        format: None,
         task: Some("build".to_string()),
         subtask: Some("unit".to_string()),
         args: vec![],
@@ -80,6 +87,13 @@ fn test_get_task_name_with_subtask_not_found() {
         auto_watch: false,
         debug: false,
         dry_run: false,
+        side_effects: false,
+        sandbox: false,
+        interactive: false,
+        help: false,
+        command: None,
+        graph: false,
+        format: None,
         task: Some("build".to_string()),
         subtask: Some("unit".to_string()),
         args: vec![],
@@ -87,7 +101,7 @@ fn test_get_task_name_with_subtask_not_found() {
 
     let result = get_task_name(&args, &manager);
     match result {
-        Err(BodoError::TaskNotFound(_)) => {}
+        Err(BodoError::TaskNotFound(..)) => {}
         _ => panic!("Expected TaskNotFound error when concatenated task not found"),
     }
 }
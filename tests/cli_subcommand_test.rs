@@ -0,0 +1,127 @@
+use bodo::cli::{get_task_name, join_task_name, Args, Command};
+use bodo::completion::Shell;
+use bodo::config::BodoConfig;
+use bodo::init::init_script;
+use bodo::manager::GraphManager;
+use clap::Parser;
+use std::fs;
+use tempfile::tempdir;
+
+#[test]
+fn test_subcommands_and_shorthand() {
+    let args = Args::parse_from(["bodo", "run", "deploy", "prod", "--watch", "--", "--x"]);
+    match args.command {
+        Some(Command::Run {
+            task,
+            subtask,
+            help,
+            args: task_args,
+        }) => {
+            assert_eq!(task.as_deref(), Some("deploy"));
+            assert_eq!(subtask.as_deref(), Some("prod"));
+            assert!(!help);
+            assert_eq!(task_args, ["--x"]);
+        }
+        other => panic!("Expected run, got {:?}", other),
+    }
+    // Options given after the command still apply
+    assert!(args.watch);
+
+    let args = Args::parse_from(["bodo", "graph", "release", "--format", "dot"]);
+    assert!(matches!(
+        args.command,
+        Some(Command::Graph { task: Some(ref task), subtask: None }) if task == "release"
+    ));
+    assert_eq!(args.format.as_deref(), Some("dot"));
+
+    assert!(matches!(
+        Args::parse_from(["bodo", "list"]).command,
+        Some(Command::List)
+    ));
+    assert!(matches!(
+        Args::parse_from(["bodo", "check"]).command,
+        Some(Command::Check)
+    ));
+    assert!(matches!(
+        Args::parse_from(["bodo", "completions", "fish"]).command,
+        Some(Command::Completions { shell: Shell::Fish })
+    ));
+    assert!(Args::try_parse_from(["bodo", "completions", "ksh"]).is_err());
+
+    // Anything that is not a command is a task
+    let args = Args::parse_from(["bodo", "deploy", "prod", "--", "eu"]);
+    assert!(args.command.is_none());
+    assert_eq!(args.task.as_deref(), Some("deploy"));
+    assert_eq!(args.subtask.as_deref(), Some("prod"));
+    assert_eq!(args.args, ["eu"]);
+}
+
+#[test]
+fn test_init_writes_a_loadable_script() {
+    let temp_dir = tempdir().unwrap();
+    let script = temp_dir.path().join("scripts").join("script.yaml");
+    init_script(&script).unwrap();
+
+    let mut manager = GraphManager::new();
+    manager
+        .build_graph(BodoConfig {
+            root_script: Some(script.to_str().unwrap().to_string()),
+            ..Default::default()
+        })
+        .unwrap();
+    let args = Args::parse_from(["bodo", "greet"]);
+    assert_eq!(get_task_name(&args, &manager).unwrap(), "greet");
+    assert!(manager.task_exists("default"));
+
+    // An existing script is left alone
+    fs::write(&script, "tasks: {}\n").unwrap();
+    let err = init_script(&script).unwrap_err();
+    assert!(
+        err.to_string().ends_with("script.yaml already exists"),
+        "{}",
+        err
+    );
+    assert_eq!(fs::read_to_string(&script).unwrap(), "tasks: {}\n");
+}
+
+#[test]
+fn test_subtask_named_like_a_command() {
+    let temp_dir = tempdir().unwrap();
+    let scripts_dir = temp_dir.path().join("scripts");
+    fs::create_dir_all(scripts_dir.join("build")).unwrap();
+    fs::write(
+        scripts_dir.join("build").join("script.yaml"),
+        "tasks:\n  check:\n    command: cargo check\n  graph:\n    command: echo graph\n",
+    )
+    .unwrap();
+    let mut manager = GraphManager::new();
+    manager
+        .build_graph(BodoConfig {
+            scripts_dirs: Some(vec![scripts_dir.to_str().unwrap().to_string()]),
+            ..Default::default()
+        })
+        .unwrap();
+
+    // Only the first word can be a command
+    for subtask in ["check", "graph", "run", "list", "completions"] {
+        let mut args = Args::parse_command_line(["bodo", "build", subtask]);
+        assert!(
+            matches!(args.command.take(), Some(Command::Run { ref task, ref subtask, .. })
+                if task.as_deref() == Some("build") && subtask.is_some()),
+            "{:?}",
+            args
+        );
+    }
+    let args = Args::parse_command_line(["bodo", "--format", "json", "build", "check"]);
+    let Some(Command::Run { task, subtask, .. }) = args.command else {
+        panic!("Expected run, got {:?}", args.command);
+    };
+    let task_name = join_task_name(task.as_deref().unwrap(), subtask.as_deref());
+    assert_eq!(task_name, "build check");
+    assert!(manager.task_exists(&task_name));
+
+    let args = Args::parse_command_line(["bodo", "build", "--graph"]);
+    assert!(args.command.is_none() && args.graph);
+    let args = Args::parse_command_line(["bodo", "--watch", "check"]);
+    assert!(matches!(args.command, Some(Command::Check)));
+}
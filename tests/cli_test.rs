@@ -36,6 +36,13 @@ fn test_cli_get_task_name_default_exists() {
         auto_watch: false,
         debug: false,
         dry_run: false,
+        side_effects: false,
+        sandbox: false,
+        interactive: false,
+        help: false,
+        command: None,
+        graph: false,
+        format: None,
         task: None,
         subtask: None,
         args: vec![],
@@ -76,6 +83,13 @@ fn test_cli_get_task_name_with_existing_task() {
         auto_watch: false,
         debug: false,
         dry_run: false,
+        side_effects: false,
+        sandbox: false,
+        interactive: false,
+        help: false,
+        command: None,
+        graph: false,
+        format: None,
         task: Some("build".to_string()),
         subtask: None,
         args: vec![],
@@ -92,8 +106,8 @@ fn test_bodo_error_variants_display() {
     let watcher_err = bodo::errors::BodoError::WatcherError("watcher error".to_string());
     assert_eq!(format!("{}", watcher_err), "watcher error");
 
-    let task_not_found = bodo::errors::BodoError::TaskNotFound("not_found".to_string());
-    assert_eq!(format!("{}", task_not_found), "not found");
+    let task_not_found = bodo::errors::BodoError::TaskNotFound("not_found".to_string(), vec![]);
+    assert_eq!(format!("{}", task_not_found), "task 'not_found' not found");
 
     let plugin_err = bodo::errors::BodoError::PluginError("plugin fail".to_string());
     assert_eq!(format!("{}", plugin_err), "Plugin error: plugin fail");
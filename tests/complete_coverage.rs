@@ -41,6 +41,13 @@ fn test_cli_get_task_name_default_exists() {
         auto_watch: false,
         debug: false,
         dry_run: false,
+        side_effects: false,
+        sandbox: false,
+        interactive: false,
+        help: false,
+        command: None,
+        graph: false,
+        format: None,
         task: None,
         subtask: None,
         args: vec![],
@@ -81,6 +88,13 @@ fn test_cli_get_task_name_with_existing_task() {
         auto_watch: false,
         debug: false,
         dry_run: false,
+        side_effects: false,
+        sandbox: false,
+        interactive: false,
+        help: false,
+        command: None,
+        graph: false,
+        format: None,
         task: Some("build".to_string()),
         subtask: None,
         args: vec![],
@@ -97,8 +111,8 @@ fn test_bodo_error_variants_display() {
     let watcher_err = BodoError::WatcherError("watcher error".to_string());
     assert_eq!(format!("{}", watcher_err), "watcher error");
 
-    let task_not_found = BodoError::TaskNotFound("not_found".to_string());
-    assert_eq!(format!("{}", task_not_found), "not found");
+    let task_not_found = BodoError::TaskNotFound("not_found".to_string(), vec![]);
+    assert_eq!(format!("{}", task_not_found), "task 'not_found' not found");
 
     let plugin_err = BodoError::PluginError("plugin fail".to_string());
     assert_eq!(format!("{}", plugin_err), "Plugin error: plugin fail");
@@ -171,6 +185,7 @@ fn test_manager_apply_task_arguments_success() {
             description: Some("Greeting msg".to_string()),
             required: true,
             default: Some("Hello".to_string()),
+            ..Default::default()
         }],
         is_default: false,
         script_id: "".to_string(),
@@ -213,6 +228,7 @@ fn test_manager_apply_task_arguments_failure() {
             description: None,
             required: true,
             default: None,
+            ..Default::default()
         }],
         is_default: false,
         script_id: "".to_string(),
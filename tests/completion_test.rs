@@ -0,0 +1,154 @@
+use bodo::completion::{complete, Shell};
+use bodo::config::BodoConfig;
+use bodo::graph::Graph;
+use bodo::script_loader::ScriptLoader;
+use std::fs;
+use tempfile::tempdir;
+
+fn load_graph() -> Graph {
+    let temp_dir = tempdir().unwrap();
+    let scripts_dir = temp_dir.path().join("scripts");
+    fs::create_dir_all(scripts_dir.join("deploy")).unwrap();
+    fs::write(
+        scripts_dir.join("script.yaml"),
+        r#"
+tasks:
+  build:
+    description: Build the project
+    command: cargo build
+  bench:
+    command: cargo bench
+"#,
+    )
+    .unwrap();
+    fs::write(
+        scripts_dir.join("deploy").join("script.yaml"),
+        r#"
+default_task:
+  description: Deploy everything
+  command: ./deploy.sh
+tasks:
+  staging:
+    description: Deploy to staging
+    command: ./deploy.sh staging "$REGION"
+    args:
+      - name: REGION
+        short: r
+        type: enum
+        choices: [eu, us]
+      - name: DRY_RUN
+        type: bool
+  production:
+    command: ./deploy.sh production
+"#,
+    )
+    .unwrap();
+    ScriptLoader::new()
+        .build_graph(BodoConfig {
+            scripts_dirs: Some(vec![scripts_dir.to_str().unwrap().to_string()]),
+            ..Default::default()
+        })
+        .unwrap()
+}
+
+fn values(graph: &Graph, words: &[&str]) -> Vec<String> {
+    let words: Vec<String> = words.iter().map(|word| word.to_string()).collect();
+    complete(graph, &words)
+        .into_iter()
+        .map(|candidate| candidate.value)
+        .collect()
+}
+
+#[test]
+fn test_complete_task_and_subtask_names() {
+    let graph = load_graph();
+    assert_eq!(
+        values(&graph, &[""]),
+        [
+            "bench",
+            "build",
+            "check",
+            "completions",
+            "deploy",
+            "describe",
+            "graph",
+            "init",
+            "list",
+            "run"
+        ]
+    );
+    assert_eq!(values(&graph, &["b"]), ["bench", "build"]);
+    assert_eq!(values(&graph, &["deploy", ""]), ["production", "staging"]);
+    assert_eq!(values(&graph, &["describe", "b"]), ["bench", "build"]);
+    assert_eq!(values(&graph, &["run", ""]), ["bench", "build", "deploy"]);
+    assert_eq!(values(&graph, &["run", "deploy", "s"]), ["staging"]);
+    assert!(values(&graph, &["list", ""]).is_empty());
+    assert_eq!(values(&graph, &["completions", "z"]), ["zsh"]);
+    assert_eq!(values(&graph, &["--format", "m"]), ["mermaid"]);
+    assert!(values(&graph, &["--dry"]).contains(&"--dry-run".to_string()));
+
+    // Candidates carry the task's description for shells that show it
+    let words = vec!["bu".to_string()];
+    assert_eq!(
+        complete(&graph, &words)[0].to_string(),
+        "build\tBuild the project"
+    );
+    let words = vec!["dep".to_string()];
+    assert_eq!(
+        complete(&graph, &words)[0].to_string(),
+        "deploy\tDeploy everything"
+    );
+}
+
+#[test]
+fn test_complete_task_arguments() {
+    let graph = load_graph();
+    assert_eq!(
+        values(&graph, &["deploy", "staging", "--", "--"]),
+        ["--region", "--dry-run"]
+    );
+    assert_eq!(
+        values(&graph, &["deploy", "staging", "--", "--region", ""]),
+        ["eu", "us"]
+    );
+    assert_eq!(
+        values(&graph, &["deploy", "staging", "--", "-r", "u"]),
+        ["us"]
+    );
+    assert_eq!(
+        values(&graph, &["deploy", "staging", "--", "--region=e"]),
+        ["--region=eu"]
+    );
+    // Positional values fill the arguments not given by name
+    assert_eq!(
+        values(&graph, &["deploy", "staging", "--", ""]),
+        ["eu", "us"]
+    );
+    assert_eq!(
+        values(&graph, &["deploy", "staging", "--", "--region", "eu", ""]),
+        ["true", "false"]
+    );
+    assert_eq!(
+        values(&graph, &["run", "deploy", "staging", "--", "--d"]),
+        ["--dry-run"]
+    );
+    assert!(values(&graph, &["build", "--", "--"]).is_empty());
+}
+
+#[test]
+fn test_completion_scripts() {
+    for (name, call) in [
+        ("bash", "complete -o default -F _bodo bodo"),
+        ("zsh", "compdef _bodo bodo"),
+        ("fish", "complete -c bodo"),
+    ] {
+        let shell: Shell = name.parse().unwrap();
+        let script = shell.script();
+        assert!(script.contains("bodo __complete --"), "{}", script);
+        assert!(script.contains(call), "{}", script);
+    }
+    assert_eq!(
+        "ksh".parse::<Shell>().unwrap_err().to_string(),
+        "Validation error: unknown shell 'ksh'; expected one of: bash, zsh, fish"
+    );
+}
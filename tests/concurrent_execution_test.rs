@@ -0,0 +1,159 @@
+use bodo::config::BodoConfig;
+use bodo::graph::{EdgeKind, NodeKind};
+use bodo::manager::GraphManager;
+use bodo::plugin::PluginConfig;
+use bodo::plugins::{concurrent_plugin::ConcurrentPlugin, execution_plugin::ExecutionPlugin};
+use bodo::Result;
+use std::fs;
+use std::time::{Duration, Instant};
+use tempfile::tempdir;
+
+fn run_task(config_yaml: &str, task: &str) -> Result<()> {
+    let config: BodoConfig = serde_yaml::from_str(config_yaml).unwrap();
+    let mut manager = GraphManager::new();
+    manager.build_graph(config)?;
+    manager.register_plugin(Box::new(ConcurrentPlugin::new()));
+    manager.register_plugin(Box::new(ExecutionPlugin::new()));
+
+    let mut options = serde_json::Map::new();
+    options.insert("task".into(), serde_json::Value::String(task.into()));
+    manager.run_plugins(Some(PluginConfig {
+        options: Some(options),
+        ..Default::default()
+    }))
+}
+
+#[test]
+fn test_concurrently_from_config_becomes_group() {
+    let config_yaml = r#"
+tasks:
+  all:
+    command: echo all
+    cwd: /tmp
+    env:
+      FROM_TASK: "1"
+    concurrently:
+      - task: check
+      - command: echo inline
+    concurrently_options:
+      fail_fast: false
+      max_concurrent_tasks: 1
+      prefix_color: cyan
+  check:
+    command: echo check
+"#;
+    let config: BodoConfig = serde_yaml::from_str(config_yaml).unwrap();
+    let mut manager = GraphManager::new();
+    manager.build_graph(config).unwrap();
+    manager.register_plugin(Box::new(ConcurrentPlugin::new()));
+    manager.run_plugins(None).unwrap();
+
+    let graph = &manager.graph;
+    let all_id = graph.task_registry["all"];
+    let groups = graph.dependencies_of(all_id, EdgeKind::Concurrent);
+    assert_eq!(groups.len(), 1);
+    let group = &graph.nodes[groups[0] as usize];
+    let NodeKind::ConcurrentGroup(data) = &group.kind else {
+        panic!("Expected a concurrent group");
+    };
+    assert!(!data.fail_fast);
+    assert_eq!(data.max_concurrent, Some(1));
+    assert_eq!(data.child_nodes.len(), 2);
+    assert_eq!(data.child_nodes[0], graph.task_registry["check"]);
+    assert_eq!(group.metadata["prefix_output"], "true");
+    assert_eq!(group.metadata["prefix_color"], "cyan");
+
+    match &graph.nodes[data.child_nodes[1] as usize].kind {
+        NodeKind::Command(cmd) => {
+            assert_eq!(cmd.raw_command, "echo inline");
+            assert_eq!(cmd.working_dir.as_deref(), Some("/tmp"));
+            assert_eq!(cmd.env["FROM_TASK"], "1");
+        }
+        other => panic!("Expected a command node, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_concurrently_runs_in_parallel() {
+    let temp_dir = tempdir().unwrap();
+    let ready = temp_dir.path().join("ready");
+    // `waiter` can only finish if `signal` runs at the same time.
+    let config_yaml = format!(
+        r#"
+tasks:
+  all:
+    concurrently:
+      - task: waiter
+      - task: signal
+  waiter:
+    command: for i in $(seq 100); do [ -f {ready} ] && exit 0; sleep 0.05; done; exit 1
+  signal:
+    command: touch {ready}
+"#,
+        ready = ready.display()
+    );
+    run_task(&config_yaml, "all").unwrap();
+}
+
+#[test]
+fn test_max_concurrent_tasks_bounds_the_pool() {
+    let temp_dir = tempdir().unwrap();
+    let log = temp_dir.path().join("order.log");
+    let config_yaml = format!(
+        r#"
+tasks:
+  all:
+    concurrently:
+      - command: echo start-a >> {log}; sleep 0.3; echo end-a >> {log}
+      - command: echo start-b >> {log}; sleep 0.3; echo end-b >> {log}
+    concurrently_options:
+      max_concurrent_tasks: 1
+"#,
+        log = log.display()
+    );
+    run_task(&config_yaml, "all").unwrap();
+
+    let output = fs::read_to_string(&log).unwrap();
+    let lines: Vec<&str> = output.lines().collect();
+    assert_eq!(lines, vec!["start-a", "end-a", "start-b", "end-b"]);
+}
+
+#[test]
+fn test_fail_fast_stops_running_siblings() {
+    let temp_dir = tempdir().unwrap();
+    let marker = temp_dir.path().join("finished");
+    let config_yaml = format!(
+        r#"
+tasks:
+  all:
+    concurrently:
+      - command: "false"
+      - command: sleep 3; touch {marker}
+"#,
+        marker = marker.display()
+    );
+    let start = Instant::now();
+    assert!(run_task(&config_yaml, "all").is_err());
+    assert!(start.elapsed() < Duration::from_secs(3));
+    assert!(!marker.exists());
+}
+
+#[test]
+fn test_without_fail_fast_every_child_runs() {
+    let temp_dir = tempdir().unwrap();
+    let marker = temp_dir.path().join("finished");
+    let config_yaml = format!(
+        r#"
+tasks:
+  all:
+    concurrently:
+      - command: "false"
+      - command: sleep 0.3; touch {marker}
+    concurrently_options:
+      fail_fast: false
+"#,
+        marker = marker.display()
+    );
+    assert!(run_task(&config_yaml, "all").is_err());
+    assert!(marker.exists());
+}
@@ -1,6 +1,6 @@
 // tests/config_test.rs
 
-use bodo::config::{BodoConfig, Dependency, TaskConfig, WatchConfig};
+use bodo::config::{BodoConfig, Dependency, SandboxConfig, TaskConfig, WatchConfig};
 use validator::Validate;
 use validator::ValidationErrors;
 
@@ -153,6 +153,11 @@ fn test_task_config_with_all_fields() {
             auto_watch: false,
         }),
         timeout: Some("1m".to_string()),
+        sandbox: Some(SandboxConfig {
+            network: false,
+            writable: vec!["target".to_string()],
+            read_only: vec![],
+        }),
         exec_paths: vec!["/usr/local/bin".to_string()],
         arguments: vec![],
         concurrently_options: Default::default(),
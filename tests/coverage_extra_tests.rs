@@ -41,6 +41,13 @@ mod new_tests {
             auto_watch: false,
             debug: false,
             dry_run: false,
+            side_effects: false,
+            sandbox: false,
+            interactive: false,
+            help: false,
+            command: None,
+            graph: false,
+            format: None,
             task: None,
             subtask: None,
             args: vec![],
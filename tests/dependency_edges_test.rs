@@ -0,0 +1,161 @@
+use bodo::config::BodoConfig;
+use bodo::graph::{EdgeKind, NodeKind};
+use bodo::manager::GraphManager;
+use bodo::plugin::PluginConfig;
+use bodo::plugins::execution_plugin::ExecutionPlugin;
+use bodo::script_loader::ScriptLoader;
+use std::fs;
+use tempfile::tempdir;
+
+#[test]
+fn test_pre_and_post_deps_become_ordered_edges() {
+    let config_yaml = r#"
+tasks:
+  build:
+    command: echo build
+    pre_deps:
+      - task: fmt
+      - command: echo "between"
+      - task: lint
+    post_deps:
+      - command: echo "done"
+  fmt:
+    command: echo fmt
+  lint:
+    command: echo lint
+"#;
+    let config: BodoConfig = serde_yaml::from_str(config_yaml).unwrap();
+    let mut loader = ScriptLoader::new();
+    let graph = loader.build_graph(config).unwrap();
+
+    let build_id = graph.task_registry["build"];
+    let pre = graph.dependencies_of(build_id, EdgeKind::PreDep);
+    assert_eq!(pre.len(), 3);
+    assert_eq!(pre[0], graph.task_registry["fmt"]);
+    match &graph.nodes[pre[1] as usize].kind {
+        NodeKind::Command(cmd) => assert_eq!(cmd.raw_command, "echo \"between\""),
+        other => panic!("Expected a command node, got {:?}", other),
+    }
+    assert_eq!(pre[2], graph.task_registry["lint"]);
+
+    let post = graph.dependencies_of(build_id, EdgeKind::PostDep);
+    assert_eq!(post.len(), 1);
+    match &graph.nodes[post[0] as usize].kind {
+        NodeKind::Command(cmd) => assert_eq!(cmd.raw_command, "echo \"done\""),
+        other => panic!("Expected a command node, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_dependency_prefers_task_in_same_script() {
+    let temp_dir = tempdir().unwrap();
+    let scripts_dir = temp_dir.path().join("scripts");
+    fs::create_dir_all(scripts_dir.join("build")).unwrap();
+    fs::write(
+        scripts_dir.join("script.yaml"),
+        r#"
+tasks:
+  fmt:
+    command: echo root-fmt
+"#,
+    )
+    .unwrap();
+    fs::write(
+        scripts_dir.join("build").join("script.yaml"),
+        r#"
+default_task:
+  command: cargo build
+  pre_deps:
+    - task: fmt
+tasks:
+  fmt:
+    command: cargo fmt
+"#,
+    )
+    .unwrap();
+
+    let mut loader = ScriptLoader::new();
+    let config = BodoConfig {
+        scripts_dirs: Some(vec![scripts_dir.to_str().unwrap().to_string()]),
+        ..Default::default()
+    };
+    let graph = loader.build_graph(config).unwrap();
+    let pre = graph.dependencies_of(graph.task_registry["build"], EdgeKind::PreDep);
+    assert_eq!(pre, vec![graph.task_registry["build fmt"]]);
+}
+
+#[test]
+fn test_unknown_dependency_is_an_error() {
+    let config_yaml = r#"
+tasks:
+  build:
+    command: echo build
+    pre_deps:
+      - task: missing
+"#;
+    let config: BodoConfig = serde_yaml::from_str(config_yaml).unwrap();
+    let mut loader = ScriptLoader::new();
+    let err = loader.build_graph(config).unwrap_err();
+    assert!(err.to_string().contains("missing"));
+}
+
+#[test]
+fn test_dependency_cycle_is_detected() {
+    let config_yaml = r#"
+tasks:
+  a:
+    command: echo a
+    pre_deps:
+      - task: b
+  b:
+    command: echo b
+    post_deps:
+      - task: a
+"#;
+    let config: BodoConfig = serde_yaml::from_str(config_yaml).unwrap();
+    let mut manager = GraphManager::new();
+    let err = manager.build_graph(config).unwrap_err();
+    assert!(
+        err.to_string().contains("cyclical dependency"),
+        "Unexpected error: {}",
+        err
+    );
+}
+
+#[test]
+fn test_dependencies_run_in_declared_order() {
+    let temp_dir = tempdir().unwrap();
+    let log = temp_dir.path().join("order.log");
+    let log = log.to_str().unwrap();
+    let config_yaml = format!(
+        r#"
+tasks:
+  main:
+    command: echo main >> {log}
+    pre_deps:
+      - task: first
+      - command: echo second >> {log}
+    post_deps:
+      - command: echo last >> {log}
+  first:
+    command: echo first >> {log}
+"#
+    );
+    let config: BodoConfig = serde_yaml::from_str(&config_yaml).unwrap();
+    let mut manager = GraphManager::new();
+    manager.build_graph(config).unwrap();
+    manager.register_plugin(Box::new(ExecutionPlugin::new()));
+
+    let mut options = serde_json::Map::new();
+    options.insert("task".into(), serde_json::Value::String("main".into()));
+    manager
+        .run_plugins(Some(PluginConfig {
+            options: Some(options),
+            ..Default::default()
+        }))
+        .unwrap();
+
+    let output = fs::read_to_string(log).unwrap();
+    let lines: Vec<&str> = output.lines().collect();
+    assert_eq!(lines, vec!["first", "second", "main", "last"]);
+}
@@ -0,0 +1,114 @@
+use bodo::cli::Args;
+use bodo::config::BodoConfig;
+use bodo::manager::GraphManager;
+use bodo::plugins::concurrent_plugin::ConcurrentPlugin;
+use bodo::plugins::describe_plugin::describe_task;
+use bodo::plugins::env_plugin::EnvPlugin;
+use bodo::plugins::path_plugin::PathPlugin;
+use bodo::task_resolver::SCRIPT_LINE_KEY;
+use clap::Parser;
+use std::fs;
+use tempfile::tempdir;
+
+const SCRIPT: &str = r#"
+env:
+  SCRIPT_VAR: one
+tasks:
+  fmt:
+    command: cargo fmt
+  build:
+    description: Build the project
+    command: cargo build --profile "$PROFILE"
+    cwd: app
+    timeout: 30s
+    exec_paths: [node_modules/.bin]
+    env:
+      RUST_LOG: debug
+    args:
+      - name: PROFILE
+        description: Cargo profile
+        type: enum
+        choices: [dev, release]
+        default: dev
+    pre_deps:
+      - task: fmt
+      - command: echo lint
+    concurrently:
+      - command: echo docs
+    concurrently_options:
+      max_concurrent_tasks: 2
+    post_deps:
+      - task: fmt
+    watch:
+      patterns: ["src/**/*.rs"]
+"#;
+
+#[test]
+fn test_describe_shows_resolved_task() {
+    let temp_dir = tempdir().unwrap();
+    let scripts_dir = temp_dir.path().join("scripts");
+    fs::create_dir_all(&scripts_dir).unwrap();
+    fs::write(scripts_dir.join("script.yaml"), SCRIPT).unwrap();
+
+    let mut manager = GraphManager::new();
+    manager
+        .build_graph(BodoConfig {
+            scripts_dirs: Some(vec![scripts_dir.to_str().unwrap().to_string()]),
+            ..Default::default()
+        })
+        .unwrap();
+    let build = manager.graph.task_registry["build"];
+    assert_eq!(
+        manager.graph.nodes[build as usize].metadata[SCRIPT_LINE_KEY],
+        "7"
+    );
+
+    manager.register_plugin(Box::new(EnvPlugin::new()));
+    manager.register_plugin(Box::new(PathPlugin::new()));
+    manager.register_plugin(Box::new(ConcurrentPlugin::new()));
+    manager.run_plugins(None).unwrap();
+
+    let described = describe_task(&manager.graph, "build").unwrap();
+    assert!(
+        described.starts_with("build\nBuild the project\n\nSource:      "),
+        "{}",
+        described
+    );
+    for expected in [
+        "script.yaml:7\n",
+        "Command:     cargo build --profile \"$PROFILE\"\n",
+        "Working dir: app\n",
+        "Timeout:     30s\n",
+        "Usage: bodo build -- [PROFILE]\n",
+        "--profile <dev|release>  Cargo profile [default: dev] [env: PROFILE]\n",
+        "\nEnvironment:\n  RUST_LOG=debug (task)\n  SCRIPT_VAR=one (script)\n",
+        "\nExec paths:\n  node_modules/.bin (task)\n",
+        "\nPre dependencies:\n  fmt\n  command: echo lint\n",
+        "\nConcurrently (max 2):\n  command: echo docs\n",
+        "\nPost dependencies:\n  fmt\n",
+        "\nWatch:\n  patterns: src/**/*.rs\n  debounce: 500ms\n",
+    ] {
+        assert!(
+            described.contains(expected),
+            "missing {:?} in\n{}",
+            expected,
+            described
+        );
+    }
+    // PATH is built from the exec paths, which are listed instead
+    assert!(!described.contains("PATH="), "{}", described);
+
+    let err = describe_task(&manager.graph, "biuld").unwrap_err();
+    assert!(err.to_string().contains("did you mean 'build'?"));
+}
+
+#[test]
+fn test_help_flag_with_task() {
+    let args = Args::parse_from(["bodo", "build", "--help"]);
+    assert!(args.help);
+    assert_eq!(args.task.as_deref(), Some("build"));
+
+    let args = Args::parse_from(["bodo", "-h"]);
+    assert!(args.help);
+    assert_eq!(args.task, None);
+}
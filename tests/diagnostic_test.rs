@@ -0,0 +1,147 @@
+use bodo::config::BodoConfig;
+use bodo::diagnostic::{Diagnostic, KeyLocation, YamlKeys};
+use bodo::script_loader::ScriptLoader;
+use bodo::BodoError;
+use std::fs;
+use std::path::Path;
+use tempfile::tempdir;
+
+fn load_error(files: &[(&str, &str)]) -> Diagnostic {
+    let temp_dir = tempdir().unwrap();
+    let scripts_dir = temp_dir.path().join("scripts");
+    for (name, content) in files {
+        let path = scripts_dir.join(name);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+    let err = ScriptLoader::new()
+        .build_graph(BodoConfig {
+            scripts_dirs: Some(vec![scripts_dir.to_str().unwrap().to_string()]),
+            ..Default::default()
+        })
+        .unwrap_err();
+    match err {
+        BodoError::ConfigError(diagnostic) => {
+            assert!(diagnostic
+                .path
+                .ends_with(Path::new("scripts").join(files[0].0)));
+            *diagnostic
+        }
+        other => panic!("Expected a config error, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_yaml_keys_follow_indentation() {
+    let source = r#"
+# comment
+tasks:
+  build:
+    # nested comment
+    command: cargo build
+    watch:
+      patterns:
+        - "src/**"
+  "quoted":
+    command: echo
+  build:
+    command: again
+default_task:
+  command: echo
+"#;
+    let keys = YamlKeys::new(source);
+    assert_eq!(
+        keys.find(&["tasks", "build", "watch", "patterns"]),
+        Some(KeyLocation {
+            line: 8,
+            column: 7,
+            width: 8
+        })
+    );
+    assert_eq!(keys.find(&["tasks", "quoted"]).unwrap().width, 8);
+    assert_eq!(keys.find(&["default_task"]).unwrap().line, 14);
+    assert_eq!(keys.find(&["tasks", "command"]), None);
+
+    let duplicates = keys.duplicates(&["tasks"]);
+    assert_eq!(duplicates.len(), 1);
+    assert_eq!(duplicates[0].0, "build");
+    assert_eq!((duplicates[0].1.line, duplicates[0].2.line), (4, 12));
+}
+
+#[test]
+fn test_invalid_task_name_points_at_key() {
+    let diagnostic = load_error(&[(
+        "script.yaml",
+        "tasks:\n  build:\n    command: cargo build\n  build.all:\n    command: x\n",
+    )]);
+    assert_eq!(
+        diagnostic.message,
+        "Task name cannot contain '/', '.' or '..'"
+    );
+    assert_eq!((diagnostic.line, diagnostic.column), (4, 3));
+    let rendered = diagnostic.to_string();
+    assert!(
+        rendered.ends_with("4 |   build.all:\n  |   ^^^^^^^^^"),
+        "{}",
+        rendered
+    );
+    assert!(rendered.contains("script.yaml:4:3"), "{}", rendered);
+}
+
+#[test]
+fn test_no_op_task_and_bad_timeout() {
+    let diagnostic = load_error(&[(
+        "script.yaml",
+        "tasks:\n  noop:\n    description: nothing to do\n",
+    )]);
+    assert_eq!(
+        diagnostic.message,
+        "A task must have a command or some dependencies"
+    );
+    assert_eq!((diagnostic.line, diagnostic.column), (2, 3));
+
+    let diagnostic = load_error(&[(
+        "script.yaml",
+        "tasks:\n  build:\n    command: cargo build\n    timeout: soon\n",
+    )]);
+    assert_eq!(diagnostic.message, "Invalid timeout format: soon");
+    assert_eq!(
+        (diagnostic.line, diagnostic.column, diagnostic.span),
+        (4, 5, 7)
+    );
+}
+
+#[test]
+fn test_duplicate_tasks_in_one_file_and_across_files() {
+    let diagnostic = load_error(&[(
+        "script.yaml",
+        "tasks:\n  build:\n    command: a\n  build:\n    command: b\n",
+    )]);
+    assert_eq!(diagnostic.message, "task 'build' is defined more than once");
+    assert_eq!(diagnostic.line, 4);
+    assert_eq!(diagnostic.note.as_deref(), Some("first defined on line 2"));
+
+    // `ci/script.yaml` is loaded first; `ci.yaml` registers the same `ci x` key.
+    let diagnostic = load_error(&[
+        ("ci.yaml", "tasks:\n  x:\n    command: a\n"),
+        ("ci/script.yaml", "tasks:\n  x:\n    command: b\n"),
+    ]);
+    assert_eq!(diagnostic.message, "task 'ci x' is defined more than once");
+    assert!(diagnostic
+        .note
+        .as_deref()
+        .unwrap()
+        .ends_with("ci/script.yaml"));
+}
+
+#[test]
+fn test_yaml_syntax_error_has_location() {
+    let diagnostic = load_error(&[("script.yaml", "tasks:\n  build:\n    command: [a\n")]);
+    assert!(
+        !diagnostic.message.contains(" at line "),
+        "{}",
+        diagnostic.message
+    );
+    assert_eq!(diagnostic.source_line, "    command: [a");
+    assert_eq!(diagnostic.line, 3);
+}
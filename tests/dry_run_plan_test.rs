@@ -0,0 +1,175 @@
+use bodo::config::BodoConfig;
+use bodo::manager::GraphManager;
+use bodo::plugin::{
+    DryRunPlan, DryRunnable, ExecutionContext, PluginConfig, SideEffect, DRY_RUN_PLAN_VERSION,
+};
+use bodo::plugins::{
+    concurrent_plugin::ConcurrentPlugin, env_plugin::EnvPlugin, execution_plugin::ExecutionPlugin,
+    path_plugin::PathPlugin, timeout_plugin::TimeoutPlugin,
+};
+use std::collections::HashMap;
+use std::time::Duration;
+use tempfile::tempdir;
+
+fn plan_config(dir: &str) -> String {
+    format!(
+        r#"
+tasks:
+  release:
+    command: touch released
+    cwd: {dir}
+    timeout: 30s
+    env:
+      STAGE: prod
+    exec_paths:
+      - {dir}/bin
+    pre_deps:
+      - task: build
+    concurrently:
+      - task: test
+      - command: touch docs
+    concurrently_options:
+      fail_fast: false
+      max_concurrent_tasks: 2
+    post_deps:
+      - command: touch notified
+  build:
+    command: touch built
+    cwd: {dir}
+  test:
+    command: touch tested
+    cwd: {dir}
+"#
+    )
+}
+
+fn task_option(task: &str) -> Option<serde_json::Map<String, serde_json::Value>> {
+    let mut options = serde_json::Map::new();
+    options.insert("task".into(), serde_json::Value::String(task.to_string()));
+    Some(options)
+}
+
+/// Run the plugins `bodo --dry-run release` would, with `execution` added when given.
+fn run(dir: &str, execution: Option<ExecutionPlugin>) -> GraphManager {
+    let config: BodoConfig = serde_yaml::from_str(&plan_config(dir)).unwrap();
+    let mut manager = GraphManager::new();
+    manager.build_graph(config).unwrap();
+    manager.register_plugin(Box::new(EnvPlugin::new()));
+    manager.register_plugin(Box::new(PathPlugin::new()));
+    manager.register_plugin(Box::new(ConcurrentPlugin::new()));
+    manager.register_plugin(Box::new(TimeoutPlugin::new()));
+    if let Some(execution) = execution {
+        manager.register_plugin(Box::new(execution));
+    }
+    manager
+        .run_plugins(Some(PluginConfig {
+            dry_run: true,
+            options: task_option("release"),
+            ..Default::default()
+        }))
+        .unwrap();
+    manager
+}
+
+#[test]
+fn test_dry_run_plans_in_execution_order_without_spawning() {
+    let temp_dir = tempdir().unwrap();
+    let dir = temp_dir.path().to_str().unwrap();
+    let manager = run(dir, None);
+
+    let plugin = ExecutionPlugin::new();
+    let task_id = manager.graph.task_registry["release"] as usize;
+    let reports = plugin.plan(&manager.graph, task_id).unwrap();
+
+    let steps: Vec<(&str, &str)> = reports
+        .iter()
+        .map(|r| (r.name.as_str(), r.command.as_str()))
+        .collect();
+    assert_eq!(
+        steps,
+        vec![
+            ("build", "touch built"),
+            ("test", "touch tested"),
+            ("command", "touch docs"),
+            ("release", "touch released"),
+            ("command", "touch notified"),
+        ]
+    );
+
+    let group = "release: concurrently (no fail_fast, max 2)";
+    assert_eq!(reports[1].group.as_deref(), Some(group));
+    assert_eq!(reports[2].group.as_deref(), Some(group));
+    assert_eq!(reports[3].group, None);
+
+    let release = &reports[3];
+    assert_eq!(release.timeout, Some(Duration::from_secs(30)));
+    assert_eq!(release.working_directory, temp_dir.path());
+    assert_eq!(release.environment["STAGE"], "prod");
+    assert_eq!(release.environment_sources["STAGE"], "task");
+    assert!(release.environment["PATH"].contains(&format!("{}/bin", dir)));
+    assert!(reports.iter().all(|r| r.side_effects.is_empty()));
+
+    // Dependencies are graph-export ids; concurrent groups expand to their children.
+    assert_eq!(
+        release.dependencies,
+        vec!["build", "test", "release/concurrent0/concurrent1"]
+    );
+    assert_eq!(reports[4].id, "release/post0");
+    assert_eq!(reports[4].dependencies, vec!["release"]);
+
+    // Running the whole lifecycle in dry-run mode must not touch anything either.
+    run(dir, Some(ExecutionPlugin::new()));
+    assert_eq!(std::fs::read_dir(temp_dir.path()).unwrap().count(), 0);
+}
+
+#[test]
+fn test_dry_runnable_reports_the_given_command() {
+    let plugin = ExecutionPlugin::new();
+    let context = ExecutionContext {
+        command: "echo $NAME".to_string(),
+        working_directory: std::env::temp_dir(),
+        environment: HashMap::from([("NAME".to_string(), "bodo".to_string())]),
+        dry_run: true,
+    };
+    let report = plugin.dry_run(&context).unwrap();
+    assert_eq!(report.command, "echo bodo");
+    assert!(report.side_effects.is_empty());
+}
+
+#[test]
+fn test_plan_serializes_with_a_stable_schema() {
+    let temp_dir = tempdir().unwrap();
+    let dir = temp_dir.path().to_str().unwrap();
+    let manager = run(dir, None);
+    let task_id = manager.graph.task_registry["release"] as usize;
+    let mut commands = ExecutionPlugin::new()
+        .plan(&manager.graph, task_id)
+        .unwrap();
+    commands[0]
+        .side_effects
+        .push(SideEffect::FileWrite(temp_dir.path().join("built")));
+    let plan = DryRunPlan::new("release".to_string(), commands, temp_dir.path());
+
+    let json: serde_json::Value = serde_json::to_value(&plan).unwrap();
+    assert_eq!(json["version"], DRY_RUN_PLAN_VERSION);
+    let release = &json["commands"][3];
+    assert_eq!(release["timeout"], "30s");
+    assert_eq!(release["environment_sources"]["STAGE"], "task");
+    // Nothing that depends on the machine or on previous runs
+    assert_eq!(release["working_directory"], ".");
+    let path = release["environment"]["PATH"].as_str().unwrap();
+    assert!(path.split(':').any(|entry| entry == "bin"), "{}", path);
+    assert!(!path.contains(dir), "{}", path);
+    assert!(release.get("estimated_duration").is_none());
+    assert_eq!(
+        json["commands"][0]["side_effects"][0],
+        serde_json::json!({"kind": "file_write", "target": "built"})
+    );
+
+    // Environment keys come out sorted, so the same plan always serializes the same way.
+    let text = serde_json::to_string(&plan).unwrap();
+    assert!(text.find("\"PATH\"").unwrap() < text.find("\"STAGE\"").unwrap());
+    assert_eq!(serde_json::from_str::<DryRunPlan>(&text).unwrap(), plan);
+    let yaml = serde_yaml::to_string(&plan).unwrap();
+    assert_eq!(serde_yaml::from_str::<DryRunPlan>(&yaml).unwrap(), plan);
+}
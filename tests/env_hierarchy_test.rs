@@ -0,0 +1,168 @@
+use bodo::config::BodoConfig;
+use bodo::graph::NodeKind;
+use bodo::manager::GraphManager;
+use bodo::plugin::PluginConfig;
+use bodo::plugins::{env_plugin::EnvPlugin, path_plugin::PathPlugin};
+use bodo::script_loader::ScriptLoader;
+use std::collections::HashMap;
+use std::fs;
+use tempfile::tempdir;
+
+fn write_script(dir: &std::path::Path) -> String {
+    let script_path = dir.join("script.yaml");
+    fs::write(
+        &script_path,
+        r#"
+env:
+  LEVEL: script
+  SCRIPT_ONLY: "1"
+exec_paths:
+  - /script/bin
+tasks:
+  build:
+    command: echo build
+    env:
+      LEVEL: task
+    exec_paths:
+      - /task/bin
+    args:
+      - name: TARGET
+        default: debug
+"#,
+    )
+    .unwrap();
+    script_path.to_str().unwrap().to_string()
+}
+
+fn global_config(root_script: String) -> BodoConfig {
+    let mut env = HashMap::new();
+    env.insert("LEVEL".to_string(), "global".to_string());
+    env.insert("GLOBAL_ONLY".to_string(), "1".to_string());
+    BodoConfig {
+        root_script: Some(root_script),
+        env,
+        exec_paths: vec!["/global/bin".to_string(), "/script/bin".to_string()],
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_env_and_exec_paths_merge_global_script_task() {
+    let temp_dir = tempdir().unwrap();
+    let config = global_config(write_script(temp_dir.path()));
+
+    let mut loader = ScriptLoader::new();
+    let graph = loader.build_graph(config).unwrap();
+    let node = &graph.nodes[graph.task_registry["build"] as usize];
+    let NodeKind::Task(task) = &node.kind else {
+        panic!("Expected a Task node");
+    };
+
+    assert_eq!(task.env["LEVEL"], "task");
+    assert_eq!(task.env["SCRIPT_ONLY"], "1");
+    assert_eq!(task.env["GLOBAL_ONLY"], "1");
+    // The most specific paths come first; duplicates keep their most specific position.
+    assert_eq!(
+        task.exec_paths,
+        vec!["/task/bin", "/script/bin", "/global/bin"]
+    );
+
+    let sources = node.env_sources();
+    assert_eq!(sources["LEVEL"], "task");
+    assert_eq!(sources["SCRIPT_ONLY"], "script");
+    assert_eq!(sources["GLOBAL_ONLY"], "global");
+    let path_sources = node.exec_path_sources();
+    assert_eq!(path_sources["/global/bin"], "global");
+    assert_eq!(path_sources["/script/bin"], "script");
+    assert_eq!(path_sources["/task/bin"], "task");
+}
+
+#[test]
+fn test_cli_arguments_override_every_level() {
+    let temp_dir = tempdir().unwrap();
+    let mut config = global_config(write_script(temp_dir.path()));
+    config
+        .env
+        .insert("TARGET".to_string(), "from-global".to_string());
+
+    let mut manager = GraphManager::new();
+    manager.build_graph(config).unwrap();
+    manager
+        .apply_task_arguments("build", &["release".to_string()])
+        .unwrap();
+    manager.register_plugin(Box::new(EnvPlugin::new()));
+    manager.register_plugin(Box::new(PathPlugin::new()));
+
+    let mut extra_env = serde_json::Map::new();
+    extra_env.insert("TARGET".into(), serde_json::Value::String("plugin".into()));
+    extra_env.insert("EXTRA".into(), serde_json::Value::String("plugin".into()));
+    let mut options = serde_json::Map::new();
+    options.insert("env".into(), serde_json::Value::Object(extra_env));
+    options.insert("preserve_path".into(), serde_json::Value::Bool(false));
+    manager
+        .run_plugins(Some(PluginConfig {
+            options: Some(options),
+            ..Default::default()
+        }))
+        .unwrap();
+
+    let node = &manager.graph.nodes[manager.graph.task_registry["build"] as usize];
+    let NodeKind::Task(task) = &node.kind else {
+        panic!("Expected a Task node");
+    };
+    assert_eq!(task.env["TARGET"], "release");
+    assert_eq!(task.env["EXTRA"], "plugin");
+    assert_eq!(task.env["PATH"], "/task/bin:/script/bin:/global/bin");
+
+    let sources = node.env_sources();
+    assert_eq!(sources["TARGET"], "cli");
+    assert_eq!(sources["EXTRA"], "plugin");
+    assert_eq!(sources["PATH"], "exec_paths");
+}
+
+#[test]
+fn test_cli_arguments_reach_inline_dependency_commands() {
+    let temp_dir = tempdir().unwrap();
+    let script_path = temp_dir.path().join("script.yaml");
+    fs::write(
+        &script_path,
+        r#"
+tasks:
+  deploy:
+    command: echo deploy
+    args:
+      - name: STAGE
+        default: dev
+    pre_deps:
+      - command: echo before $STAGE
+    post_deps:
+      - command: echo after $STAGE
+"#,
+    )
+    .unwrap();
+    let config = BodoConfig {
+        root_script: Some(script_path.to_str().unwrap().to_string()),
+        ..Default::default()
+    };
+
+    let mut manager = GraphManager::new();
+    manager.build_graph(config).unwrap();
+    manager
+        .apply_task_arguments("deploy", &["prod".to_string()])
+        .unwrap();
+
+    let commands: Vec<_> = manager
+        .graph
+        .nodes
+        .iter()
+        .filter(|node| matches!(node.kind, NodeKind::Command(_)))
+        .collect();
+    assert_eq!(commands.len(), 2);
+    for node in commands {
+        let NodeKind::Command(cmd) = &node.kind else {
+            unreachable!()
+        };
+        assert_eq!(cmd.env["STAGE"], "prod", "{}", cmd.raw_command);
+        assert_eq!(node.env_sources()["STAGE"], "cli");
+    }
+}
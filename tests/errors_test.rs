@@ -11,8 +11,8 @@ fn test_bodo_error_display() {
     let err = BodoError::WatcherError("watcher_error".to_string());
     assert_eq!(format!("{}", err), "watcher_error");
 
-    let err = BodoError::TaskNotFound("missing_task".to_string());
-    assert_eq!(format!("{}", err), "not found");
+    let err = BodoError::TaskNotFound("missing_task".to_string(), vec![]);
+    assert_eq!(format!("{}", err), "task 'missing_task' not found");
 
     let err = BodoError::PluginError("plugin failure".to_string());
     assert_eq!(format!("{}", err), "Plugin error: plugin failure");
@@ -246,11 +246,16 @@ fn test_enhanced_dry_run_output() {
 
     // Create mock dry run reports
     let reports = vec![bodo::plugin::DryRunReport {
+        id: "test_task".to_string(),
+        name: "test_task".to_string(),
         command: "echo 'test' > file.txt".to_string(),
         environment: HashMap::new(),
+        environment_sources: HashMap::new(),
         working_directory: std::path::PathBuf::from("/tmp"),
         dependencies: vec![],
         estimated_duration: Some(std::time::Duration::from_secs(1)),
+        timeout: None,
+        group: None,
         side_effects: vec![
             SideEffect::ProcessSpawn("echo 'test' > file.txt".to_string()),
             SideEffect::FileWrite(std::path::PathBuf::from("/tmp/file.txt")),
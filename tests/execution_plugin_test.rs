@@ -92,6 +92,7 @@ fn test_execution_plugin_on_after_run_with_command_node() -> Result<()> {
         description: None,
         working_dir: None,
         env: HashMap::new(),
+        exec_paths: vec![],
         watch: None,
     }));
     graph.add_edge(task_id, command_id)?;
@@ -159,5 +160,5 @@ fn test_execution_plugin_task_not_found() {
     plugin.task_name = Some("nonexistent_task".to_string());
     let mut graph = Graph::new();
     let result = plugin.on_after_run(&mut graph);
-    assert!(matches!(result, Err(BodoError::TaskNotFound(_))));
+    assert!(matches!(result, Err(BodoError::TaskNotFound(..))));
 }
@@ -41,6 +41,13 @@ mod extra_get_task_name_success {
             auto_watch: false,
             debug: false,
             dry_run: false,
+            side_effects: false,
+            sandbox: false,
+            interactive: false,
+            help: false,
+            command: None,
+            graph: false,
+            format: None,
             task: None,
             subtask: None,
             args: vec![],
@@ -81,6 +88,13 @@ mod extra_get_task_name_success {
             auto_watch: false,
             debug: false,
             dry_run: false,
+            side_effects: false,
+            sandbox: false,
+            interactive: false,
+            help: false,
+            command: None,
+            graph: false,
+            format: None,
             task: Some("build".to_string()),
             subtask: None,
             args: vec![],
@@ -121,6 +135,13 @@ mod extra_get_task_name_success {
             auto_watch: false,
             debug: false,
             dry_run: false,
+            side_effects: false,
+            sandbox: false,
+            interactive: false,
+            help: false,
+            command: None,
+            graph: false,
+            format: None,
             task: Some("deploy".to_string()),
             subtask: Some("prod".to_string()),
             args: vec![],
@@ -138,6 +159,13 @@ mod extra_get_task_name_success {
             auto_watch: false,
             debug: false,
             dry_run: false,
+            side_effects: false,
+            sandbox: false,
+            interactive: false,
+            help: false,
+            command: None,
+            graph: false,
+            format: None,
             task: None,
             subtask: None,
             args: vec![],
@@ -178,11 +206,18 @@ mod extra_get_task_name_success {
             auto_watch: false,
             debug: false,
             dry_run: false,
+            side_effects: false,
+            sandbox: false,
+            interactive: false,
+            help: false,
+            command: None,
+            graph: false,
+            format: None,
             task: Some("nonexistent".to_string()),
             subtask: None,
             args: vec![],
         };
         let res = get_task_name(&args, &gm);
-        assert!(matches!(res, Err(BodoError::TaskNotFound(_))));
+        assert!(matches!(res, Err(BodoError::TaskNotFound(..))));
     }
 }
@@ -129,6 +129,7 @@ fn test_apply_task_arguments_with_defaults() {
             description: None,
             required: false,
             default: Some("Hello".to_string()),
+            ..Default::default()
         }],
         ..Default::default()
     };
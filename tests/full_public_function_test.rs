@@ -43,6 +43,13 @@ mod new_tests {
             auto_watch: false,
             debug: false,
             dry_run: false,
+            side_effects: false,
+            sandbox: false,
+            interactive: false,
+            help: false,
+            command: None,
+            graph: false,
+            format: None,
             task: None,
             subtask: None,
             args: vec![],
@@ -83,6 +90,13 @@ mod new_tests {
             auto_watch: false,
             debug: false,
             dry_run: false,
+            side_effects: false,
+            sandbox: false,
+            interactive: false,
+            help: false,
+            command: None,
+            graph: false,
+            format: None,
             task: Some("build".to_string()),
             subtask: None,
             args: vec![],
@@ -100,6 +114,13 @@ mod new_tests {
             auto_watch: false,
             debug: false,
             dry_run: false,
+            side_effects: false,
+            sandbox: false,
+            interactive: false,
+            help: false,
+            command: None,
+            graph: false,
+            format: None,
             task: None,
             subtask: None,
             args: vec![],
@@ -140,11 +161,18 @@ mod new_tests {
             auto_watch: false,
             debug: false,
             dry_run: false,
+            side_effects: false,
+            sandbox: false,
+            interactive: false,
+            help: false,
+            command: None,
+            graph: false,
+            format: None,
             task: Some("nonexistent".to_string()),
             subtask: None,
             args: vec![],
         };
         let res = get_task_name(&args, &gm);
-        assert!(matches!(res, Err(BodoError::TaskNotFound(_))));
+        assert!(matches!(res, Err(BodoError::TaskNotFound(..))));
     }
 }
@@ -1,6 +1,10 @@
+use bodo::config::BodoConfig;
 use bodo::graph::TaskData;
-use bodo::graph::{Edge, Graph, NodeKind};
+use bodo::graph::{Edge, EdgeKind, Graph, NodeKind};
+use bodo::manager::GraphManager;
+use bodo::plugins::concurrent_plugin::ConcurrentPlugin;
 use std::collections::HashMap;
+use std::fs;
 
 #[test]
 fn test_detect_and_format_cycle() {
@@ -58,9 +62,21 @@ fn test_detect_and_format_cycle() {
         concurrently_options: Default::default(),
     }));
     // Add edges to form a cycle: A->B, B->C, C->A.
-    graph.edges.push(Edge { from: a, to: b });
-    graph.edges.push(Edge { from: b, to: c });
-    graph.edges.push(Edge { from: c, to: a });
+    graph.edges.push(Edge {
+        from: a,
+        to: b,
+        kind: EdgeKind::PreDep,
+    });
+    graph.edges.push(Edge {
+        from: b,
+        to: c,
+        kind: EdgeKind::PreDep,
+    });
+    graph.edges.push(Edge {
+        from: c,
+        to: a,
+        kind: EdgeKind::PreDep,
+    });
 
     let cycle = graph.detect_cycle();
     assert!(cycle.is_some(), "Cycle should be detected");
@@ -71,3 +87,30 @@ fn test_detect_and_format_cycle() {
         "Error message should mention dependencies"
     );
 }
+
+#[test]
+fn test_cycle_through_concurrently_is_rejected_after_graph_build() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    fs::write(
+        temp_dir.path().join("script.yaml"),
+        "tasks:\n  a:\n    concurrently:\n      - task: b\n  b:\n    command: echo b\n    pre_deps:\n      - task: a\n",
+    )
+    .unwrap();
+
+    let mut manager = GraphManager::new();
+    manager
+        .build_graph(BodoConfig {
+            scripts_dirs: Some(vec![temp_dir.path().to_str().unwrap().to_string()]),
+            ..Default::default()
+        })
+        .unwrap();
+    manager.register_plugin(Box::new(ConcurrentPlugin::new()));
+
+    let err = manager.run_plugins(None).unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("found cyclical dependency involving"),
+        "{}",
+        err
+    );
+}
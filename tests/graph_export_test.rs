@@ -0,0 +1,143 @@
+use bodo::config::BodoConfig;
+use bodo::manager::GraphManager;
+use bodo::plugins::concurrent_plugin::ConcurrentPlugin;
+use bodo::plugins::graph_plugin::{
+    export_graph, to_dot, to_mermaid, GraphExport, GraphFormat, GRAPH_EXPORT_VERSION,
+};
+use std::collections::HashSet;
+
+const CONFIG: &str = r#"
+tasks:
+  release:
+    command: echo release
+    timeout: 30s
+    pre_deps:
+      - task: build
+    concurrently:
+      - task: test
+      - command: echo "docs"
+    concurrently_options:
+      prefix_color: cyan
+    post_deps:
+      - task: notify
+  build:
+    command: cargo build
+    watch:
+      patterns:
+        - "src/**/*.rs"
+  test:
+    command: cargo test
+  notify:
+    command: echo done
+  unrelated:
+    command: echo unrelated
+"#;
+
+fn build() -> GraphManager {
+    let config: BodoConfig = serde_yaml::from_str(CONFIG).unwrap();
+    let mut manager = GraphManager::new();
+    manager.build_graph(config).unwrap();
+    manager.register_plugin(Box::new(ConcurrentPlugin::new()));
+    manager.run_plugins(None).unwrap();
+    manager
+}
+
+fn export(root: Option<&str>) -> GraphExport {
+    let manager = build();
+    let root = root.map(|task| manager.graph.task_registry[task]);
+    export_graph(&manager.graph, root)
+}
+
+#[test]
+fn test_json_export_has_stable_ids_and_edge_kinds() {
+    let export = export(Some("release"));
+    assert_eq!(export.version, GRAPH_EXPORT_VERSION);
+    assert_eq!(export.root.as_deref(), Some("release"));
+
+    let ids: Vec<&str> = export.nodes.iter().map(|n| n.id.as_str()).collect();
+    assert_eq!(
+        ids,
+        vec![
+            "build",
+            "notify",
+            "release",
+            "release/concurrent0",
+            "release/concurrent0/concurrent1",
+            "test"
+        ]
+    );
+    let edges: Vec<(&str, &str, &str)> = export
+        .edges
+        .iter()
+        .map(|e| (e.from.as_str(), e.to.as_str(), e.kind.as_str()))
+        .collect();
+    assert_eq!(
+        edges,
+        vec![
+            ("release", "build", "pre"),
+            ("release", "release/concurrent0", "concurrent"),
+            ("release", "notify", "post"),
+            ("release/concurrent0", "test", "concurrent"),
+            (
+                "release/concurrent0",
+                "release/concurrent0/concurrent1",
+                "concurrent"
+            ),
+        ]
+    );
+
+    let release = &export.nodes[2];
+    assert_eq!(release.metadata["timeout"], "30s");
+    assert!(!release.metadata.contains_key("env_sources"));
+    let group = &export.nodes[3];
+    assert_eq!(group.kind, "concurrent_group");
+    assert_eq!(group.metadata["prefix_color"], "cyan");
+    // Only what the scripts declare; no bookkeeping such as script paths or skip_main_pass
+    let keys: HashSet<&str> = export
+        .nodes
+        .iter()
+        .flat_map(|node| node.metadata.keys().map(String::as_str))
+        .collect();
+    assert_eq!(keys, HashSet::from(["timeout", "prefix_color"]));
+    assert_eq!(
+        export.nodes[0].watch.as_ref().unwrap().patterns,
+        vec!["src/**/*.rs"]
+    );
+}
+
+#[test]
+fn test_json_export_is_identical_across_builds() {
+    // Node ids in the graph depend on load order; the export must not.
+    let first = serde_json::to_string_pretty(&export(None)).unwrap();
+    for _ in 0..5 {
+        assert_eq!(serde_json::to_string_pretty(&export(None)).unwrap(), first);
+    }
+    let parsed: GraphExport = serde_json::from_str(&first).unwrap();
+    assert_eq!(parsed, export(None));
+    assert!(parsed.nodes.iter().any(|n| n.id == "unrelated"));
+}
+
+#[test]
+fn test_dot_and_mermaid_output() {
+    let export = export(Some("release"));
+
+    let dot = to_dot(&export);
+    assert!(dot.starts_with("digraph bodo {"));
+    assert!(dot.contains(r#""release" [label="release\ntimeout: 30s", shape=box];"#));
+    assert!(dot.contains(r#"[label="echo \"docs\"", shape=ellipse]"#));
+    assert!(dot.contains(r#""release" -> "notify" [label="post", style=dashed];"#));
+
+    let mermaid = to_mermaid(&export);
+    assert!(mermaid.starts_with("flowchart LR\n"));
+    assert!(mermaid.contains(r#"n4(["echo #quot;docs#quot;"])"#));
+    assert!(mermaid.contains("n2 -->|pre| n0"));
+    assert!(mermaid.contains("n2 -.->|post| n1"));
+    assert!(mermaid.contains("n3 ==>|concurrent| n5"));
+}
+
+#[test]
+fn test_unknown_format_is_rejected() {
+    assert_eq!("dot".parse::<GraphFormat>().unwrap(), GraphFormat::Dot);
+    let err = "xml".parse::<GraphFormat>().unwrap_err().to_string();
+    assert!(err.contains("dot, mermaid, json"), "{}", err);
+}
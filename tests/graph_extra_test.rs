@@ -32,6 +32,7 @@ fn test_print_debug_and_get_node_name() {
         description: Some("Command Description".to_string()),
         working_dir: None,
         env: HashMap::new(),
+        exec_paths: vec![],
         watch: None,
     }));
     let cmd_name = graph.node_name(cmd_id as usize);
@@ -82,6 +83,7 @@ fn test_get_node_name_for_various_types() {
         description: None,
         working_dir: None,
         env: HashMap::new(),
+        exec_paths: vec![],
         watch: None,
     }));
     let name2 = graph.node_name(cmd_id as usize);
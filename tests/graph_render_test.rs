@@ -0,0 +1,117 @@
+use bodo::config::BodoConfig;
+use bodo::manager::GraphManager;
+use bodo::plugins::concurrent_plugin::ConcurrentPlugin;
+use bodo::plugins::graph_plugin::{render_tree, TreeStyle};
+
+fn build(config_yaml: &str) -> GraphManager {
+    let config: BodoConfig = serde_yaml::from_str(config_yaml).unwrap();
+    let mut manager = GraphManager::new();
+    manager.build_graph(config).unwrap();
+    manager.register_plugin(Box::new(ConcurrentPlugin::new()));
+    manager.run_plugins(None).unwrap();
+    manager
+}
+
+const CONFIG: &str = r#"
+tasks:
+  release:
+    command: echo release
+    pre_deps:
+      - task: build
+      - task: lint
+    concurrently:
+      - task: test
+      - command: echo docs
+    concurrently_options:
+      max_concurrent_tasks: 2
+    post_deps:
+      - task: notify
+  build:
+    command: cargo build
+  lint:
+    command: cargo clippy
+    pre_deps:
+      - task: build
+  test:
+    command: cargo test
+    pre_deps:
+      - task: build
+  notify:
+    command: echo done
+"#;
+
+#[test]
+fn test_unicode_tree_marks_node_kinds_and_back_references() {
+    let manager = build(CONFIG);
+    let root = manager.graph.task_registry["release"];
+    let tree = render_tree(&manager.graph, root, TreeStyle::Unicode);
+    let expected = r#"📦 release
+├── 📦 build
+│   └── 🚀 "cargo build"
+├── 📦 lint
+│   ├── 📦 build (see above)
+│   └── 🚀 "cargo clippy"
+├── 🔀 concurrently (fail_fast, max 2)
+│   ╔══════════════════════════╗
+│   📦 test                    🚀 "echo docs"
+│   ├── 📦 build (see above)
+│   └── 🚀 "cargo test"
+├── 🚀 "echo release"
+└── 📦 notify (after)
+    └── 🚀 "echo done"
+"#;
+    assert_eq!(tree, expected);
+}
+
+#[test]
+fn test_ascii_tree() {
+    let manager = build(CONFIG);
+    let root = manager.graph.task_registry["lint"];
+    let tree = render_tree(&manager.graph, root, TreeStyle::Ascii);
+    let expected = r#"[task] lint
+|-- [task] build
+|   `-- [cmd] "cargo build"
+`-- [cmd] "cargo clippy"
+"#;
+    assert_eq!(tree, expected);
+}
+
+#[test]
+fn test_concurrent_children_are_drawn_in_columns() {
+    let manager = build(
+        r#"
+tasks:
+  ci:
+    concurrently:
+      - task: unit
+      - task: e2e
+      - command: cargo fmt --check
+  unit:
+    command: cargo test
+  e2e:
+    command: npm run e2e
+    pre_deps:
+      - task: unit
+"#,
+    );
+    let root = manager.graph.task_registry["ci"];
+    let tree = render_tree(&manager.graph, root, TreeStyle::Unicode);
+    let expected = r#"📦 ci
+└── 🔀 concurrently (fail_fast)
+    ╔═════════════════════╦═════════════════════════╗
+    📦 unit               📦 e2e                    🚀 "cargo fmt --check"
+    └── 🚀 "cargo test"   ├── 📦 unit (see above)
+                          └── 🚀 "npm run e2e"
+"#;
+    assert_eq!(tree, expected);
+
+    let tree = render_tree(&manager.graph, root, TreeStyle::Ascii);
+    let expected = r#"[task] ci
+`-- [parallel] concurrently (fail_fast)
+    +========================+=============================+
+    [task] unit              [task] e2e                    [cmd] "cargo fmt --check"
+    `-- [cmd] "cargo test"   |-- [task] unit (see above)
+                             `-- [cmd] "npm run e2e"
+"#;
+    assert_eq!(tree, expected);
+}
@@ -0,0 +1,140 @@
+use bodo::config::BodoConfig;
+use bodo::history::{History, HISTORY_VERSION, MAX_SAMPLES};
+use bodo::manager::GraphManager;
+use bodo::plugin::PluginConfig;
+use bodo::plugins::execution_plugin::ExecutionPlugin;
+use std::fs;
+use std::time::Duration;
+use tempfile::tempdir;
+
+fn secs(s: u64) -> Duration {
+    Duration::from_secs(s)
+}
+
+#[test]
+fn test_percentiles_over_a_rolling_window() {
+    let temp_dir = tempdir().unwrap();
+    let mut history = History::load(temp_dir.path().join("history.json"));
+    assert_eq!(history.estimate("build"), None);
+
+    for s in 1..=10 {
+        history.record("build", secs(s));
+    }
+    assert_eq!(history.estimate("build"), Some(secs(5)));
+    assert_eq!(history.percentile("build", 90.0), Some(secs(9)));
+    assert_eq!(history.percentile("build", 100.0), Some(secs(10)));
+
+    // Old samples fall out of the window.
+    for _ in 0..MAX_SAMPLES {
+        history.record("build", secs(100));
+    }
+    assert_eq!(history.runs("build"), MAX_SAMPLES);
+    assert_eq!(history.percentile("build", 0.0), Some(secs(100)));
+}
+
+#[test]
+fn test_regressions_need_enough_history_and_a_real_slowdown() {
+    let temp_dir = tempdir().unwrap();
+    let mut history = History::load(temp_dir.path().join("history.json"));
+    for _ in 0..4 {
+        history.record("test", secs(10));
+    }
+    assert_eq!(history.regression("test", secs(60)), None);
+
+    history.record("test", secs(10));
+    assert_eq!(history.regression("test", secs(14)), None);
+    let regression = history.regression("test", secs(16)).unwrap();
+    assert_eq!(regression.p50, secs(10));
+    assert_eq!(
+        regression.to_string(),
+        "took 16.0s, usually 10.0s (p90 10.0s)"
+    );
+
+    // Tiny tasks need to be at least a second slower.
+    for _ in 0..5 {
+        history.record("lint", Duration::from_millis(10));
+    }
+    assert_eq!(history.regression("lint", Duration::from_millis(500)), None);
+}
+
+#[test]
+fn test_history_survives_a_reload_and_ignores_other_versions() {
+    let temp_dir = tempdir().unwrap();
+    let path = temp_dir.path().join(".bodo").join("history.json");
+    let mut history = History::load(&path);
+    history.record("deploy prod", secs(3));
+    history.save().unwrap();
+    history.save().unwrap();
+    assert_eq!(History::load(&path).estimate("deploy prod"), Some(secs(3)));
+    // The file is replaced by renaming a complete copy over it, which is not left behind
+    let files: Vec<_> = fs::read_dir(path.parent().unwrap())
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name())
+        .collect();
+    assert_eq!(files, ["history.json"]);
+
+    fs::write(
+        &path,
+        format!(
+            r#"{{"version": {}, "tasks": {{"deploy prod": [1.0]}}}}"#,
+            HISTORY_VERSION + 1
+        ),
+    )
+    .unwrap();
+    assert_eq!(History::load(&path).runs("deploy prod"), 0);
+    fs::write(&path, "not json").unwrap();
+    assert_eq!(History::load(&path).runs("deploy prod"), 0);
+}
+
+fn run(history_path: &std::path::Path, dry_run: bool) -> GraphManager {
+    let config: BodoConfig = serde_yaml::from_str(
+        r#"
+tasks:
+  build:
+    command: "true"
+    post_deps:
+      - command: "true"
+"#,
+    )
+    .unwrap();
+    let mut manager = GraphManager::new();
+    manager.build_graph(config).unwrap();
+    manager.register_plugin(Box::new(
+        ExecutionPlugin::new().with_history(History::load(history_path)),
+    ));
+    let mut options = serde_json::Map::new();
+    options.insert("task".into(), "build".into());
+    manager
+        .run_plugins(Some(PluginConfig {
+            dry_run,
+            options: Some(options),
+            ..Default::default()
+        }))
+        .unwrap();
+    manager
+}
+
+#[test]
+fn test_runs_are_recorded_and_used_for_dry_run_estimates() {
+    let temp_dir = tempdir().unwrap();
+    let path = temp_dir.path().join("history.json");
+
+    run(&path, false);
+    let history = History::load(&path);
+    assert_eq!(history.runs("build"), 1);
+    assert_eq!(history.runs("build/post0"), 1);
+
+    let mut history = History::load(&path);
+    for _ in 0..3 {
+        history.record("build", secs(42));
+    }
+    history.save().unwrap();
+
+    // A dry run estimates from the history and records nothing.
+    let manager = run(&path, true);
+    let plugin = ExecutionPlugin::new().with_history(History::load(&path));
+    let build = manager.graph.task_registry["build"] as usize;
+    let reports = plugin.plan(&manager.graph, build).unwrap();
+    assert_eq!(reports[0].estimated_duration, Some(secs(42)));
+    assert_eq!(History::load(&path).runs("build"), 4);
+}
@@ -0,0 +1,59 @@
+#![cfg(feature = "dialoguer")]
+
+use bodo::config::BodoConfig;
+use bodo::interactive::{choice_labels, task_choices};
+use bodo::script_loader::ScriptLoader;
+use std::fs;
+use tempfile::tempdir;
+
+#[test]
+fn test_choices_are_grouped_by_script_root_first() {
+    let temp_dir = tempdir().unwrap();
+    let scripts_dir = temp_dir.path().join("scripts");
+    fs::create_dir_all(scripts_dir.join("deploy")).unwrap();
+    fs::write(
+        scripts_dir.join("deploy").join("script.yaml"),
+        r#"
+tasks:
+  prod:
+    description: Ship it
+    command: echo prod
+    args:
+      - name: VERSION
+        default: latest
+"#,
+    )
+    .unwrap();
+    let root_script = temp_dir.path().join("script.yaml");
+    fs::write(
+        &root_script,
+        r#"
+default_task:
+  command: echo hello
+  description: Say hello
+tasks:
+  build:
+    command: echo build
+"#,
+    )
+    .unwrap();
+
+    let graph = ScriptLoader::new()
+        .build_graph(BodoConfig {
+            root_script: Some(root_script.to_str().unwrap().to_string()),
+            scripts_dirs: Some(vec![scripts_dir.to_str().unwrap().to_string()]),
+            ..Default::default()
+        })
+        .unwrap();
+
+    let choices = task_choices(&graph);
+    let keys: Vec<&str> = choices.iter().map(|c| c.key.as_str()).collect();
+    assert_eq!(keys, vec!["build", "default", "deploy prod"]);
+    assert_eq!(choices[2].script, "deploy");
+    assert_eq!(choices[2].arguments[0].default.as_deref(), Some("latest"));
+
+    let labels = choice_labels(&choices);
+    assert_eq!(labels[0], "build");
+    assert_eq!(labels[1], "default      Say hello");
+    assert_eq!(labels[2], "deploy prod  Ship it  [deploy]");
+}
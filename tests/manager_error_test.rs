@@ -11,6 +11,7 @@ fn test_get_task_config_non_task_node() {
         description: None,
         working_dir: None,
         env: Default::default(),
+        exec_paths: vec![],
         watch: None,
     }));
     manager
@@ -33,6 +34,7 @@ fn test_apply_task_arguments_non_task_node() {
             description: Some("A command".to_string()),
             working_dir: None,
             env: Default::default(),
+            exec_paths: vec![],
             watch: None,
         }));
     manager
@@ -12,6 +12,7 @@ fn test_missing_required_argument() {
             description: Some("A required argument".to_string()),
             required: true,
             default: None,
+            ..Default::default()
         }],
         ..Default::default()
     };
@@ -34,3 +35,23 @@ fn test_missing_required_argument() {
         panic!("Expected PluginError for missing required argument");
     }
 }
+
+#[test]
+fn test_task_without_arguments_ignores_trailing_values() {
+    let task_config = TaskConfig {
+        command: Some("cargo test".to_string()),
+        ..Default::default()
+    };
+    let mut tasks = HashMap::new();
+    tasks.insert("test".to_string(), task_config);
+    let config = BodoConfig {
+        tasks,
+        ..Default::default()
+    };
+    let mut manager = GraphManager::new();
+    manager.build_graph(config).unwrap();
+    // `bodo test -- --nocapture`
+    manager
+        .apply_task_arguments("test", &["--nocapture".to_string()])
+        .unwrap();
+}
@@ -76,6 +76,7 @@ fn test_apply_task_arguments_with_defaults() {
             description: None,
             required: false,
             default: Some("Hello".to_string()),
+            ..Default::default()
         }],
         ..Default::default()
     };
@@ -108,5 +109,5 @@ fn test_apply_task_arguments_with_defaults() {
 fn test_get_task_config_nonexistent_task() {
     let manager = GraphManager::new();
     let result = manager.get_task_config("nonexistent");
-    assert!(matches!(result, Err(BodoError::TaskNotFound(_))));
+    assert!(matches!(result, Err(BodoError::TaskNotFound(..))));
 }
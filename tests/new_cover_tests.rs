@@ -42,6 +42,13 @@ mod new_tests {
             auto_watch: false,
             debug: false,
             dry_run: false,
+            side_effects: false,
+            sandbox: false,
+            interactive: false,
+            help: false,
+            command: None,
+            graph: false,
+            format: None,
             task: None,
             subtask: None,
             args: vec![],
@@ -82,6 +89,13 @@ mod new_tests {
             auto_watch: false,
             debug: false,
             dry_run: false,
+            side_effects: false,
+            sandbox: false,
+            interactive: false,
+            help: false,
+            command: None,
+            graph: false,
+            format: None,
             task: Some("build".to_string()),
             subtask: None,
             args: vec![],
@@ -98,8 +112,8 @@ mod new_tests {
         let watcher_err = BodoError::WatcherError("watcher error".to_string());
         assert_eq!(format!("{}", watcher_err), "watcher error");
 
-        let task_not_found = BodoError::TaskNotFound("not_found".to_string());
-        assert_eq!(format!("{}", task_not_found), "not found");
+        let task_not_found = BodoError::TaskNotFound("not_found".to_string(), vec![]);
+        assert_eq!(format!("{}", task_not_found), "task 'not_found' not found");
 
         let plugin_err = BodoError::PluginError("plugin fail".to_string());
         assert_eq!(format!("{}", plugin_err), "Plugin error: plugin fail");
@@ -26,3 +26,53 @@ fn test_build_path_with_no_working_dir_and_preserve() {
     env::set_var("PATH", original);
     assert_eq!(result, "/default:/exec:/existing");
 }
+
+#[test]
+fn test_inline_commands_get_task_exec_paths() {
+    use bodo::config::BodoConfig;
+    use bodo::graph::NodeKind;
+    use bodo::manager::GraphManager;
+    use bodo::plugins::concurrent_plugin::ConcurrentPlugin;
+
+    let config: BodoConfig = serde_yaml::from_str(
+        r#"
+tasks:
+  build:
+    command: echo build
+    exec_paths: ["/opt/tools/bin"]
+    pre_deps:
+      - command: echo before
+    post_deps:
+      - command: echo after
+    concurrently:
+      - command: echo alongside
+"#,
+    )
+    .unwrap();
+    let mut manager = GraphManager::new();
+    manager.build_graph(config).unwrap();
+    manager.register_plugin(Box::new(ConcurrentPlugin::new()));
+    manager.register_plugin(Box::new(PathPlugin::new()));
+    manager.run_plugins(None).unwrap();
+
+    let commands: Vec<_> = manager
+        .graph
+        .nodes
+        .iter()
+        .filter_map(|node| match &node.kind {
+            NodeKind::Command(cmd) => Some(cmd),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(commands.len(), 3);
+    for cmd in commands {
+        assert_eq!(cmd.exec_paths, vec!["/opt/tools/bin".to_string()]);
+        let path = cmd.env.get("PATH").map(String::as_str).unwrap_or_default();
+        assert!(
+            path.split(':').any(|p| p == "/opt/tools/bin"),
+            "{}: PATH is {:?}",
+            cmd.raw_command,
+            path
+        );
+    }
+}
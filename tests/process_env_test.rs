@@ -0,0 +1,90 @@
+use bodo::config::BodoConfig;
+use bodo::manager::GraphManager;
+use bodo::plugin::PluginConfig;
+use bodo::plugins::{
+    env_plugin::EnvPlugin, execution_plugin::ExecutionPlugin, path_plugin::PathPlugin,
+};
+use bodo::process::{ProcessManager, SpawnOptions};
+use std::collections::HashMap;
+use std::fs;
+use tempfile::tempdir;
+
+#[test]
+fn test_spawn_with_options_sets_child_env() {
+    let temp_dir = tempdir().unwrap();
+    let out = temp_dir.path().join("env.txt");
+    let mut env = HashMap::new();
+    env.insert("BODO_TEST_VALUE".to_string(), "from-task".to_string());
+
+    let mut pm = ProcessManager::new(true);
+    pm.spawn_with_options(
+        "env",
+        &format!("printenv BODO_TEST_VALUE > {}", out.display()),
+        SpawnOptions {
+            env,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    pm.run_concurrently().unwrap();
+
+    assert_eq!(fs::read_to_string(&out).unwrap().trim(), "from-task");
+}
+
+#[test]
+fn test_task_env_script_env_and_exec_paths_reach_child() {
+    let temp_dir = tempdir().unwrap();
+    let bin_dir = temp_dir.path().join("bin");
+    fs::create_dir_all(&bin_dir).unwrap();
+    let tool = bin_dir.join("bodo-test-tool");
+    fs::write(&tool, "#!/bin/sh\necho \"tool $SCRIPT_VAR $TASK_VAR\"\n").unwrap();
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&tool, fs::Permissions::from_mode(0o755)).unwrap();
+    }
+    let out = temp_dir.path().join("out.txt");
+
+    let script_path = temp_dir.path().join("script.yaml");
+    fs::write(
+        &script_path,
+        format!(
+            r#"
+env:
+  SCRIPT_VAR: script
+tasks:
+  run-tool:
+    env:
+      TASK_VAR: task
+    exec_paths:
+      - {}
+    command: bodo-test-tool > {}
+"#,
+            bin_dir.display(),
+            out.display()
+        ),
+    )
+    .unwrap();
+
+    let mut manager = GraphManager::new();
+    manager
+        .build_graph(BodoConfig {
+            root_script: Some(script_path.to_str().unwrap().to_string()),
+            ..Default::default()
+        })
+        .unwrap();
+    manager.register_plugin(Box::new(EnvPlugin::new()));
+    manager.register_plugin(Box::new(PathPlugin::new()));
+    manager.register_plugin(Box::new(ExecutionPlugin::new()));
+
+    let mut options = serde_json::Map::new();
+    options.insert("task".into(), serde_json::Value::String("run-tool".into()));
+    manager
+        .run_plugins(Some(PluginConfig {
+            options: Some(options),
+            ..Default::default()
+        }))
+        .unwrap();
+
+    assert_eq!(fs::read_to_string(&out).unwrap().trim(), "tool script task");
+}
@@ -65,3 +65,19 @@ fn test_spawn_command_with_invalid_prefix_color() {
     pm.run_concurrently().unwrap();
     // Should not panic or crash due to invalid color
 }
+
+#[cfg(target_os = "linux")]
+#[test]
+fn test_fail_fast_blames_failed_process_and_reaps_the_others() {
+    let mut pm = ProcessManager::new(true);
+    pm.spawn_command("slow", "sleep 30", false, None, None, None)
+        .unwrap();
+    pm.spawn_command("broken", "sleep 0.2; exit 3", false, None, None, None)
+        .unwrap();
+    let slow = pm.children[0].child.id();
+
+    let err = pm.run_concurrently().unwrap_err();
+    assert_eq!(err.to_string(), "Process 'broken' failed with exit code 3");
+    // Neither running nor a zombie waiting to be reaped
+    assert!(!std::path::Path::new(&format!("/proc/{}", slow)).exists());
+}
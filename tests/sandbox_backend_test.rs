@@ -0,0 +1,148 @@
+use bodo::config::{BodoConfig, SandboxConfig};
+use bodo::manager::GraphManager;
+use bodo::plugins::execution_plugin::ExecutionPlugin;
+use bodo::process::{ProcessManager, SpawnOptions};
+use bodo::sandbox::SandboxBackend;
+use std::path::Path;
+use std::process::Command;
+use tempfile::tempdir;
+
+fn args_of(command: &Command) -> Vec<String> {
+    command
+        .get_args()
+        .map(|arg| arg.to_string_lossy().into_owned())
+        .collect()
+}
+
+fn contains(args: &[String], expected: &[&str]) -> bool {
+    args.windows(expected.len())
+        .any(|window| window == expected)
+}
+
+fn policy() -> SandboxConfig {
+    SandboxConfig {
+        network: false,
+        writable: vec!["out".to_string()],
+        read_only: vec!["/opt/tools".to_string()],
+    }
+}
+
+#[test]
+fn test_bwrap_confines_writes_and_network() {
+    let dir = Path::new("/work/project");
+    let command = SandboxBackend::Bwrap.command(&policy(), "make", dir);
+    assert_eq!(command.get_program(), "bwrap");
+    let args = args_of(&command);
+    assert!(args.contains(&"--unshare-all".to_string()));
+    assert!(!args.contains(&"--share-net".to_string()));
+    assert!(contains(&args, &["--ro-bind-try", "/usr", "/usr"]));
+    assert!(contains(
+        &args,
+        &["--ro-bind-try", "/opt/tools", "/opt/tools"]
+    ));
+    assert!(contains(
+        &args,
+        &["--bind", "/work/project", "/work/project"]
+    ));
+    assert!(contains(
+        &args,
+        &["--bind-try", "/work/project/out", "/work/project/out"]
+    ));
+    assert!(args.ends_with(&["sh".into(), "-c".into(), "make".into()]));
+
+    let networked = SandboxConfig {
+        network: true,
+        ..Default::default()
+    };
+    let args = args_of(&SandboxBackend::Bwrap.command(&networked, "make", dir));
+    assert!(args.contains(&"--share-net".to_string()));
+}
+
+#[test]
+fn test_firejail_confines_writes_and_network() {
+    let dir = Path::new("/work/project");
+    let command = SandboxBackend::Firejail.command(&policy(), "make", dir);
+    assert_eq!(command.get_program(), "firejail");
+    assert_eq!(command.get_current_dir(), Some(dir));
+    let args = args_of(&command);
+    assert!(args.contains(&"--net=none".to_string()));
+    assert!(args.contains(&"--read-only=/".to_string()));
+    assert!(args.contains(&"--read-write=/work/project".to_string()));
+    assert!(args.contains(&"--read-write=/work/project/out".to_string()));
+    assert!(args.ends_with(&["--".into(), "sh".into(), "-c".into(), "make".into()]));
+}
+
+#[test]
+fn test_policy_comes_from_the_task_and_covers_its_inline_commands() {
+    let config: BodoConfig = serde_yaml::from_str(
+        r#"
+tasks:
+  untrusted:
+    command: ./install.sh
+    sandbox:
+      network: true
+      writable: [build]
+    post_deps:
+      - command: echo done
+  trusted:
+    command: echo trusted
+"#,
+    )
+    .unwrap();
+    let mut manager = GraphManager::new();
+    manager.build_graph(config).unwrap();
+    let graph = &manager.graph;
+    let untrusted = graph.task_registry["untrusted"];
+    let trusted = graph.task_registry["trusted"];
+    let post = graph.dependencies_of(untrusted, bodo::graph::EdgeKind::PostDep)[0];
+
+    let mut plugin = ExecutionPlugin::new();
+    let expected = SandboxConfig {
+        network: true,
+        writable: vec!["build".to_string()],
+        read_only: vec![],
+    };
+    assert_eq!(
+        plugin.sandbox_policy(graph, untrusted).unwrap(),
+        Some(expected.clone())
+    );
+    assert_eq!(plugin.sandbox_policy(graph, post).unwrap(), Some(expected));
+    assert_eq!(plugin.sandbox_policy(graph, trusted).unwrap(), None);
+
+    // `--sandbox` puts everything else in the default sandbox.
+    plugin.sandbox = true;
+    assert_eq!(
+        plugin.sandbox_policy(graph, trusted).unwrap(),
+        Some(SandboxConfig::default())
+    );
+
+    let err = serde_yaml::from_str::<SandboxConfig>("network: false\nwrite: [x]\n").unwrap_err();
+    assert!(err.to_string().contains("unknown field `write`"), "{}", err);
+}
+
+#[test]
+fn test_sandboxed_spawn_never_runs_unconfined() {
+    let temp_dir = tempdir().unwrap();
+    let mut pm = ProcessManager::new(true);
+    let result = pm.spawn_with_options(
+        "untrusted",
+        "touch inside",
+        SpawnOptions {
+            working_dir: Some(temp_dir.path().to_string_lossy().into_owned()),
+            sandbox: Some(SandboxConfig::default()),
+            ..Default::default()
+        },
+    );
+    match SandboxBackend::detect() {
+        None => {
+            let err = result.unwrap_err();
+            assert_eq!(err.kind(), std::io::ErrorKind::NotFound);
+            assert!(!temp_dir.path().join("inside").exists());
+        }
+        Some(_) => {
+            result.unwrap();
+            pm.run_concurrently().unwrap();
+            assert!(temp_dir.path().join("inside").exists());
+        }
+    }
+}
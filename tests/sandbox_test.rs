@@ -200,3 +200,72 @@ fn test_sandbox_working_directory() {
         }
     }
 }
+
+#[test]
+fn test_sandbox_reports_modified_deleted_and_created_paths() {
+    use bodo::plugin::SideEffect;
+    use bodo::sandbox::SandboxBackend;
+    use std::fs;
+
+    // Commands are only run for analysis inside bwrap or firejail
+    if SandboxBackend::detect().is_none() {
+        println!("neither bwrap nor firejail is available, skipping");
+        return;
+    }
+    let sandbox = Sandbox::new().expect("Failed to create sandbox");
+    let project = TempDir::new().expect("Failed to create temp dir");
+    let wd = project.path();
+    fs::write(wd.join("a.txt"), "a\n").unwrap();
+    fs::write(wd.join("b.txt"), "b\n").unwrap();
+    fs::write(wd.join("c.txt"), "c\n").unwrap();
+    fs::create_dir(wd.join("target")).unwrap();
+
+    let command = "echo new > new.txt && echo changed >> a.txt && rm b.txt && mkdir out \
+                   && test -f c.txt && touch saw_c && (test -d target || touch no_target)";
+    let side_effects = sandbox
+        .execute_and_analyze(command, wd, &HashMap::new())
+        .expect("Sandbox execution failed");
+
+    for expected in [
+        SideEffect::FileWrite(wd.join("new.txt")),
+        SideEffect::FileModify(wd.join("a.txt")),
+        SideEffect::FileDelete(wd.join("b.txt")),
+        SideEffect::DirectoryCreate(wd.join("out")),
+        // The project's files are copied in, but not build output
+        SideEffect::FileWrite(wd.join("saw_c")),
+        SideEffect::FileWrite(wd.join("no_target")),
+    ] {
+        assert!(
+            side_effects.contains(&expected),
+            "missing {:?} in {:?}",
+            expected,
+            side_effects
+        );
+    }
+    assert!(!side_effects.contains(&SideEffect::FileModify(wd.join("c.txt"))));
+
+    // The command ran against the copy, not the project
+    assert_eq!(fs::read_to_string(wd.join("a.txt")).unwrap(), "a\n");
+    assert!(wd.join("b.txt").exists());
+    assert!(!wd.join("new.txt").exists());
+}
+
+#[test]
+fn test_sandbox_refuses_to_run_without_isolation() {
+    use bodo::sandbox::SandboxBackend;
+
+    if SandboxBackend::detect().is_some() {
+        println!("a sandbox tool is available, skipping");
+        return;
+    }
+    let project = TempDir::new().expect("Failed to create temp dir");
+    let marker = project.path().join("ran");
+    let command = format!("touch {}", marker.display());
+
+    let result = Sandbox::new()
+        .expect("Failed to create sandbox")
+        .execute_and_analyze(&command, project.path(), &HashMap::new());
+    let err = result.expect_err("analysis should refuse to run the command unconfined");
+    assert!(err.to_string().contains("bwrap"), "{}", err);
+    assert!(!marker.exists(), "the command ran");
+}
@@ -25,13 +25,12 @@ tasks:
         ..Default::default()
     };
     let graph = loader.build_graph(config).expect("Failed to build graph");
-    // Since root_script was provided, tasks are registered with the key: "<root_script> <task_name>"
-    let expected_key = format!("{} {}", script_path.to_str().unwrap(), "test_task");
+    // Tasks from the root script are registered under their bare names.
     assert!(
-        graph.task_registry.contains_key(&expected_key),
-        "Expected task registry to contain key \"{}\"",
-        expected_key
+        graph.task_registry.contains_key("test_task"),
+        "Expected task registry to contain key \"test_task\""
     );
+    assert!(graph.task_registry.contains_key("default"));
 }
 
 #[test]
@@ -89,18 +88,100 @@ tasks:
     fs::write(&script2_path, script2_content).unwrap();
 
     let mut loader = ScriptLoader::new();
-    // Instead of using scripts_dirs (which is not implemented), we simulate loading one file.
-    // We set root_script to script1_path.
     let config = BodoConfig {
-        root_script: Some(script1_path.to_str().unwrap().to_string()),
+        scripts_dirs: Some(vec![scripts_dir.to_str().unwrap().to_string()]),
         ..Default::default()
     };
     let graph = loader
         .build_graph(config)
-        .expect("Failed to build graph from root_script");
-    let expected_key = format!("{} {}", script1_path.to_str().unwrap(), "task1");
+        .expect("Failed to build graph from scripts_dirs");
+    // Each file registers its tasks under a namespace derived from its path.
     assert!(
-        graph.task_registry.contains_key(&expected_key),
+        graph.task_registry.contains_key("script1 task1"),
         "Task1 not found in task registry"
     );
+    assert!(graph.task_registry.contains_key("script1"));
+    assert!(graph.task_registry.contains_key("script2 task2"));
+    assert!(graph.task_registry.contains_key("script2"));
+}
+
+#[test]
+fn test_load_nested_scripts_dirs() {
+    let temp_dir = tempdir().unwrap();
+    let scripts_dir = temp_dir.path().join("scripts");
+    fs::create_dir_all(scripts_dir.join("build")).unwrap();
+    fs::create_dir_all(scripts_dir.join("deploy")).unwrap();
+
+    let root_path = scripts_dir.join("script.yaml");
+    fs::write(
+        &root_path,
+        r#"
+default_task:
+  command: echo "root"
+tasks:
+  hello:
+    command: echo "hello"
+"#,
+    )
+    .unwrap();
+    fs::write(
+        scripts_dir.join("build").join("script.yaml"),
+        r#"
+default_task:
+  command: cargo build
+tasks:
+  check:
+    command: cargo check
+"#,
+    )
+    .unwrap();
+    fs::write(
+        scripts_dir.join("deploy").join("script.yaml"),
+        r#"
+tasks:
+  prod:
+    command: echo "prod"
+"#,
+    )
+    .unwrap();
+
+    let mut loader = ScriptLoader::new();
+    let config = BodoConfig {
+        root_script: Some(root_path.to_str().unwrap().to_string()),
+        scripts_dirs: Some(vec![scripts_dir.to_str().unwrap().to_string()]),
+        ..Default::default()
+    };
+    let graph = loader.build_graph(config).expect("Failed to build graph");
+
+    // The root script is not loaded twice even though it lives inside scripts_dirs.
+    assert!(graph.task_registry.contains_key("default"));
+    assert!(graph.task_registry.contains_key("hello"));
+    assert!(graph.task_registry.contains_key("build"));
+    assert!(graph.task_registry.contains_key("deploy prod"));
+
+    let check_id = graph.task_registry["build check"];
+    if let bodo::graph::NodeKind::Task(task) = &graph.nodes[check_id as usize].kind {
+        assert_eq!(task.name, "check");
+        assert_eq!(task.script_id, "build");
+        assert_eq!(task.command.as_deref(), Some("cargo check"));
+    } else {
+        panic!("Expected a Task node");
+    }
+}
+
+#[test]
+fn test_load_scripts_dir_missing_is_skipped() {
+    let temp_dir = tempdir().unwrap();
+    let mut loader = ScriptLoader::new();
+    let config = BodoConfig {
+        scripts_dirs: Some(vec![temp_dir
+            .path()
+            .join("does-not-exist")
+            .to_str()
+            .unwrap()
+            .to_string()]),
+        ..Default::default()
+    };
+    let graph = loader.build_graph(config).expect("Failed to build graph");
+    assert!(graph.task_registry.is_empty());
 }
@@ -0,0 +1,132 @@
+use bodo::cli::{get_task_name, Args};
+use bodo::config::BodoConfig;
+use bodo::manager::GraphManager;
+use bodo::suggest::{did_you_mean, suggest};
+use bodo::BodoError;
+use std::fs;
+use tempfile::tempdir;
+
+#[test]
+fn test_suggest_ranks_close_names() {
+    let candidates = ["build", "built", "test", "deploy prod"];
+    assert_eq!(suggest("biuld", candidates), vec!["build"]);
+    assert_eq!(suggest("buil", candidates), vec!["build", "built"]);
+    assert_eq!(suggest("deploy prdo", candidates), vec!["deploy prod"]);
+    assert_eq!(suggest("TEST", candidates), vec!["test"]);
+    assert!(suggest("xyz", candidates).is_empty());
+    assert!(suggest("build", candidates).iter().all(|s| s != "build"));
+}
+
+#[test]
+fn test_did_you_mean_formatting() {
+    assert_eq!(did_you_mean(&[]), "");
+    assert_eq!(
+        did_you_mean(&["build".to_string()]),
+        " — did you mean 'build'?"
+    );
+    assert_eq!(
+        did_you_mean(&["a".to_string(), "b".to_string(), "c".to_string()]),
+        " — did you mean 'a', 'b' or 'c'?"
+    );
+}
+
+fn manager_with_scripts() -> (tempfile::TempDir, GraphManager) {
+    let temp_dir = tempdir().unwrap();
+    let scripts_dir = temp_dir.path().join("scripts");
+    fs::create_dir_all(scripts_dir.join("deploy")).unwrap();
+    fs::write(
+        scripts_dir.join("deploy").join("script.yaml"),
+        r#"
+tasks:
+  prod:
+    command: echo prod
+"#,
+    )
+    .unwrap();
+    fs::write(
+        scripts_dir.join("script.yaml"),
+        r#"
+tasks:
+  build:
+    command: echo build
+"#,
+    )
+    .unwrap();
+    let mut manager = GraphManager::new();
+    manager
+        .build_graph(BodoConfig {
+            scripts_dirs: Some(vec![scripts_dir.to_str().unwrap().to_string()]),
+            ..Default::default()
+        })
+        .unwrap();
+    (temp_dir, manager)
+}
+
+fn args(task: &str, subtask: Option<&str>) -> Args {
+    Args {
+        list: false,
+        watch: false,
+        auto_watch: false,
+        debug: false,
+        dry_run: false,
+        side_effects: false,
+        sandbox: false,
+        interactive: false,
+        help: false,
+        command: None,
+        graph: false,
+        format: None,
+        task: Some(task.to_string()),
+        subtask: subtask.map(String::from),
+        args: vec![],
+    }
+}
+
+#[test]
+fn test_unknown_task_suggests_registry_keys_and_namespaces() {
+    let (_temp_dir, manager) = manager_with_scripts();
+
+    let err = get_task_name(&args("biuld", None), &manager).unwrap_err();
+    assert!(matches!(err, BodoError::TaskNotFound(..)));
+    assert_eq!(
+        err.to_string(),
+        "task 'biuld' not found — did you mean 'build'?"
+    );
+
+    let err = get_task_name(&args("deplyo", None), &manager).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "task 'deplyo' not found — did you mean 'deploy'?"
+    );
+
+    let err = get_task_name(&args("deploy", Some("prdo")), &manager).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "task 'deploy prdo' not found — did you mean 'deploy prod'?"
+    );
+}
+
+#[test]
+fn test_misspelled_dependency_is_reported_with_suggestion() {
+    let config: BodoConfig = serde_yaml::from_str(
+        r#"
+tasks:
+  release:
+    command: echo release
+    pre_deps:
+      - task: biuld
+  build:
+    command: echo build
+"#,
+    )
+    .unwrap();
+    let mut manager = GraphManager::new();
+    let err = manager.build_graph(config).unwrap_err().to_string();
+    assert!(
+        err.ends_with(
+            "task 'biuld' referenced by 'release' does not exist — did you mean 'build'?"
+        ),
+        "Unexpected error: {}",
+        err
+    );
+}
@@ -0,0 +1,191 @@
+use bodo::arguments::{parse_task_arguments, task_help, wants_help};
+use bodo::config::{ArgumentType, BodoConfig, TaskArgument};
+use bodo::script_loader::ScriptLoader;
+use bodo::BodoError;
+use std::collections::HashMap;
+use std::fs;
+use tempfile::tempdir;
+
+const CONFIG: &str = r#"
+tasks:
+  commit:
+    description: Commit the staged changes
+    command: git commit -m "$MESSAGE"
+    args:
+      - name: MESSAGE
+        description: Commit message
+        short: m
+        required: true
+      - name: KIND
+        type: enum
+        choices: [feat, fix]
+        default: fix
+      - name: RETRIES
+        type: int
+        default: "0"
+      - name: NO_VERIFY
+        type: bool
+      - name: FILES
+        type: path
+        variadic: true
+"#;
+
+fn definitions() -> Vec<TaskArgument> {
+    let config: BodoConfig = serde_yaml::from_str(CONFIG).unwrap();
+    config.tasks["commit"].arguments.clone()
+}
+
+fn parse(args: &[&str]) -> Result<HashMap<String, String>, String> {
+    let args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+    parse_task_arguments(&definitions(), &args).map_err(|e| e.to_string())
+}
+
+#[test]
+fn test_arguments_by_name_short_alias_and_position() {
+    let env = parse(&["--message", "fix typo", "--no-verify", "--kind=feat"]).unwrap();
+    assert_eq!(env["MESSAGE"], "fix typo");
+    assert_eq!(env["KIND"], "feat");
+    assert_eq!(env["RETRIES"], "0");
+    assert_eq!(env["NO_VERIFY"], "true");
+    assert!(!env.contains_key("FILES"));
+
+    // Named arguments are taken out first; positional values fill the rest in order
+    let env = parse(&["-m", "msg", "feat", "3", "no", "a.txt", "-5"]).unwrap();
+    assert_eq!(env["MESSAGE"], "msg");
+    assert_eq!(env["KIND"], "feat");
+    assert_eq!(env["RETRIES"], "3");
+    assert_eq!(env["NO_VERIFY"], "false");
+    let cwd = std::env::current_dir().unwrap();
+    assert_eq!(
+        env["FILES"],
+        format!(
+            "{} {}",
+            cwd.join("a.txt").display(),
+            cwd.join("-5").display()
+        )
+    );
+
+    // After a bare `--`, flags are values
+    let env = parse(&["--kind", "feat", "--", "--message"]).unwrap();
+    assert_eq!(env["MESSAGE"], "--message");
+}
+
+#[test]
+fn test_invalid_arguments_are_reported() {
+    assert_eq!(
+        parse(&[]).unwrap_err(),
+        "Plugin error: Missing required argument: MESSAGE"
+    );
+    assert_eq!(
+        parse(&["msg", "--kind", "chore"]).unwrap_err(),
+        "Plugin error: Invalid value for argument KIND: 'chore' is not one of: feat, fix"
+    );
+    assert_eq!(
+        parse(&["msg", "--retries=many"]).unwrap_err(),
+        "Plugin error: Invalid value for argument RETRIES: 'many' is not a whole number"
+    );
+    assert_eq!(
+        parse(&["msg", "--mesage", "x"]).unwrap_err(),
+        "Plugin error: Unknown argument --mesage — did you mean '--message'?"
+    );
+    assert_eq!(
+        parse(&["-m", "a", "-m", "b"]).unwrap_err(),
+        "Plugin error: Argument --message is given more than once"
+    );
+    assert_eq!(
+        parse(&["msg", "--kind"]).unwrap_err(),
+        "Plugin error: Argument --kind needs a value"
+    );
+}
+
+#[test]
+fn test_tasks_without_arguments_accept_anything() {
+    let args: Vec<String> = ["--nocapture", "extra", "--", "-x"]
+        .iter()
+        .map(|arg| arg.to_string())
+        .collect();
+    assert_eq!(parse_task_arguments(&[], &args).unwrap(), HashMap::new());
+}
+
+#[test]
+fn test_task_help_is_built_from_definitions() {
+    let help = task_help("commit", Some("Commit the staged changes"), &definitions());
+    assert_eq!(
+        help,
+        "Commit the staged changes
+
+Usage: bodo commit -- <MESSAGE> [KIND] [RETRIES] [NO_VERIFY] [FILES]...
+
+Arguments:
+  -m, --message <string>  Commit message [required] [env: MESSAGE]
+      --kind <feat|fix>   [default: fix] [env: KIND]
+      --retries <int>     [default: 0] [env: RETRIES]
+      --no-verify         [env: NO_VERIFY]
+      --files <path>...   [env: FILES]
+"
+    );
+    assert_eq!(task_help("build", None, &[]), "Usage: bodo build\n");
+
+    assert!(wants_help(&["-m".into(), "x".into(), "--help".into()]));
+    assert!(!wants_help(&["--".into(), "--help".into()]));
+}
+
+#[test]
+fn test_bad_argument_definitions_fail_to_load() {
+    let load = |args: &str| {
+        let temp_dir = tempdir().unwrap();
+        let scripts_dir = temp_dir.path().join("scripts");
+        fs::create_dir_all(&scripts_dir).unwrap();
+        fs::write(
+            scripts_dir.join("script.yaml"),
+            format!("tasks:\n  build:\n    command: make\n    args:\n{}", args),
+        )
+        .unwrap();
+        ScriptLoader::new().build_graph(BodoConfig {
+            scripts_dirs: Some(vec![scripts_dir.to_str().unwrap().to_string()]),
+            ..Default::default()
+        })
+    };
+
+    let cases = [
+        (
+            "      - name: MODE\n        type: enum\n",
+            "Enum argument 'MODE' needs choices",
+        ),
+        (
+            "      - name: MODE\n        choices: [a]\n",
+            "Argument 'MODE' has choices but is not an enum",
+        ),
+        (
+            "      - name: FILES\n        variadic: true\n      - name: OUT\n",
+            "Variadic argument 'FILES' must be the last argument",
+        ),
+        (
+            "      - name: DRY_RUN\n      - name: dry-run\n",
+            "Arguments 'DRY_RUN' and 'dry-run' have the same flag",
+        ),
+        (
+            "      - name: JOBS\n        type: int\n        default: lots\n",
+            "Invalid default for argument 'JOBS': 'lots' is not a whole number",
+        ),
+        (
+            "      - name: HOST\n        short: h\n",
+            "Argument 'HOST' cannot use --help or -h, which show the task's help",
+        ),
+    ];
+    for (args, message) in cases {
+        match load(args) {
+            Err(BodoError::ConfigError(diagnostic)) => {
+                assert_eq!(diagnostic.message, message);
+                assert_eq!((diagnostic.line, diagnostic.column), (4, 5));
+            }
+            other => panic!("Expected a config error for {:?}, got {:?}", args, other),
+        }
+    }
+
+    let config: BodoConfig = serde_yaml::from_str(CONFIG).unwrap();
+    assert_eq!(
+        config.tasks["commit"].arguments[3].arg_type,
+        ArgumentType::Bool
+    );
+}
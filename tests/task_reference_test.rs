@@ -0,0 +1,152 @@
+use bodo::config::BodoConfig;
+use bodo::graph::EdgeKind;
+use bodo::script_loader::ScriptLoader;
+use bodo::Graph;
+use std::fs;
+use std::path::Path;
+use tempfile::tempdir;
+
+fn write(path: &Path, content: &str) {
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(path, content).unwrap();
+}
+
+fn load(scripts_dir: &Path) -> bodo::Result<Graph> {
+    ScriptLoader::new().build_graph(BodoConfig {
+        scripts_dirs: Some(vec![scripts_dir.to_str().unwrap().to_string()]),
+        ..Default::default()
+    })
+}
+
+fn write_ci_script(scripts_dir: &Path) {
+    write(
+        &scripts_dir.join("ci").join("test.yaml"),
+        r#"
+default_task:
+  command: echo all-tests
+tasks:
+  unit_tests:
+    command: echo unit
+"#,
+    );
+}
+
+#[test]
+fn test_relative_path_references() {
+    let temp_dir = tempdir().unwrap();
+    let scripts_dir = temp_dir.path().join("scripts");
+    write_ci_script(&scripts_dir);
+    write(
+        &scripts_dir.join("app").join("script.yaml"),
+        r#"
+tasks:
+  release:
+    command: echo release
+    pre_deps:
+      - task: ../ci/test.yaml
+      - task: ../ci/test.yaml/unit_tests
+"#,
+    );
+
+    let graph = load(&scripts_dir).unwrap();
+    let pre = graph.dependencies_of(graph.task_registry["app release"], EdgeKind::PreDep);
+    assert_eq!(
+        pre,
+        vec![
+            graph.task_registry["ci/test"],
+            graph.task_registry["ci/test unit_tests"]
+        ]
+    );
+}
+
+#[test]
+fn test_project_root_is_expanded() {
+    let temp_dir = tempdir().unwrap();
+    let scripts_dir = temp_dir.path().join("scripts");
+    write_ci_script(&scripts_dir);
+    write(
+        &scripts_dir.join("app").join("script.yaml"),
+        r#"
+tasks:
+  release:
+    command: echo release
+    pre_deps:
+      - task: $BODO_PROJECT_ROOT/scripts/ci/test.yaml/unit_tests
+"#,
+    );
+
+    std::env::set_var("BODO_PROJECT_ROOT", temp_dir.path());
+    let graph = load(&scripts_dir).unwrap();
+    let pre = graph.dependencies_of(graph.task_registry["app release"], EdgeKind::PreDep);
+    assert_eq!(pre, vec![graph.task_registry["ci/test unit_tests"]]);
+}
+
+#[test]
+fn test_unique_short_name_resolves_across_files() {
+    let temp_dir = tempdir().unwrap();
+    let scripts_dir = temp_dir.path().join("scripts");
+    write_ci_script(&scripts_dir);
+    write(
+        &scripts_dir.join("app").join("script.yaml"),
+        r#"
+tasks:
+  release:
+    command: echo release
+    pre_deps:
+      - task: unit_tests
+"#,
+    );
+
+    let graph = load(&scripts_dir).unwrap();
+    let pre = graph.dependencies_of(graph.task_registry["app release"], EdgeKind::PreDep);
+    assert_eq!(pre, vec![graph.task_registry["ci/test unit_tests"]]);
+}
+
+#[test]
+fn test_ambiguous_short_name_lists_candidates() {
+    let temp_dir = tempdir().unwrap();
+    let scripts_dir = temp_dir.path().join("scripts");
+    for ns in ["backend", "frontend"] {
+        write(
+            &scripts_dir.join(ns).join("script.yaml"),
+            r#"
+tasks:
+  lint:
+    command: echo lint
+"#,
+        );
+    }
+    write(
+        &scripts_dir.join("app").join("script.yaml"),
+        r#"
+tasks:
+  release:
+    command: echo release
+    pre_deps:
+      - task: lint
+"#,
+    );
+
+    let err = load(&scripts_dir).unwrap_err().to_string();
+    assert!(err.contains("ambiguous"), "Unexpected error: {}", err);
+    assert!(err.contains("backend lint, frontend lint"), "{}", err);
+}
+
+#[test]
+fn test_missing_script_reference_is_an_error() {
+    let temp_dir = tempdir().unwrap();
+    let scripts_dir = temp_dir.path().join("scripts");
+    write(
+        &scripts_dir.join("app").join("script.yaml"),
+        r#"
+tasks:
+  release:
+    command: echo release
+    pre_deps:
+      - task: ../missing.yaml
+"#,
+    );
+
+    let err = load(&scripts_dir).unwrap_err().to_string();
+    assert!(err.contains("missing.yaml"), "Unexpected error: {}", err);
+}
@@ -0,0 +1,230 @@
+use bodo::config::BodoConfig;
+use bodo::errors::BodoError;
+use bodo::manager::GraphManager;
+use bodo::plugin::PluginConfig;
+use bodo::plugins::{execution_plugin::ExecutionPlugin, timeout_plugin::TimeoutPlugin};
+use bodo::process::{ProcessManager, SpawnOptions};
+use std::fs;
+use std::thread;
+use std::time::{Duration, Instant};
+use tempfile::tempdir;
+
+#[test]
+fn test_process_is_killed_at_timeout() {
+    let mut pm = ProcessManager::new(true);
+    pm.spawn_with_options(
+        "slow",
+        "sleep 30",
+        SpawnOptions {
+            timeout: Some(Duration::from_millis(200)),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let start = Instant::now();
+    let err = pm.run_concurrently().unwrap_err();
+    assert!(start.elapsed() < Duration::from_secs(5));
+    match err {
+        BodoError::Timeout(name, elapsed) => {
+            assert_eq!(name, "slow");
+            assert!(elapsed >= Duration::from_millis(200));
+        }
+        other => panic!("Expected a timeout error, got {:?}", other),
+    }
+}
+
+#[cfg(unix)]
+#[test]
+fn test_timeout_kills_whole_process_tree() {
+    let temp_dir = tempdir().unwrap();
+    let marker = temp_dir.path().join("survived");
+
+    let mut pm = ProcessManager::new(true);
+    pm.grace_period = Duration::from_millis(200);
+    // The shell ignores SIGTERM, so only SIGKILL on the group stops the background job.
+    pm.spawn_with_options(
+        "tree",
+        &format!("trap '' TERM; (sleep 1; touch {}) & wait", marker.display()),
+        SpawnOptions {
+            timeout: Some(Duration::from_millis(200)),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let err = pm.run_concurrently().unwrap_err();
+    assert!(matches!(err, BodoError::Timeout(_, _)));
+
+    thread::sleep(Duration::from_millis(1500));
+    assert!(!marker.exists(), "Background process outlived the timeout");
+}
+
+#[cfg(unix)]
+#[test]
+fn test_timeout_kills_group_after_leader_exits() {
+    let temp_dir = tempdir().unwrap();
+    let marker = temp_dir.path().join("survived");
+
+    let mut pm = ProcessManager::new(true);
+    pm.grace_period = Duration::from_millis(500);
+    // The shell exits on SIGTERM right away; its background job ignores SIGTERM.
+    pm.spawn_with_options(
+        "leader",
+        &format!(
+            "(trap '' TERM; sleep 1; touch {}) & sleep 30",
+            marker.display()
+        ),
+        SpawnOptions {
+            timeout: Some(Duration::from_millis(200)),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let err = pm.run_concurrently().unwrap_err();
+    assert!(matches!(err, BodoError::Timeout(_, _)));
+
+    thread::sleep(Duration::from_millis(1500));
+    assert!(
+        !marker.exists(),
+        "Background process outlived its group leader"
+    );
+}
+
+/// Run a task that sleeps for 30s under `timeout`, returning its error and how long it ran.
+fn run_slow_task(timeout: &str) -> (BodoError, Duration) {
+    let temp_dir = tempdir().unwrap();
+    let script_path = temp_dir.path().join("script.yaml");
+    fs::write(
+        &script_path,
+        format!(
+            r#"
+tasks:
+  slow:
+    command: sleep 30
+    timeout: {}
+"#,
+            timeout
+        ),
+    )
+    .unwrap();
+
+    let mut manager = GraphManager::new();
+    manager
+        .build_graph(BodoConfig {
+            root_script: Some(script_path.to_str().unwrap().to_string()),
+            ..Default::default()
+        })
+        .unwrap();
+    manager.register_plugin(Box::new(TimeoutPlugin::new()));
+    manager.register_plugin(Box::new(ExecutionPlugin::new()));
+
+    let mut options = serde_json::Map::new();
+    options.insert("task".into(), serde_json::Value::String("slow".into()));
+    let start = Instant::now();
+    let err = manager
+        .run_plugins(Some(PluginConfig {
+            options: Some(options),
+            ..Default::default()
+        }))
+        .unwrap_err();
+    (err, start.elapsed())
+}
+
+#[test]
+fn test_task_timeout_from_config_is_enforced() {
+    let (err, elapsed) = run_slow_task("1s");
+    assert!(elapsed < Duration::from_secs(10));
+    assert!(
+        err.to_string()
+            .starts_with("Task 'slow' timed out after 1s"),
+        "Unexpected error: {}",
+        err
+    );
+}
+
+#[test]
+fn test_sub_second_task_timeout_is_not_rounded_down() {
+    let (err, elapsed) = run_slow_task("800ms");
+    match err {
+        BodoError::Timeout(name, after) => {
+            assert_eq!(name, "slow");
+            assert!(after >= Duration::from_millis(800), "{:?}", after);
+        }
+        other => panic!("Expected a timeout error, got {:?}", other),
+    }
+    assert!(elapsed < Duration::from_secs(10));
+}
+
+/// Whether `pid` is running: neither gone nor a zombie waiting to be reaped.
+#[cfg(target_os = "linux")]
+fn is_running(pid: &str) -> bool {
+    fs::read_to_string(format!("/proc/{}/stat", pid))
+        .ok()
+        .and_then(|stat| {
+            stat.rsplit_once(')')
+                .map(|(_, rest)| !rest.trim_start().starts_with('Z'))
+        })
+        .unwrap_or(false)
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn test_signal_to_bodo_stops_task_with_timeout() {
+    let project = tempdir().unwrap();
+    fs::create_dir_all(project.path().join("scripts")).unwrap();
+    fs::write(
+        project.path().join("scripts").join("script.yaml"),
+        r#"
+tasks:
+  slow:
+    command: sleep 1234 & echo $! > child.pid; wait
+    timeout: 10m
+"#,
+    )
+    .unwrap();
+
+    let mut bodo = std::process::Command::new(env!("CARGO_BIN_EXE_bodo"))
+        .arg("slow")
+        .current_dir(project.path())
+        .env("BODO_NO_HISTORY", "1")
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .spawn()
+        .unwrap();
+    let pid_file = project.path().join("child.pid");
+    let start = Instant::now();
+    let child = loop {
+        if let Some(pid) = fs::read_to_string(&pid_file)
+            .ok()
+            .filter(|pid| pid.ends_with('\n'))
+        {
+            break pid.trim().to_string();
+        }
+        assert!(
+            start.elapsed() < Duration::from_secs(10),
+            "task never started"
+        );
+        thread::sleep(Duration::from_millis(20));
+    };
+    assert!(is_running(&child));
+
+    // SAFETY: kill(2) has no memory-safety preconditions.
+    unsafe {
+        libc::kill(bodo.id() as libc::pid_t, libc::SIGTERM);
+    }
+    let start = Instant::now();
+    let status = loop {
+        if let Some(status) = bodo.try_wait().unwrap() {
+            break status;
+        }
+        if start.elapsed() > Duration::from_secs(10) {
+            bodo.kill().unwrap();
+            panic!("bodo kept running after SIGTERM");
+        }
+        thread::sleep(Duration::from_millis(20));
+    };
+    assert!(!status.success());
+    assert!(!is_running(&child), "the task's child outlived bodo");
+}
@@ -0,0 +1,102 @@
+use bodo::plugin::SideEffect;
+use bodo::sandbox::{Sandbox, SandboxBackend};
+use bodo::trace::{parse_trace, read_traces, Strace};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+
+#[test]
+fn test_parse_trace_maps_syscalls_to_side_effects() {
+    let trace = r#"execve("/bin/sh", ["/bin/sh", "-c", "cat in.txt > out.txt"], 0x7ffd5a1c8e08 /* 4 vars */) = 0
+openat(AT_FDCWD, "/etc/ld.so.cache", O_RDONLY|O_CLOEXEC) = 3</etc/ld.so.cache>
+openat(AT_FDCWD, "/lib/x86_64-linux-gnu/libc.so.6", O_RDONLY|O_CLOEXEC) = 3</usr/lib/x86_64-linux-gnu/libc.so.6>
+openat(AT_FDCWD, "out.txt", O_WRONLY|O_CREAT|O_TRUNC, 0666) = 3</work/out.txt>
+execve("/usr/bin/cat", ["cat", "in.txt"], 0x5581e0b8a0a8 /* 4 vars */) = 0
+openat(AT_FDCWD, "in.txt", O_RDONLY) = 3</work/in.txt>
+openat(AT_FDCWD, "missing.txt", O_RDONLY) = -1 ENOENT (No such file or directory)
+openat(AT_FDCWD, ".", O_RDONLY|O_NONBLOCK|O_CLOEXEC|O_DIRECTORY) = 3</work>
+openat(AT_FDCWD, "/dev/null", O_WRONLY|O_CREAT|O_TRUNC, 0666) = 3</dev/null>
+--- SIGCHLD {si_signo=SIGCHLD, si_code=CLD_EXITED, si_pid=42, si_uid=0, si_status=0} ---
+mkdir("build", 0777) = 0
+chdir("build") = 0
+unlinkat(AT_FDCWD, "../old.log", 0) = 0
+mkdirat(3</work/build>, "cache", 0777) = 0
+renameat2(AT_FDCWD, "a.tmp", AT_FDCWD, "a.bin", RENAME_NOREPLACE) = 0
+creat("new\x20file\n", 0644) = 4</work/build/new file\n>
+connect(3, {sa_family=AF_UNIX, sun_path="/var/run/nscd/socket"}, 110) = -1 ENOENT (No such file or directory)
+connect(3, {sa_family=AF_INET, sin_port=htons(443), sin_addr=inet_addr("93.184.215.14")}, 16) = -1 EINPROGRESS (Operation now in progress)
+connect(4, {sa_family=AF_INET6, sin6_port=htons(8080), sin6_flowinfo=htonl(0), inet_pton(AF_INET6, "::1", &sin6_addr), sin6_scope_id=0}, 28) = 0
+"#;
+    let effects = parse_trace(trace, Path::new("/work"));
+    let path = PathBuf::from;
+    assert_eq!(
+        effects,
+        vec![
+            SideEffect::ProcessSpawn("/bin/sh -c cat in.txt > out.txt".to_string()),
+            SideEffect::FileWrite(path("/work/out.txt")),
+            SideEffect::ProcessSpawn("cat in.txt".to_string()),
+            SideEffect::FileRead(path("/work/in.txt")),
+            SideEffect::DirectoryCreate(path("/work/build")),
+            SideEffect::FileDelete(path("/work/old.log")),
+            SideEffect::DirectoryCreate(path("/work/build/cache")),
+            SideEffect::FileDelete(path("/work/build/a.tmp")),
+            SideEffect::FileWrite(path("/work/build/a.bin")),
+            SideEffect::FileWrite(path("/work/build/new file\n")),
+            SideEffect::NetworkRequest("93.184.215.14:443".to_string()),
+            SideEffect::NetworkRequest("[::1]:8080".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn test_read_traces_merges_and_removes_process_files() {
+    let dir = TempDir::new().unwrap();
+    let output = dir.path().join("trace");
+    assert_eq!(read_traces(&output, Path::new("/work")), None);
+
+    fs::write(
+        dir.path().join("trace.101"),
+        "openat(AT_FDCWD, \"a.txt\", O_RDONLY) = 3\n",
+    )
+    .unwrap();
+    fs::write(
+        dir.path().join("trace.102"),
+        "openat(AT_FDCWD, \"a.txt\", O_RDONLY) = 3\nunlink(\"b.txt\") = 0\n",
+    )
+    .unwrap();
+    fs::write(dir.path().join("unrelated.txt"), "").unwrap();
+
+    assert_eq!(
+        read_traces(&output, Path::new("/work")),
+        Some(vec![
+            SideEffect::FileRead(PathBuf::from("/work/a.txt")),
+            SideEffect::FileDelete(PathBuf::from("/work/b.txt")),
+        ])
+    );
+    assert!(!dir.path().join("trace.101").exists());
+    assert!(dir.path().join("unrelated.txt").exists());
+}
+
+#[test]
+fn test_sandbox_reports_traced_reads() {
+    // Only meaningful where strace can trace processes inside bwrap
+    if Strace::detect().is_none() || SandboxBackend::detect() != Some(SandboxBackend::Bwrap) {
+        println!("strace or bwrap is not available, skipping");
+        return;
+    }
+    let project = TempDir::new().unwrap();
+    let wd = project.path();
+    fs::write(wd.join("c.txt"), "c\n").unwrap();
+
+    let side_effects = Sandbox::new()
+        .unwrap()
+        .execute_and_analyze("cat c.txt > copy.txt", wd, &HashMap::new())
+        .unwrap();
+    assert!(
+        side_effects.contains(&SideEffect::FileRead(wd.join("c.txt"))),
+        "{:?}",
+        side_effects
+    );
+    assert!(side_effects.contains(&SideEffect::FileWrite(wd.join("copy.txt"))));
+}
@@ -0,0 +1,122 @@
+use bodo::config::BodoConfig;
+use bodo::manager::GraphManager;
+use bodo::plugin::{Plugin, PluginConfig};
+use bodo::plugins::watch_plugin::{Debouncer, TaskRunner, WatchPlugin};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::Ordering;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+#[test]
+fn test_debouncer_fires_once_after_quiet_window() {
+    let window = Duration::from_millis(100);
+    let mut debouncer = Debouncer::new(window);
+    let start = Instant::now();
+    assert!(!debouncer.ready(start));
+    assert_eq!(debouncer.time_until_ready(start), None);
+
+    debouncer.add(vec![PathBuf::from("b.rs")], start);
+    debouncer.add(
+        vec![PathBuf::from("a.rs")],
+        start + Duration::from_millis(60),
+    );
+    // The second change restarted the window.
+    assert!(!debouncer.ready(start + Duration::from_millis(120)));
+    assert_eq!(
+        debouncer.time_until_ready(start + Duration::from_millis(120)),
+        Some(Duration::from_millis(40))
+    );
+    assert!(debouncer.ready(start + Duration::from_millis(160)));
+
+    assert_eq!(
+        debouncer.take(),
+        vec![PathBuf::from("a.rs"), PathBuf::from("b.rs")]
+    );
+    assert!(!debouncer.ready(start + Duration::from_secs(1)));
+}
+
+#[test]
+fn test_debouncer_coalesces_duplicate_paths() {
+    let mut debouncer = Debouncer::new(Duration::ZERO);
+    let now = Instant::now();
+    debouncer.add(vec![PathBuf::from("a.rs"), PathBuf::from("a.rs")], now);
+    debouncer.add(vec![PathBuf::from("a.rs")], now);
+    debouncer.add(Vec::new(), now);
+    assert_eq!(debouncer.take(), vec![PathBuf::from("a.rs")]);
+}
+
+#[test]
+fn test_burst_of_changes_triggers_one_run_with_all_files() {
+    let temp_dir = tempfile::tempdir_in(env!("CARGO_MANIFEST_DIR")).unwrap();
+    let rel_dir = temp_dir.path().file_name().unwrap().to_str().unwrap();
+    let config: BodoConfig = serde_yaml::from_str(&format!(
+        r#"
+tasks:
+  build:
+    command: echo build
+    watch:
+      patterns:
+        - "{}/**/*.txt"
+      debounce_ms: 300
+"#,
+        rel_dir
+    ))
+    .unwrap();
+    let mut manager = GraphManager::new();
+    manager.build_graph(config).unwrap();
+
+    let runs: Arc<Mutex<Vec<Vec<PathBuf>>>> = Arc::new(Mutex::new(Vec::new()));
+    let runner: TaskRunner = {
+        let runs = runs.clone();
+        Arc::new(move |_, changed| {
+            runs.lock().unwrap().push(changed);
+            Ok(())
+        })
+    };
+    let mut plugin = WatchPlugin::new(true, false).with_runner(runner);
+    let mut options = serde_json::Map::new();
+    options.insert("task".into(), serde_json::Value::String("build".into()));
+    plugin
+        .on_init(&PluginConfig {
+            watch: true,
+            options: Some(options),
+            ..Default::default()
+        })
+        .unwrap();
+    plugin.on_graph_build(&mut manager.graph).unwrap();
+
+    let shutdown = plugin.shutdown_handle();
+    let mut graph = manager.graph.clone();
+    let watcher = thread::spawn(move || plugin.on_after_run(&mut graph));
+
+    thread::sleep(Duration::from_millis(300));
+    for name in ["one.txt", "two.txt", "three.txt"] {
+        fs::write(temp_dir.path().join(name), name).unwrap();
+        thread::sleep(Duration::from_millis(50));
+    }
+
+    let start = Instant::now();
+    while runs.lock().unwrap().len() < 2 {
+        assert!(start.elapsed() < Duration::from_secs(10), "No rerun");
+        thread::sleep(Duration::from_millis(20));
+    }
+    // Nothing else should follow the single coalesced run.
+    thread::sleep(Duration::from_millis(600));
+    shutdown.store(true, Ordering::SeqCst);
+    watcher.join().unwrap().unwrap();
+
+    let runs = runs.lock().unwrap();
+    assert_eq!(runs.len(), 2, "Unexpected runs: {:?}", runs);
+    assert!(runs[0].is_empty());
+    let base = PathBuf::from(rel_dir);
+    assert_eq!(
+        runs[1],
+        vec![
+            base.join("one.txt"),
+            base.join("three.txt"),
+            base.join("two.txt")
+        ]
+    );
+}
@@ -0,0 +1,220 @@
+use bodo::config::BodoConfig;
+use bodo::errors::BodoError;
+use bodo::manager::GraphManager;
+use bodo::plugin::{Plugin, PluginConfig};
+use bodo::plugins::watch_plugin::{TaskRunner, WatchPlugin};
+use std::fs;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+fn watched_config(watch_dir: &str) -> BodoConfig {
+    let yaml = format!(
+        r#"
+tasks:
+  serve:
+    command: echo serve
+    pre_deps:
+      - task: assets
+    watch:
+      patterns:
+        - "{dir}/src/**/*.txt"
+      debounce_ms: 100
+  assets:
+    command: echo assets
+    watch:
+      patterns:
+        - "{dir}/assets/**/*.txt"
+  unrelated:
+    command: echo unrelated
+    watch:
+      patterns:
+        - "{dir}/other/**/*.txt"
+"#,
+        dir = watch_dir
+    );
+    serde_yaml::from_str(&yaml).unwrap()
+}
+
+fn init_watch(plugin: &mut WatchPlugin, manager: &mut GraphManager, task: &str) {
+    let mut options = serde_json::Map::new();
+    options.insert("task".into(), serde_json::Value::String(task.into()));
+    plugin
+        .on_init(&PluginConfig {
+            watch: true,
+            options: Some(options),
+            ..Default::default()
+        })
+        .unwrap();
+    plugin.on_graph_build(&mut manager.graph).unwrap();
+}
+
+fn wait_for(what: &str, condition: impl Fn() -> bool) {
+    let start = Instant::now();
+    while !condition() {
+        assert!(
+            start.elapsed() < Duration::from_secs(10),
+            "Timed out waiting for {}",
+            what
+        );
+        thread::sleep(Duration::from_millis(20));
+    }
+}
+
+#[test]
+fn test_only_requested_task_and_its_deps_are_watched() {
+    let mut manager = GraphManager::new();
+    manager.build_graph(watched_config("watched")).unwrap();
+
+    let runner: TaskRunner = Arc::new(|_, _| Ok(()));
+    let mut plugin = WatchPlugin::new(true, false).with_runner(runner);
+    init_watch(&mut plugin, &mut manager, "serve");
+
+    assert_eq!(plugin.get_watch_entry_count(), 2);
+    let serve = &manager.graph.nodes[manager.graph.task_registry["serve"] as usize];
+    assert_eq!(serve.metadata["watch_managed"], "true");
+}
+
+#[test]
+fn test_change_kills_previous_run_and_restarts() {
+    let temp_dir = tempfile::tempdir_in(env!("CARGO_MANIFEST_DIR")).unwrap();
+    let rel_dir = temp_dir.path().file_name().unwrap().to_str().unwrap();
+    fs::create_dir_all(temp_dir.path().join("src")).unwrap();
+
+    let mut manager = GraphManager::new();
+    manager.build_graph(watched_config(rel_dir)).unwrap();
+
+    // Each run behaves like a dev server: it only ends when cancelled.
+    let log = Arc::new(Mutex::new(Vec::new()));
+    let runner: TaskRunner = {
+        let log = log.clone();
+        Arc::new(move |cancel: Arc<AtomicBool>, _| {
+            let run = {
+                let mut log = log.lock().unwrap();
+                let run = log
+                    .iter()
+                    .filter(|e: &&String| e.starts_with("start"))
+                    .count()
+                    + 1;
+                log.push(format!("start {}", run));
+                run
+            };
+            while !cancel.load(Ordering::SeqCst) {
+                thread::sleep(Duration::from_millis(10));
+            }
+            log.lock().unwrap().push(format!("stop {}", run));
+            Ok(())
+        })
+    };
+
+    let mut plugin = WatchPlugin::new(true, false).with_runner(runner);
+    init_watch(&mut plugin, &mut manager, "serve");
+    let shutdown = plugin.shutdown_handle();
+    let mut graph = manager.graph.clone();
+    let watcher = thread::spawn(move || plugin.on_after_run(&mut graph));
+
+    let started = |n: usize| {
+        let log = log.clone();
+        move || log.lock().unwrap().contains(&format!("start {}", n))
+    };
+    wait_for("first run", started(1));
+    // Give the watcher a moment to start before changing anything.
+    thread::sleep(Duration::from_millis(300));
+    fs::write(Path::new(temp_dir.path()).join("src").join("main.txt"), "x").unwrap();
+    wait_for("restart", started(2));
+
+    shutdown.store(true, Ordering::SeqCst);
+    watcher.join().unwrap().unwrap();
+
+    let log = log.lock().unwrap();
+    assert_eq!(*log, vec!["start 1", "stop 1", "start 2", "stop 2"]);
+}
+
+#[test]
+fn test_cancel_flag_kills_running_task() {
+    let config: BodoConfig = serde_yaml::from_str(
+        r#"
+tasks:
+  server:
+    command: sleep 30
+"#,
+    )
+    .unwrap();
+    let mut manager = GraphManager::new();
+    manager.build_graph(config).unwrap();
+    let cancel = Arc::new(AtomicBool::new(false));
+    manager.register_plugin(Box::new(
+        bodo::plugins::execution_plugin::ExecutionPlugin::new().with_cancel_flag(cancel.clone()),
+    ));
+
+    let start = Instant::now();
+    let run = thread::spawn(move || {
+        let mut options = serde_json::Map::new();
+        options.insert("task".into(), serde_json::Value::String("server".into()));
+        manager.run_plugins(Some(PluginConfig {
+            options: Some(options),
+            ..Default::default()
+        }))
+    });
+    thread::sleep(Duration::from_millis(200));
+    cancel.store(true, Ordering::SeqCst);
+
+    assert!(run.join().unwrap().is_err());
+    assert!(start.elapsed() < Duration::from_secs(5));
+}
+
+#[test]
+fn test_failed_run_stops_watch_with_its_error() {
+    let mut manager = GraphManager::new();
+    manager.build_graph(watched_config("watched")).unwrap();
+
+    let runner: TaskRunner = Arc::new(|_, _| Err(BodoError::PluginError("boom".into())));
+    let mut plugin = WatchPlugin::new(true, true).with_runner(runner);
+    init_watch(&mut plugin, &mut manager, "serve");
+
+    let err = plugin.on_after_run(&mut manager.graph).unwrap_err();
+    assert_eq!(err.to_string(), "Plugin error: boom");
+}
+
+#[test]
+fn test_watch_exits_with_failure_when_run_fails() {
+    let project = tempfile::tempdir().unwrap();
+    fs::create_dir_all(project.path().join("scripts")).unwrap();
+    fs::create_dir_all(project.path().join("src")).unwrap();
+    fs::write(
+        project.path().join("scripts").join("script.yaml"),
+        r#"
+tasks:
+  fail:
+    command: exit 3
+    watch:
+      patterns:
+        - "src/**/*.txt"
+"#,
+    )
+    .unwrap();
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_bodo"))
+        .args(["--watch", "fail"])
+        .current_dir(project.path())
+        .env("BODO_NO_HISTORY", "1")
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .unwrap();
+    let start = Instant::now();
+    let status = loop {
+        if let Some(status) = child.try_wait().unwrap() {
+            break status;
+        }
+        if start.elapsed() > Duration::from_secs(10) {
+            child.kill().unwrap();
+            panic!("bodo --watch kept running after the task failed");
+        }
+        thread::sleep(Duration::from_millis(20));
+    };
+    assert!(!status.success());
+}